@@ -0,0 +1,42 @@
+-- 008_add_watched_channels.sql
+-- Follow mode: let a user watch Twitch channels they don't own and receive their
+-- go-live / offline / title / category notifications in their own integrations.
+--
+-- `watched_channels` stores one row per (user, broadcaster). Broadcaster metadata is
+-- resolved through the Helix users endpoint when the channel is added.
+--
+-- `watched_channel_subscriptions` stores the app-token EventSub subscriptions created
+-- for watched broadcasters. They are keyed by broadcaster (not by user) so a single
+-- subscription serves every user watching that channel.
+
+CREATE TABLE IF NOT EXISTS watched_channels (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    broadcaster_id TEXT NOT NULL,
+    broadcaster_login TEXT NOT NULL,
+    broadcaster_display_name TEXT NOT NULL,
+    broadcaster_profile_image_url TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, broadcaster_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_watched_channels_user_id ON watched_channels(user_id);
+CREATE INDEX IF NOT EXISTS idx_watched_channels_broadcaster_id ON watched_channels(broadcaster_id);
+
+CREATE TABLE IF NOT EXISTS watched_channel_subscriptions (
+    id TEXT PRIMARY KEY,
+    twitch_subscription_id TEXT NOT NULL UNIQUE,
+    broadcaster_id TEXT NOT NULL,
+    subscription_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_watched_channel_subscriptions_broadcaster_id
+    ON watched_channel_subscriptions(broadcaster_id);
+
+-- Queued retries must link to the right channel: for watched channels the stream URL
+-- differs from the owner's own channel, so store it with the task.
+ALTER TABLE notification_queue ADD COLUMN stream_url TEXT;
@@ -0,0 +1,17 @@
+-- One-time codes for linking a Telegram chat from inside the chat itself.
+--
+-- A user (the owner or a grantee with manage rights) generates a code in the web UI
+-- and sends `/link <code>` in the target chat. The bot verifies that the sender is a
+-- chat administrator, consumes the code and creates the Telegram integration for
+-- `user_id`. Codes are short-lived and deleted once used.
+
+CREATE TABLE IF NOT EXISTS telegram_link_codes (
+    code TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_by TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_telegram_link_codes_user_id ON telegram_link_codes(user_id);
+CREATE INDEX IF NOT EXISTS idx_telegram_link_codes_expires_at ON telegram_link_codes(expires_at);
@@ -0,0 +1,19 @@
+-- Chats the bot has been added to, discovered from Telegram `my_chat_member` updates
+-- so the UI can offer a chat picker instead of asking for a raw chat id. Rows are
+-- visible to the Telegram user who added the bot (`added_by_telegram_user_id`) and
+-- removed when the bot leaves the chat.
+CREATE TABLE IF NOT EXISTS telegram_pending_chats (
+    chat_id TEXT PRIMARY KEY,
+    chat_title TEXT,
+    chat_type TEXT NOT NULL,
+    added_by_telegram_user_id TEXT,
+    bot_status TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_telegram_pending_chats_added_by ON telegram_pending_chats(added_by_telegram_user_id);
+
+-- Why an integration was disabled automatically (e.g. `bot_removed`). Cleared when
+-- the integration is re-enabled.
+ALTER TABLE telegram_integrations ADD COLUMN disabled_reason TEXT;
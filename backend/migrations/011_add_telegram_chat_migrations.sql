@@ -0,0 +1,13 @@
+-- Audit trail of Telegram group -> supergroup migrations.
+-- When a group is upgraded Telegram assigns it a new chat id and rejects sends to the
+-- old one with `migrate_to_chat_id`. Integrations are moved to the new id automatically;
+-- each move is recorded here.
+CREATE TABLE IF NOT EXISTS telegram_chat_migrations (
+    id TEXT PRIMARY KEY,
+    old_chat_id TEXT NOT NULL,
+    new_chat_id TEXT NOT NULL,
+    integrations_updated INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_telegram_chat_migrations_old_chat_id ON telegram_chat_migrations(old_chat_id);
@@ -0,0 +1,51 @@
+-- Forum topics: a Telegram integration may target a specific topic (`message_thread_id`)
+-- of a forum supergroup instead of "General".
+--
+-- UNIQUE(user_id, telegram_chat_id) is relaxed to one integration per (user, chat, topic).
+-- SQLite cannot drop a table constraint, so the table is rebuilt.
+
+CREATE TABLE telegram_integrations_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    telegram_chat_id TEXT NOT NULL,
+    telegram_chat_title TEXT,
+    telegram_chat_type TEXT NOT NULL DEFAULT 'private', -- 'private', 'group', 'supergroup', 'channel'
+    message_thread_id INTEGER, -- forum topic id; NULL = no topic / "General"
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Per-integration notification settings
+    notify_stream_online BOOLEAN NOT NULL DEFAULT TRUE,
+    notify_stream_offline BOOLEAN NOT NULL DEFAULT FALSE,
+    notify_title_change BOOLEAN NOT NULL DEFAULT TRUE,
+    notify_category_change BOOLEAN NOT NULL DEFAULT TRUE,
+    notify_reward_redemption BOOLEAN NOT NULL DEFAULT FALSE,
+
+    last_telegram_message_id INTEGER,
+    disabled_reason TEXT,
+
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO telegram_integrations_new (
+    id, user_id, telegram_chat_id, telegram_chat_title, telegram_chat_type, is_enabled,
+    notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change,
+    notify_reward_redemption, last_telegram_message_id, disabled_reason, created_at, updated_at
+)
+SELECT
+    id, user_id, telegram_chat_id, telegram_chat_title, telegram_chat_type, is_enabled,
+    notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change,
+    notify_reward_redemption, last_telegram_message_id, disabled_reason, created_at, updated_at
+FROM telegram_integrations;
+
+DROP TABLE telegram_integrations;
+ALTER TABLE telegram_integrations_new RENAME TO telegram_integrations;
+
+CREATE INDEX IF NOT EXISTS idx_telegram_integrations_user_id ON telegram_integrations(user_id);
+CREATE INDEX IF NOT EXISTS idx_telegram_integrations_chat_id ON telegram_integrations(telegram_chat_id);
+-- NULL topics are compared as 0 so a chat can't be linked twice without a topic
+CREATE UNIQUE INDEX IF NOT EXISTS idx_telegram_integrations_user_chat_thread
+    ON telegram_integrations(user_id, telegram_chat_id, COALESCE(message_thread_id, 0));
+
+-- Queued retries must land in the same topic as the original send
+ALTER TABLE notification_queue ADD COLUMN message_thread_id INTEGER;
@@ -0,0 +1,11 @@
+-- Per-event mention for Discord integrations.
+-- Each column holds NULL (no mention), 'here' (@here) or a guild role id.
+
+ALTER TABLE discord_integrations ADD COLUMN mention_stream_online TEXT;
+ALTER TABLE discord_integrations ADD COLUMN mention_stream_offline TEXT;
+ALTER TABLE discord_integrations ADD COLUMN mention_title_change TEXT;
+ALTER TABLE discord_integrations ADD COLUMN mention_category_change TEXT;
+ALTER TABLE discord_integrations ADD COLUMN mention_reward_redemption TEXT;
+
+-- Mention resolved at send time, kept for retries
+ALTER TABLE notification_queue ADD COLUMN discord_mention TEXT;
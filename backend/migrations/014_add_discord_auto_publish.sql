@@ -0,0 +1,6 @@
+-- Auto-publish (crosspost) notifications posted to Discord announcement channels
+
+ALTER TABLE discord_integrations ADD COLUMN auto_publish BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Kept for retries
+ALTER TABLE notification_queue ADD COLUMN discord_auto_publish BOOLEAN NOT NULL DEFAULT FALSE;
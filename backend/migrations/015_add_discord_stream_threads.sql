@@ -0,0 +1,18 @@
+-- Discord thread per stream: the stream.online message gets a thread and later
+-- title/category/reward notifications of that broadcast are posted into it.
+
+ALTER TABLE discord_integrations ADD COLUMN stream_thread_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Current broadcast per (integration, broadcaster); replaced on the next stream.online
+CREATE TABLE discord_stream_threads (
+    integration_id TEXT NOT NULL REFERENCES discord_integrations(id) ON DELETE CASCADE,
+    broadcaster_login TEXT NOT NULL, -- owner or watched channel the stream belongs to
+    channel_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,        -- stream.online message
+    thread_id TEXT,                  -- NULL if the thread could not be created
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (integration_id, broadcaster_login)
+);
+
+-- Kept for retries
+ALTER TABLE notification_queue ADD COLUMN discord_thread_id TEXT;
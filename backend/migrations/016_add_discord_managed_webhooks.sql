@@ -0,0 +1,5 @@
+-- Webhooks created by the backend (as opposed to URLs pasted by the user).
+-- Managed webhooks are recreated when Discord reports them unknown and deleted
+-- together with the integration.
+
+ALTER TABLE discord_integrations ADD COLUMN discord_webhook_managed BOOLEAN NOT NULL DEFAULT FALSE;
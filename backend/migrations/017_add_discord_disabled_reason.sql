@@ -0,0 +1,4 @@
+-- Why a Discord integration was disabled automatically (e.g. its channel or
+-- webhook was deleted); NULL if enabled or disabled by the user.
+
+ALTER TABLE discord_integrations ADD COLUMN disabled_reason TEXT;
@@ -0,0 +1,6 @@
+-- Consecutive permanent delivery failures (chat or channel gone, missing access).
+-- Every successful send resets the streak; the integration is disabled once it
+-- reaches NOTIFICATION_AUTO_DISABLE_AFTER_FAILURES.
+
+ALTER TABLE telegram_integrations ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE discord_integrations ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
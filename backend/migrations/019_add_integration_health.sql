@@ -0,0 +1,8 @@
+-- Result of the daily integration health probe: "ok", "unreachable" (chat or channel gone,
+-- bot removed), "missing_permissions" (bot may not post) or "error" (probe was rejected).
+-- NULL until the integration has been probed once.
+
+ALTER TABLE telegram_integrations ADD COLUMN health_status TEXT;
+ALTER TABLE telegram_integrations ADD COLUMN health_checked_at TIMESTAMP;
+ALTER TABLE discord_integrations ADD COLUMN health_status TEXT;
+ALTER TABLE discord_integrations ADD COLUMN health_checked_at TIMESTAMP;
@@ -0,0 +1,25 @@
+-- Role-based settings shares replace the single `can_manage` flag:
+--   viewer              read-only access
+--   template_editor     can also edit message templates and notification settings
+--   integration_manager can also create, edit and delete integrations
+--   manager             full access, including managing the owner's shares
+-- Existing shares keep their rights: `can_manage` becomes `manager`, the rest `viewer`.
+
+ALTER TABLE settings_shares ADD COLUMN role TEXT NOT NULL DEFAULT 'viewer';
+UPDATE settings_shares SET role = 'manager' WHERE can_manage;
+ALTER TABLE settings_shares DROP COLUMN can_manage;
+
+-- Append-only record of configuration changes: who (actor) changed what on whose
+-- (owner) account. `details` holds a JSON description of the change.
+CREATE TABLE IF NOT EXISTS audit_events (
+    id TEXT PRIMARY KEY,
+    actor_user_id TEXT NOT NULL,
+    owner_user_id TEXT NOT NULL,
+    entity TEXT NOT NULL,
+    entity_id TEXT,
+    action TEXT NOT NULL,
+    details TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_owner_created ON audit_events(owner_user_id, created_at);
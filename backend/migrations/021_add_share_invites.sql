@@ -0,0 +1,19 @@
+-- Invite links for settings shares.
+--
+-- An owner (or a grantee with the manager role) creates an invite carrying a share
+-- role. The invitee opens the link, which starts the Twitch login with the invite in
+-- the OAuth state; after the callback the invite is consumed and the share created.
+-- Only a SHA-256 hash of the token is stored. Invites are single-use and expire.
+
+CREATE TABLE IF NOT EXISTS share_invites (
+    id TEXT PRIMARY KEY,
+    token_hash TEXT NOT NULL UNIQUE,
+    owner_user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_by TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_invites_owner_user_id ON share_invites(owner_user_id);
+CREATE INDEX IF NOT EXISTS idx_share_invites_expires_at ON share_invites(expires_at);
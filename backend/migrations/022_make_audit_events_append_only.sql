@@ -0,0 +1,17 @@
+-- Audit events are append-only. Rows can't be changed, and can only be deleted
+-- once the owner's account is gone (account deletion removes its audit trail).
+
+CREATE TRIGGER IF NOT EXISTS audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_events_no_delete
+BEFORE DELETE ON audit_events
+WHEN EXISTS (SELECT 1 FROM users WHERE id = OLD.owner_user_id)
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_owner_entity ON audit_events(owner_user_id, entity);
@@ -0,0 +1,20 @@
+-- Personal API tokens for scripts and dashboards.
+--
+-- A user creates a named token with a set of scopes (space-separated, e.g.
+-- `read:notifications manage:integrations`). The token is shown once; only its SHA-256
+-- hash is stored, plus a short prefix so the user can tell tokens apart. Revoking a
+-- token deletes the row.
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    expires_at DATETIME,
+    last_used_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
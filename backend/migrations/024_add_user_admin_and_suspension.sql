@@ -0,0 +1,9 @@
+-- Instance administration. `is_admin` follows `ADMIN_TWITCH_IDS`, which is
+-- authoritative: on startup and login the listed Twitch ids are granted the role
+-- and everyone else loses it. Administrators can suspend an account:
+-- `suspended_at` is set while the account is suspended, and its notifications are
+-- not dispatched.
+
+ALTER TABLE users ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN suspended_at DATETIME;
+ALTER TABLE users ADD COLUMN suspended_reason TEXT;
@@ -0,0 +1,4 @@
+-- Discord channel type (0 text, 5 announcement), recorded when an integration is created
+-- or edited so auto-publish needs no channel lookup per send. NULL for older rows.
+
+ALTER TABLE discord_integrations ADD COLUMN discord_channel_type INTEGER;
@@ -10,6 +10,8 @@ pub struct Config {
     pub telegram: TelegramConfig,
     pub discord: DiscordConfig,
     pub jwt: JwtConfig,
+    pub encryption: EncryptionConfig,
+    pub admin: AdminConfig,
     pub rate_limit: RateLimitConfig,
     pub notification_retry: NotificationRetryConfig,
 }
@@ -45,6 +47,10 @@ pub struct TwitchConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct TelegramConfig {
     pub bot_token: Option<String>,
+    /// Whether to run the bot update dispatcher (long polling for commands and chat membership).
+    /// Disable when several instances share one bot token: only one may poll for updates.
+    /// Read from env var `TELEGRAM_BOT_COMMANDS_ENABLED`.
+    pub commands_enabled: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -52,6 +58,9 @@ pub struct DiscordConfig {
     pub bot_token: Option<String>,
     pub client_id: Option<String>,
     pub client_secret: Option<String>,
+    /// Application public key (hex) used to verify interaction requests.
+    /// Slash commands are disabled when unset. Read from env var `DISCORD_PUBLIC_KEY`.
+    pub public_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -60,6 +69,62 @@ pub struct JwtConfig {
     pub expiration_hours: i64,
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EncryptionConfig {
+    /// Keys for encrypting OAuth tokens at rest, newest first. The first key encrypts,
+    /// the rest only decrypt values written before a rotation.
+    /// Read from env var `TOKEN_ENCRYPTION_KEYS` as `<version>:<base64 32-byte key>,...`.
+    pub keys: Vec<EncryptionKey>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AdminConfig {
+    /// Twitch user ids of the instance administrators, applied on startup and login;
+    /// users not listed lose the role.
+    /// Read from env var `ADMIN_TWITCH_IDS` (comma-separated).
+    pub twitch_ids: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EncryptionKey {
+    pub version: u32,
+    pub bytes: Vec<u8>,
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("version", &self.version)
+            .field("bytes", &"<redacted>")
+            .finish()
+    }
+}
+
+impl EncryptionConfig {
+    fn parse_keys(raw: &str) -> Result<Vec<EncryptionKey>, ConfigError> {
+        use base64::Engine;
+
+        let invalid = || ConfigError::InvalidValue("TOKEN_ENCRYPTION_KEYS".to_string());
+        raw.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (version, key) = entry.split_once(':').ok_or_else(invalid)?;
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(key.trim())
+                    .map_err(|_| invalid())?;
+                if bytes.len() != 32 {
+                    return Err(invalid());
+                }
+                Ok(EncryptionKey {
+                    version: version.trim().parse().map_err(|_| invalid())?,
+                    bytes,
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct RateLimitConfig {
     /// Allowed requests per second (per IP) for auth endpoints (e.g. /api/auth/login)
@@ -93,6 +158,8 @@ pub struct NotificationRetryConfig {
     pub title_change_ttl_seconds: u64,
     pub category_change_ttl_seconds: u64,
     pub reward_redemption_ttl_seconds: u64,
+    /// Disable an integration after this many permanent failures in a row (0 = never).
+    pub auto_disable_after_failures: u32,
 }
 
 impl Config {
@@ -138,11 +205,20 @@ impl Config {
             },
             telegram: TelegramConfig {
                 bot_token: env::var("TELEGRAM_BOT_TOKEN").ok(),
+                commands_enabled: match env::var("TELEGRAM_BOT_COMMANDS_ENABLED") {
+                    Ok(v) => match v.to_lowercase().as_str() {
+                        "1" | "true" | "yes" => true,
+                        "0" | "false" | "no" => false,
+                        _ => true,
+                    },
+                    Err(_) => true,
+                },
             },
             discord: DiscordConfig {
                 bot_token: env::var("DISCORD_BOT_TOKEN").ok(),
                 client_id: env::var("DISCORD_CLIENT_ID").ok(),
                 client_secret: env::var("DISCORD_CLIENT_SECRET").ok(),
+                public_key: env::var("DISCORD_PUBLIC_KEY").ok(),
             },
             jwt: JwtConfig {
                 secret: env::var("JWT_SECRET")
@@ -152,6 +228,23 @@ impl Config {
                     .parse()
                     .unwrap_or(24),
             },
+            encryption: EncryptionConfig {
+                keys: match env::var("TOKEN_ENCRYPTION_KEYS") {
+                    Ok(raw) => EncryptionConfig::parse_keys(&raw)?,
+                    Err(_) => Vec::new(),
+                },
+            },
+            admin: AdminConfig {
+                twitch_ids: env::var("ADMIN_TWITCH_IDS")
+                    .map(|raw| {
+                        raw.split(',')
+                            .map(str::trim)
+                            .filter(|id| !id.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
             rate_limit: RateLimitConfig {
                 auth_per_second: env::var("RATE_LIMIT_AUTH_PER_SECOND")
                     .unwrap_or_else(|_| "3".to_string())
@@ -224,6 +317,10 @@ impl Config {
                 .unwrap_or_else(|_| "300".to_string())
                 .parse()
                 .unwrap_or(300u64),
+                auto_disable_after_failures: env::var("NOTIFICATION_AUTO_DISABLE_AFTER_FAILURES")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .unwrap_or(3u32),
             },
         })
     }
@@ -258,16 +355,22 @@ impl Default for Config {
                 client_secret: String::new(),
                 redirect_uri: "http://localhost:3000/auth/callback".to_string(),
             },
-            telegram: TelegramConfig { bot_token: None },
+            telegram: TelegramConfig {
+                bot_token: None,
+                commands_enabled: true,
+            },
             discord: DiscordConfig {
                 bot_token: None,
                 client_id: None,
                 client_secret: None,
+                public_key: None,
             },
             jwt: JwtConfig {
                 secret: String::new(),
                 expiration_hours: 24,
             },
+            encryption: EncryptionConfig::default(),
+            admin: AdminConfig::default(),
             rate_limit: RateLimitConfig {
                 auth_per_second: 3,
                 auth_burst: 10,
@@ -286,6 +389,7 @@ impl Default for Config {
                 title_change_ttl_seconds: 300,
                 category_change_ttl_seconds: 300,
                 reward_redemption_ttl_seconds: 300,
+                auto_disable_after_failures: 3,
             },
         }
     }
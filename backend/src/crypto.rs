@@ -0,0 +1,219 @@
+//! Encryption of secrets stored in the database (Twitch OAuth tokens).
+//!
+//! Values are sealed with AES-256-GCM and stored as `enc:v<version>:<base64(nonce || ciphertext)>`.
+//! The version names the key that sealed the value, so keys can be rotated: the first
+//! configured key encrypts, all configured keys decrypt, and `needs_reencryption` tells
+//! which stored values still use an older key (or are plaintext).
+//!
+//! The cipher is process-wide (like the translations in `i18n`) so repositories can apply
+//! it without threading the config through every call. Without configured keys values
+//! are stored as plaintext.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::config::EncryptionKey;
+use crate::error::{AppError, AppResult};
+
+const PREFIX: &str = "enc:v";
+
+static TOKEN_CIPHER: OnceLock<Option<TokenCipher>> = OnceLock::new();
+
+pub struct TokenCipher {
+    current_version: u32,
+    keys: HashMap<u32, LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl TokenCipher {
+    /// Build a cipher from the configured keys; the first key is used for encryption.
+    /// Returns `None` when no keys are configured.
+    pub fn new(keys: &[EncryptionKey]) -> anyhow::Result<Option<Self>> {
+        let Some(current) = keys.first() else {
+            return Ok(None);
+        };
+
+        let mut map = HashMap::new();
+        for key in keys {
+            let unbound = UnboundKey::new(&AES_256_GCM, &key.bytes)
+                .map_err(|_| anyhow::anyhow!("Encryption key v{} must be 32 bytes", key.version))?;
+            if map.insert(key.version, LessSafeKey::new(unbound)).is_some() {
+                anyhow::bail!("Encryption key version {} is configured twice", key.version);
+            }
+        }
+
+        Ok(Some(Self {
+            current_version: current.version,
+            keys: map,
+            rng: SystemRandom::new(),
+        }))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> AppResult<String> {
+        let key = &self.keys[&self.current_version];
+
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Failed to generate nonce")))?;
+
+        let mut sealed = plaintext.as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut sealed,
+        )
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Failed to encrypt value")))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&sealed);
+        Ok(format!(
+            "{}{}:{}",
+            PREFIX,
+            self.current_version,
+            base64::engine::general_purpose::STANDARD.encode(payload)
+        ))
+    }
+
+    /// Decrypt a stored value; plaintext values are returned unchanged
+    pub fn decrypt(&self, stored: &str) -> AppResult<String> {
+        let Some((version, payload)) = parse(stored)? else {
+            return Ok(stored.to_string());
+        };
+
+        let key = self.keys.get(&version).ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!(
+                "Value is encrypted with unknown key version {}",
+                version
+            ))
+        })?;
+
+        if payload.len() < NONCE_LEN {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Encrypted value is truncated"
+            )));
+        }
+        let (nonce, sealed) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalid nonce")))?;
+
+        let mut buffer = sealed.to_vec();
+        let plaintext = key
+            .open_in_place(nonce, Aad::empty(), &mut buffer)
+            .map_err(|_| {
+                AppError::Internal(anyhow::anyhow!(
+                    "Failed to decrypt value with key version {}",
+                    version
+                ))
+            })?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Decrypted value is not UTF-8")))
+    }
+
+    /// Whether a stored value is plaintext or sealed with an older key
+    pub fn needs_reencryption(&self, stored: &str) -> bool {
+        !stored.starts_with(&format!("{}{}:", PREFIX, self.current_version))
+    }
+}
+
+/// Split `enc:v<version>:<payload>` into version and decoded payload; `None` for plaintext
+fn parse(stored: &str) -> AppResult<Option<(u32, Vec<u8>)>> {
+    let Some(rest) = stored.strip_prefix(PREFIX) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::Internal(anyhow::anyhow!("Malformed encrypted value"));
+
+    let (version, payload) = rest.split_once(':').ok_or_else(invalid)?;
+    let version = version.parse().map_err(|_| invalid())?;
+    let payload = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|_| invalid())?;
+    Ok(Some((version, payload)))
+}
+
+/// Install the process-wide cipher. Called once at startup.
+pub fn init(keys: &[EncryptionKey]) -> anyhow::Result<()> {
+    let cipher = TokenCipher::new(keys)?;
+    match &cipher {
+        Some(c) => tracing::info!(
+            "Token encryption enabled (key version {})",
+            c.current_version
+        ),
+        None => {
+            tracing::warn!("TOKEN_ENCRYPTION_KEYS is not set; OAuth tokens are stored unencrypted")
+        }
+    }
+
+    TOKEN_CIPHER
+        .set(cipher)
+        .map_err(|_| anyhow::anyhow!("Token cipher already initialized"))
+}
+
+fn cipher() -> Option<&'static TokenCipher> {
+    TOKEN_CIPHER.get().and_then(Option::as_ref)
+}
+
+/// Encrypt a token for storage (unchanged when encryption is disabled)
+pub fn encrypt_token(plaintext: &str) -> AppResult<String> {
+    match cipher() {
+        Some(c) => c.encrypt(plaintext),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// Decrypt a stored token
+pub fn decrypt_token(stored: &str) -> AppResult<String> {
+    match cipher() {
+        Some(c) => c.decrypt(stored),
+        None if parse(stored)?.is_some() => Err(AppError::Internal(anyhow::anyhow!(
+            "Token is encrypted but TOKEN_ENCRYPTION_KEYS is not set"
+        ))),
+        None => Ok(stored.to_string()),
+    }
+}
+
+/// Whether a stored token should be rewritten with the current key
+pub fn token_needs_reencryption(stored: &str) -> bool {
+    cipher().is_some_and(|c| c.needs_reencryption(stored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(version: u32, byte: u8) -> EncryptionKey {
+        EncryptionKey {
+            version,
+            bytes: vec![byte; 32],
+        }
+    }
+
+    #[test]
+    fn round_trip_and_rotation() {
+        let old = TokenCipher::new(&[key(1, 1)]).unwrap().unwrap();
+        let sealed = old.encrypt("secret").unwrap();
+        assert!(sealed.starts_with("enc:v1:"));
+        assert_ne!(old.encrypt("secret").unwrap(), sealed, "nonce must differ");
+        assert_eq!(old.decrypt(&sealed).unwrap(), "secret");
+
+        // Plaintext passes through and is flagged for encryption
+        assert_eq!(old.decrypt("plain").unwrap(), "plain");
+        assert!(old.needs_reencryption("plain"));
+        assert!(!old.needs_reencryption(&sealed));
+
+        // After rotation the old key still decrypts, new values use v2
+        let rotated = TokenCipher::new(&[key(2, 2), key(1, 1)]).unwrap().unwrap();
+        assert_eq!(rotated.decrypt(&sealed).unwrap(), "secret");
+        assert!(rotated.needs_reencryption(&sealed));
+        assert!(rotated.encrypt("secret").unwrap().starts_with("enc:v2:"));
+
+        // A dropped key can't decrypt its values
+        let only_new = TokenCipher::new(&[key(2, 2)]).unwrap().unwrap();
+        assert!(only_new.decrypt(&sealed).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// API Token Models
+// ============================================================================
+
+/// Personal API token used by scripts instead of the browser session.
+/// Only the SHA-256 (hex) of the token is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub token_hash: String,
+    /// First characters of the token, shown so the user can tell tokens apart
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// `None` for tokens that don't expire
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// What an API token may be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiTokenScope {
+    /// Notification history, stats and the delivery queue (read-only)
+    #[serde(rename = "read:notifications")]
+    ReadNotifications,
+    /// Notification settings and message templates
+    #[serde(rename = "write:settings")]
+    WriteSettings,
+    /// Integrations, including their delivery queue
+    #[serde(rename = "manage:integrations")]
+    ManageIntegrations,
+}
+
+impl ApiTokenScope {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "read:notifications" => Some(ApiTokenScope::ReadNotifications),
+            "write:settings" => Some(ApiTokenScope::WriteSettings),
+            "manage:integrations" => Some(ApiTokenScope::ManageIntegrations),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiTokenScope::ReadNotifications => "read:notifications",
+            ApiTokenScope::WriteSettings => "write:settings",
+            ApiTokenScope::ManageIntegrations => "manage:integrations",
+        }
+    }
+
+    /// Scope an API token needs for a request, by method and path (as seen by the
+    /// top-level router). `None` means the endpoint is not available to API tokens:
+    /// sessions, tokens, shares, account management and the like need a browser login.
+    pub fn required_for(method: &http::Method, path: &str) -> Option<Self> {
+        let segments: Vec<&str> = path
+            .trim_start_matches("/api/")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        match segments.first().copied() {
+            Some("notifications") if method == http::Method::GET => {
+                Some(ApiTokenScope::ReadNotifications)
+            }
+            // Changing the queue needs the same rights as managing integrations
+            Some("notifications") => Some(ApiTokenScope::ManageIntegrations),
+            Some("integrations") => Some(ApiTokenScope::ManageIntegrations),
+            Some("settings") if !segments.iter().any(|s| matches!(*s, "shared" | "invites")) => {
+                Some(ApiTokenScope::WriteSettings)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Space-separated scope list as stored in the database. Unknown scopes are dropped.
+pub fn parse_api_token_scopes(scopes: &str) -> Vec<ApiTokenScope> {
+    scopes
+        .split_whitespace()
+        .filter_map(ApiTokenScope::from_str)
+        .collect()
+}
+
+pub fn format_api_token_scopes(scopes: &[ApiTokenScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Method;
+
+    #[test]
+    fn required_scope_by_route() {
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/notifications/stats"),
+            Some(ApiTokenScope::ReadNotifications)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::POST, "/api/notifications/queue/discard"),
+            Some(ApiTokenScope::ManageIntegrations)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::PUT, "/api/integrations/discord/abc"),
+            Some(ApiTokenScope::ManageIntegrations)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::PUT, "/api/settings/messages"),
+            Some(ApiTokenScope::WriteSettings)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::POST, "/api/settings/u1/shared"),
+            None
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/auth/tokens"),
+            None
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::DELETE, "/api/account"),
+            None
+        );
+    }
+
+    #[test]
+    fn scopes_round_trip() {
+        let scopes = vec![
+            ApiTokenScope::ReadNotifications,
+            ApiTokenScope::ManageIntegrations,
+        ];
+        let stored = format_api_token_scopes(&scopes);
+        assert_eq!(stored, "read:notifications manage:integrations");
+        assert_eq!(parse_api_token_scopes(&format!("{} bogus", stored)), scopes);
+    }
+}
@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ============================================================================
+// Audit Event Models
+// ============================================================================
+
+/// A configuration change: `actor_user_id` did `action` on `entity` of `owner_user_id`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub actor_user_id: String,
+    pub owner_user_id: String,
+    pub entity: String,
+    pub entity_id: Option<String>,
+    pub action: String,
+    /// JSON description of the change (a diff for updates)
+    pub details: Option<String>,
+    pub created_at: NaiveDateTime,
+}
@@ -11,6 +11,8 @@ pub struct DiscordIntegration {
     pub discord_guild_name: Option<String>,
     pub discord_channel_name: Option<String>,
     pub discord_webhook_url: Option<String>,
+    /// Webhook was created by the backend (recreated on 404, deleted with the integration)
+    pub discord_webhook_managed: bool,
     pub is_enabled: bool,
 
     // Per-integration notification settings
@@ -23,6 +25,30 @@ pub struct DiscordIntegration {
     // Calendar sync to Discord events
     pub calendar_sync_enabled: bool,
 
+    // Crosspost notifications when the channel is an announcement channel
+    pub auto_publish: bool,
+    /// Discord channel type (0 text, 5 announcement), recorded on create and edit so sends don't
+    /// look the channel up; `None` for integrations created before it was stored
+    pub discord_channel_type: Option<u8>,
+
+    // Open a thread on stream.online and post follow-up notifications into it
+    pub stream_thread_enabled: bool,
+
+    // Per-event mention: None, "here" (@here) or a guild role id
+    pub mention_stream_online: Option<String>,
+    pub mention_stream_offline: Option<String>,
+    pub mention_title_change: Option<String>,
+    pub mention_category_change: Option<String>,
+    pub mention_reward_redemption: Option<String>,
+
+    /// Why the integration was disabled automatically (see `DISABLED_REASON_*`); `None` if enabled
+    /// or disabled by the user.
+    pub disabled_reason: Option<String>,
+
+    /// Result of the last health probe (see `HEALTH_STATUS_*`); `None` until first probed
+    pub health_status: Option<String>,
+    pub health_checked_at: Option<NaiveDateTime>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -48,4 +74,29 @@ pub struct UpdateDiscordIntegration {
     pub notify_category_change: Option<bool>,
     pub notify_reward_redemption: Option<bool>,
     pub calendar_sync_enabled: Option<bool>,
+    pub auto_publish: Option<bool>,
+    pub stream_thread_enabled: Option<bool>,
+    /// Mentions: `Some("")` clears, `None` leaves unchanged
+    pub mention_stream_online: Option<String>,
+    pub mention_stream_offline: Option<String>,
+    pub mention_title_change: Option<String>,
+    pub mention_category_change: Option<String>,
+    pub mention_reward_redemption: Option<String>,
+}
+
+/// Value stored in `mention_*` columns that stands for `@here`
+pub const DISCORD_MENTION_HERE: &str = "here";
+
+impl DiscordIntegration {
+    /// Configured mention for a notification type (`stream_online`, `title_change`, ...)
+    pub fn mention_for(&self, notification_type: &str) -> Option<&str> {
+        match notification_type {
+            "stream_online" => self.mention_stream_online.as_deref(),
+            "stream_offline" => self.mention_stream_offline.as_deref(),
+            "title_change" => self.mention_title_change.as_deref(),
+            "category_change" => self.mention_category_change.as_deref(),
+            "reward_redemption" => self.mention_reward_redemption.as_deref(),
+            _ => None,
+        }
+    }
 }
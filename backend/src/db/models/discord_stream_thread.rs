@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ============================================================================
+// Discord Stream Thread Models
+// ============================================================================
+
+/// stream.online message (and its discussion thread) of the current broadcast.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DiscordStreamThread {
+    pub integration_id: String,
+    /// Owner or watched channel the stream belongs to
+    pub broadcaster_login: String,
+    pub channel_id: String,
+    pub message_id: String,
+    /// `None` if the thread could not be created
+    pub thread_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
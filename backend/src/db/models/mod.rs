@@ -4,25 +4,39 @@
 //! This module re-exports individual model modules so existing imports like
 //! `use crate::db::models::*;` continue to work.
 
+pub mod api_token;
+pub mod audit_event;
 pub mod calendar;
 pub mod chat_type;
 pub mod discord_integration;
+pub mod discord_stream_thread;
 pub mod eventsub_subscription;
 pub mod notification_history;
 pub mod notification_queue;
 pub mod notification_settings;
+pub mod session;
 pub mod share;
 pub mod telegram_integration;
+pub mod telegram_link_code;
+pub mod telegram_pending_chat;
 pub mod user;
+pub mod watched_channel;
 
 // Re-export all types at the `crate::db::models` namespace for backward compatibility.
+pub use self::api_token::*;
+pub use self::audit_event::*;
 pub use self::calendar::*;
 pub use self::chat_type::*;
 pub use self::discord_integration::*;
+pub use self::discord_stream_thread::*;
 pub use self::eventsub_subscription::*;
 pub use self::notification_history::*;
 pub use self::notification_queue::*;
 pub use self::notification_settings::*;
+pub use self::session::*;
 pub use self::share::*;
 pub use self::telegram_integration::*;
+pub use self::telegram_link_code::*;
+pub use self::telegram_pending_chat::*;
 pub use self::user::*;
+pub use self::watched_channel::*;
@@ -40,6 +40,22 @@ pub struct NotificationTask {
     /// Optional webhook URL (used for Discord webhook-based integrations).
     pub webhook_url: Option<String>,
 
+    /// Stream URL substituted into `{url}` when the message is re-rendered.
+    /// Set for watched channels, whose URL differs from the owner's own channel.
+    pub stream_url: Option<String>,
+
+    /// Telegram forum topic the message is posted to (if any).
+    pub message_thread_id: Option<i32>,
+
+    /// Discord mention prepended to the message (if any).
+    pub discord_mention: Option<String>,
+
+    /// Crosspost the message if the Discord channel is an announcement channel.
+    pub discord_auto_publish: bool,
+
+    /// Discord thread the message is posted to (if any).
+    pub discord_thread_id: Option<String>,
+
     /// Number of attempts already made.
     pub attempts: i32,
 
@@ -81,6 +97,11 @@ pub struct CreateNotificationTask {
     pub destination_type: String,
     pub destination_id: String,
     pub webhook_url: Option<String>,
+    pub stream_url: Option<String>,
+    pub message_thread_id: Option<i32>,
+    pub discord_mention: Option<String>,
+    pub discord_auto_publish: bool,
+    pub discord_thread_id: Option<String>,
 
     /// Optional override for maximum attempts; repository can default this.
     pub max_attempts: Option<i32>,
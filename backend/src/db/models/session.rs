@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ============================================================================
+// Session Models
+// ============================================================================
+
+/// Server-side record of a login. Every issued JWT names its session (`sid` claim);
+/// deleting the row revokes the token.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub user_id: String,
+    /// SHA-256 (hex) of the issued JWT
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+}
@@ -11,7 +11,131 @@ pub struct SettingsShare {
     pub id: String,
     pub owner_user_id: String,
     pub grantee_user_id: String,
-    pub can_manage: bool,
+    pub role: ShareRole,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
+
+/// Single-use invite that creates a share for whoever accepts it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareInvite {
+    pub id: String,
+    /// SHA-256 (hex) of the invite token
+    pub token_hash: String,
+    pub owner_user_id: String,
+    /// User who created the invite (owner or a grantee with the manager role)
+    pub created_by: String,
+    pub role: ShareRole,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+/// Outcome of accepting a share invite
+#[derive(Debug, Clone)]
+pub struct AcceptedShareInvite {
+    pub invite: ShareInvite,
+    pub share: SettingsShare,
+    /// Role of the share before the invite was accepted, `None` if the share is new
+    pub previous_role: Option<ShareRole>,
+}
+
+/// What a grantee may do with the owner's settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareRole {
+    /// Read-only access
+    Viewer,
+    /// Can edit message templates and notification settings
+    TemplateEditor,
+    /// Can create, edit, test and delete integrations and manage their queue
+    IntegrationManager,
+    /// Full access, including managing the owner's shares
+    Manager,
+}
+
+/// An action on the owner's resources that requires a share permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharePermission {
+    View,
+    EditTemplates,
+    ManageIntegrations,
+    ManageShares,
+}
+
+impl ShareRole {
+    /// Convert from string (case-insensitive)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Some(ShareRole::Viewer),
+            "template_editor" => Some(ShareRole::TemplateEditor),
+            "integration_manager" => Some(ShareRole::IntegrationManager),
+            "manager" => Some(ShareRole::Manager),
+            _ => None,
+        }
+    }
+
+    /// Convert to string
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShareRole::Viewer => "viewer",
+            ShareRole::TemplateEditor => "template_editor",
+            ShareRole::IntegrationManager => "integration_manager",
+            ShareRole::Manager => "manager",
+        }
+    }
+
+    pub fn allows(self, permission: SharePermission) -> bool {
+        match permission {
+            SharePermission::View => true,
+            SharePermission::EditTemplates => {
+                matches!(self, ShareRole::TemplateEditor | ShareRole::Manager)
+            }
+            SharePermission::ManageIntegrations => {
+                matches!(self, ShareRole::IntegrationManager | ShareRole::Manager)
+            }
+            SharePermission::ManageShares => self == ShareRole::Manager,
+        }
+    }
+
+    /// Whether this role grants every permission `other` does
+    pub fn includes(self, other: ShareRole) -> bool {
+        use SharePermission::*;
+
+        [View, EditTemplates, ManageIntegrations, ManageShares]
+            .into_iter()
+            .all(|permission| !other.allows(permission) || self.allows(permission))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_grant_expected_permissions() {
+        use SharePermission::*;
+
+        let cases = [
+            (ShareRole::Viewer, [true, false, false, false]),
+            (ShareRole::TemplateEditor, [true, true, false, false]),
+            (ShareRole::IntegrationManager, [true, false, true, false]),
+            (ShareRole::Manager, [true, true, true, true]),
+        ];
+        for (role, expected) in cases {
+            let actual = [View, EditTemplates, ManageIntegrations, ManageShares]
+                .map(|permission| role.allows(permission));
+            assert_eq!(actual, expected, "{:?}", role);
+            assert_eq!(ShareRole::from_str(role.as_str()), Some(role));
+        }
+    }
+
+    #[test]
+    fn roles_include_weaker_roles_only() {
+        assert!(ShareRole::Manager.includes(ShareRole::IntegrationManager));
+        assert!(ShareRole::TemplateEditor.includes(ShareRole::Viewer));
+        assert!(ShareRole::Viewer.includes(ShareRole::Viewer));
+        assert!(!ShareRole::Viewer.includes(ShareRole::TemplateEditor));
+        assert!(!ShareRole::TemplateEditor.includes(ShareRole::IntegrationManager));
+        assert!(!ShareRole::IntegrationManager.includes(ShareRole::Manager));
+    }
+}
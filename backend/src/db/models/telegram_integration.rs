@@ -4,6 +4,28 @@ use sqlx::FromRow;
 
 use super::ChatType;
 
+/// The bot was removed from the chat (or lost its rights there)
+pub const DISABLED_REASON_BOT_REMOVED: &str = "bot_removed";
+
+/// A send failed because the chat (or Discord channel/webhook) no longer exists
+/// or the bot was blocked or removed there
+pub const DISABLED_REASON_TARGET_GONE: &str = "target_gone";
+
+/// Sends kept failing because the bot may not post there (or its token is invalid)
+pub const DISABLED_REASON_PERMISSION_DENIED: &str = "permission_denied";
+
+/// The bot can reach the destination and post there
+pub const HEALTH_STATUS_OK: &str = "ok";
+
+/// The chat or channel (or Discord webhook) no longer exists or the bot was removed
+pub const HEALTH_STATUS_UNREACHABLE: &str = "unreachable";
+
+/// The destination exists but the bot lacks the rights to post there
+pub const HEALTH_STATUS_MISSING_PERMISSIONS: &str = "missing_permissions";
+
+/// The probe was rejected for another reason
+pub const HEALTH_STATUS_ERROR: &str = "error";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramIntegration {
     pub id: String,
@@ -11,6 +33,8 @@ pub struct TelegramIntegration {
     pub telegram_chat_id: String,
     pub telegram_chat_title: Option<String>,
     pub telegram_chat_type: Option<ChatType>,
+    /// Forum topic to post into; `None` posts to the chat itself ("General" in forums).
+    pub message_thread_id: Option<i32>,
     pub is_enabled: bool,
 
     // Per-integration notification settings
@@ -23,6 +47,14 @@ pub struct TelegramIntegration {
     /// Last Telegram message id sent to this chat; used to delete the previous message when sending a new one.
     pub last_telegram_message_id: Option<i32>,
 
+    /// Why the integration was disabled automatically (see `DISABLED_REASON_*`); `None` if enabled
+    /// or disabled by the user.
+    pub disabled_reason: Option<String>,
+
+    /// Result of the last health probe (see `HEALTH_STATUS_*`); `None` until first probed
+    pub health_status: Option<String>,
+    pub health_checked_at: Option<NaiveDateTime>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -32,6 +64,7 @@ pub struct CreateTelegramIntegration {
     pub telegram_chat_id: String,
     pub telegram_chat_title: Option<String>,
     pub telegram_chat_type: Option<ChatType>,
+    pub message_thread_id: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ============================================================================
+// Telegram Link Code Models
+// ============================================================================
+
+/// One-time code used to link a Telegram chat via the bot's `/link` command.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TelegramLinkCode {
+    pub code: String,
+    /// Owner the integration will be created for
+    pub user_id: String,
+    /// User who generated the code (owner or a grantee with manage rights)
+    pub created_by: String,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::ChatType;
+
+// ============================================================================
+// Telegram Pending Chat Models
+// ============================================================================
+
+/// A chat the bot is a member of, discovered from `my_chat_member` updates and
+/// offered in the UI chat picker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramPendingChat {
+    pub chat_id: String,
+    pub chat_title: Option<String>,
+    pub chat_type: Option<ChatType>,
+    /// Telegram user who added the bot to the chat
+    pub added_by_telegram_user_id: Option<String>,
+    /// Bot membership status in the chat (`administrator`, `member`, ...)
+    pub bot_status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertTelegramPendingChat {
+    pub chat_id: String,
+    pub chat_title: Option<String>,
+    pub chat_type: ChatType,
+    pub added_by_telegram_user_id: Option<String>,
+    pub bot_status: String,
+}
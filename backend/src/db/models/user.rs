@@ -25,6 +25,13 @@ pub struct User {
     pub discord_avatar_url: Option<String>,
     pub lang: Option<String>,
 
+    /// Instance administrator (see `AdminConfig`)
+    pub is_admin: bool,
+    /// Set while an administrator has suspended the account; suspended accounts
+    /// receive no notifications
+    pub suspended_at: Option<NaiveDateTime>,
+    pub suspended_reason: Option<String>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// ============================================================================
+// Watched Channel Models
+// ============================================================================
+
+/// A Twitch channel the user follows without owning it ("follow mode").
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WatchedChannel {
+    pub id: String,
+    pub user_id: String,
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_display_name: String,
+    pub broadcaster_profile_image_url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWatchedChannel {
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_display_name: String,
+    pub broadcaster_profile_image_url: Option<String>,
+}
+
+/// App-token EventSub subscription for a watched broadcaster (shared by all watchers).
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WatchedChannelSubscription {
+    pub id: String,
+    pub twitch_subscription_id: String,
+    pub broadcaster_id: String,
+    pub subscription_type: String,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,303 @@
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+
+use crate::db::models::{format_api_token_scopes, parse_api_token_scopes, ApiToken, ApiTokenScope};
+use crate::error::{AppError, AppResult};
+
+/// `last_used_at` is only written when it is older than this, to avoid a write per request
+const TOUCH_INTERVAL_SECONDS: i64 = 60;
+
+// Intermediate structure for reading from DB (scopes stored as a space-separated list)
+struct ApiTokenRow {
+    id: String,
+    user_id: String,
+    name: String,
+    token_hash: String,
+    token_prefix: String,
+    scopes: String,
+    expires_at: Option<chrono::NaiveDateTime>,
+    last_used_at: Option<chrono::NaiveDateTime>,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl From<ApiTokenRow> for ApiToken {
+    fn from(row: ApiTokenRow) -> Self {
+        ApiToken {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            token_hash: row.token_hash,
+            token_prefix: row.token_prefix,
+            scopes: parse_api_token_scopes(&row.scopes),
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ============================================================================
+// API Token Repository
+// ============================================================================
+
+pub struct ApiTokenRepository;
+
+impl ApiTokenRepository {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        id: &str,
+        user_id: &str,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[ApiTokenScope],
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) -> AppResult<ApiToken> {
+        let now = Utc::now().naive_utc();
+        let scopes = format_api_token_scopes(scopes);
+
+        sqlx::query_as!(
+            ApiTokenRow,
+            r#"
+            INSERT INTO api_tokens (id, user_id, name, token_hash, token_prefix, scopes, expires_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: String",
+                user_id as "user_id!: String",
+                name as "name!: String",
+                token_hash as "token_hash!: String",
+                token_prefix as "token_prefix!: String",
+                scopes as "scopes!: String",
+                expires_at as "expires_at?: chrono::NaiveDateTime",
+                last_used_at as "last_used_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            user_id,
+            name,
+            token_hash,
+            token_prefix,
+            scopes,
+            expires_at,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map(ApiToken::from)
+        .map_err(AppError::Database)
+    }
+
+    /// Find an unexpired token by its hash
+    pub async fn find_active_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> AppResult<Option<ApiToken>> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            ApiTokenRow,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                name as "name!: String",
+                token_hash as "token_hash!: String",
+                token_prefix as "token_prefix!: String",
+                scopes as "scopes!: String",
+                expires_at as "expires_at?: chrono::NaiveDateTime",
+                last_used_at as "last_used_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM api_tokens
+            WHERE token_hash = ? AND (expires_at IS NULL OR expires_at > ?)
+            "#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(ApiToken::from))
+        .map_err(AppError::Database)
+    }
+
+    /// Tokens of a user (including expired ones), newest first
+    pub async fn list_for_user(pool: &SqlitePool, user_id: &str) -> AppResult<Vec<ApiToken>> {
+        let rows = sqlx::query_as!(
+            ApiTokenRow,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                name as "name!: String",
+                token_hash as "token_hash!: String",
+                token_prefix as "token_prefix!: String",
+                scopes as "scopes!: String",
+                expires_at as "expires_at?: chrono::NaiveDateTime",
+                last_used_at as "last_used_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM api_tokens
+            WHERE user_id = ?
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(ApiToken::from).collect())
+    }
+
+    pub async fn count_for_user(pool: &SqlitePool, user_id: &str) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM api_tokens WHERE user_id = ?"#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Record that a token was used (at most once a minute)
+    pub async fn touch(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+        let stale_before = now - Duration::seconds(TOUCH_INTERVAL_SECONDS);
+
+        sqlx::query!(
+            r#"
+            UPDATE api_tokens SET last_used_at = ?
+            WHERE id = ? AND (last_used_at IS NULL OR last_used_at < ?)
+            "#,
+            now,
+            id,
+            stale_before
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Revoke a token of a user. Returns the revoked token, or `None` if there was no such token.
+    pub async fn delete_for_user(
+        pool: &SqlitePool,
+        id: &str,
+        user_id: &str,
+    ) -> AppResult<Option<ApiToken>> {
+        sqlx::query_as!(
+            ApiTokenRow,
+            r#"
+            DELETE FROM api_tokens
+            WHERE id = ? AND user_id = ?
+            RETURNING
+                id as "id!: String",
+                user_id as "user_id!: String",
+                name as "name!: String",
+                token_hash as "token_hash!: String",
+                token_prefix as "token_prefix!: String",
+                scopes as "scopes!: String",
+                expires_at as "expires_at?: chrono::NaiveDateTime",
+                last_used_at as "last_used_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(ApiToken::from))
+        .map_err(AppError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn expired_and_revoked_tokens_are_not_active() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE api_tokens (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                token_prefix TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                expires_at DATETIME,
+                last_used_at DATETIME,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+        let earlier = Utc::now().naive_utc() - Duration::hours(1);
+        let scopes = [ApiTokenScope::WriteSettings];
+
+        ApiTokenRepository::create(&pool, "a", "u1", "ci", "hash-a", "tn_a", &scopes, None)
+            .await
+            .map_err(err)?;
+        ApiTokenRepository::create(
+            &pool,
+            "b",
+            "u1",
+            "old",
+            "hash-b",
+            "tn_b",
+            &scopes,
+            Some(earlier),
+        )
+        .await
+        .map_err(err)?;
+
+        let token = ApiTokenRepository::find_active_by_token_hash(&pool, "hash-a")
+            .await
+            .map_err(err)?
+            .expect("token should be active");
+        assert_eq!(token.scopes, scopes);
+        assert!(token.last_used_at.is_none());
+
+        ApiTokenRepository::touch(&pool, "a").await.map_err(err)?;
+        assert!(ApiTokenRepository::list_for_user(&pool, "u1")
+            .await
+            .map_err(err)?
+            .iter()
+            .any(|t| t.id == "a" && t.last_used_at.is_some()));
+
+        assert!(
+            ApiTokenRepository::find_active_by_token_hash(&pool, "hash-b")
+                .await
+                .map_err(err)?
+                .is_none()
+        );
+
+        // Another user can't revoke the token
+        assert!(ApiTokenRepository::delete_for_user(&pool, "a", "u2")
+            .await
+            .map_err(err)?
+            .is_none());
+        assert!(ApiTokenRepository::delete_for_user(&pool, "a", "u1")
+            .await
+            .map_err(err)?
+            .is_some());
+        assert!(
+            ApiTokenRepository::find_active_by_token_hash(&pool, "hash-a")
+                .await
+                .map_err(err)?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
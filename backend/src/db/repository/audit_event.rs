@@ -0,0 +1,228 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::models::AuditEvent;
+use crate::error::{AppError, AppResult};
+
+// ============================================================================
+// Audit Event Repository
+// ============================================================================
+
+/// Append-only: events are only ever inserted
+pub struct AuditEventRepository;
+
+impl AuditEventRepository {
+    pub async fn create(
+        pool: &SqlitePool,
+        actor_user_id: &str,
+        owner_user_id: &str,
+        entity: &str,
+        entity_id: Option<&str>,
+        action: &str,
+        details: Option<&str>,
+    ) -> AppResult<()> {
+        let id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_events (
+                id, actor_user_id, owner_user_id, entity, entity_id, action, details, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            id,
+            actor_user_id,
+            owner_user_id,
+            entity,
+            entity_id,
+            action,
+            details,
+            now
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Events of an owner, newest first, with the actor's Twitch login when the actor
+    /// still exists. `entity` and `actor_user_id` narrow the result when given.
+    pub async fn list_for_owner(
+        pool: &SqlitePool,
+        owner_user_id: &str,
+        entity: Option<&str>,
+        actor_user_id: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<(AuditEvent, Option<String>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                e.id as "id!: String",
+                e.actor_user_id as "actor_user_id!: String",
+                e.owner_user_id as "owner_user_id!: String",
+                e.entity as "entity!: String",
+                e.entity_id as "entity_id?: String",
+                e.action as "action!: String",
+                e.details as "details?: String",
+                e.created_at as "created_at!: chrono::NaiveDateTime",
+                u.twitch_login as "actor_login?: String"
+            FROM audit_events e
+            LEFT JOIN users u ON u.id = e.actor_user_id
+            WHERE e.owner_user_id = ?
+              AND (? IS NULL OR e.entity = ?)
+              AND (? IS NULL OR e.actor_user_id = ?)
+            ORDER BY e.created_at DESC, e.rowid DESC
+            LIMIT ? OFFSET ?
+            "#,
+            owner_user_id,
+            entity,
+            entity,
+            actor_user_id,
+            actor_user_id,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let event = AuditEvent {
+                    id: r.id,
+                    actor_user_id: r.actor_user_id,
+                    owner_user_id: r.owner_user_id,
+                    entity: r.entity,
+                    entity_id: r.entity_id,
+                    action: r.action,
+                    details: r.details,
+                    created_at: r.created_at,
+                };
+                (event, r.actor_login)
+            })
+            .collect())
+    }
+
+    pub async fn count_for_owner(
+        pool: &SqlitePool,
+        owner_user_id: &str,
+        entity: Option<&str>,
+        actor_user_id: Option<&str>,
+    ) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM audit_events
+            WHERE owner_user_id = ?
+              AND (? IS NULL OR entity = ?)
+              AND (? IS NULL OR actor_user_id = ?)
+            "#,
+            owner_user_id,
+            entity,
+            entity,
+            actor_user_id,
+            actor_user_id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn list_filters_and_rejects_changes() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (id TEXT PRIMARY KEY, twitch_login TEXT NOT NULL);
+            CREATE TABLE audit_events (
+                id TEXT PRIMARY KEY,
+                actor_user_id TEXT NOT NULL,
+                owner_user_id TEXT NOT NULL,
+                entity TEXT NOT NULL,
+                entity_id TEXT,
+                action TEXT NOT NULL,
+                details TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(include_str!(
+            "../../../migrations/022_make_audit_events_append_only.sql"
+        ))
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "INSERT INTO users (id, twitch_login) VALUES ('owner', 'owner'), ('mod', 'moderator')",
+        )
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+        AuditEventRepository::create(&pool, "owner", "owner", "settings", None, "update", None)
+            .await
+            .map_err(err)?;
+        AuditEventRepository::create(
+            &pool,
+            "mod",
+            "owner",
+            "discord_integration",
+            Some("d1"),
+            "update",
+            Some(r#"{"is_enabled":{"from":true,"to":false}}"#),
+        )
+        .await
+        .map_err(err)?;
+
+        let all = AuditEventRepository::list_for_owner(&pool, "owner", None, None, 10, 0)
+            .await
+            .map_err(err)?;
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0.actor_user_id, "mod", "newest first");
+        assert_eq!(all[0].1.as_deref(), Some("moderator"));
+
+        let by_mod = AuditEventRepository::list_for_owner(&pool, "owner", None, Some("mod"), 10, 0)
+            .await
+            .map_err(err)?;
+        assert_eq!(by_mod.len(), 1);
+        assert_eq!(
+            AuditEventRepository::count_for_owner(&pool, "owner", Some("settings"), None)
+                .await
+                .map_err(err)?,
+            1
+        );
+
+        // Append-only while the owner exists
+        assert!(sqlx::query("UPDATE audit_events SET action = 'x'")
+            .execute(&pool)
+            .await
+            .is_err());
+        assert!(sqlx::query("DELETE FROM audit_events")
+            .execute(&pool)
+            .await
+            .is_err());
+
+        sqlx::query("DELETE FROM users WHERE id = 'owner'")
+            .execute(&pool)
+            .await?;
+        sqlx::query("DELETE FROM audit_events")
+            .execute(&pool)
+            .await?;
+
+        Ok(())
+    }
+}
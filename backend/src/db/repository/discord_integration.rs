@@ -35,6 +35,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -42,6 +43,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
@@ -79,6 +91,7 @@ impl DiscordIntegrationRepository {
                     discord_guild_name as "discord_guild_name?: String",
                     discord_channel_name as "discord_channel_name?: String",
                     discord_webhook_url as "discord_webhook_url?: String",
+                    discord_webhook_managed as "discord_webhook_managed!: bool",
                     is_enabled as "is_enabled!: bool",
                     notify_stream_online as "notify_stream_online!: bool",
                     notify_stream_offline as "notify_stream_offline!: bool",
@@ -86,6 +99,17 @@ impl DiscordIntegrationRepository {
                     notify_category_change as "notify_category_change!: bool",
                     notify_reward_redemption as "notify_reward_redemption!: bool",
                     calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                    auto_publish as "auto_publish!: bool",
+                    discord_channel_type as "discord_channel_type?: u8",
+                    stream_thread_enabled as "stream_thread_enabled!: bool",
+                    mention_stream_online as "mention_stream_online?: String",
+                    mention_stream_offline as "mention_stream_offline?: String",
+                    mention_title_change as "mention_title_change?: String",
+                    mention_category_change as "mention_category_change?: String",
+                    mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                     created_at as "created_at!: chrono::NaiveDateTime",
                     updated_at as "updated_at!: chrono::NaiveDateTime"
                 FROM discord_integrations
@@ -114,6 +138,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -121,6 +146,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM discord_integrations
@@ -150,6 +186,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -157,6 +194,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM discord_integrations
@@ -171,6 +219,50 @@ impl DiscordIntegrationRepository {
         .map_err(AppError::Database)
     }
 
+    /// Find all enabled Discord integrations (for the health probe)
+    pub async fn find_all_enabled(pool: &SqlitePool) -> AppResult<Vec<DiscordIntegration>> {
+        sqlx::query_as!(
+            DiscordIntegration,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                discord_guild_id as "discord_guild_id!: String",
+                discord_channel_id as "discord_channel_id!: String",
+                discord_guild_name as "discord_guild_name?: String",
+                discord_channel_name as "discord_channel_name?: String",
+                discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
+                is_enabled as "is_enabled!: bool",
+                notify_stream_online as "notify_stream_online!: bool",
+                notify_stream_offline as "notify_stream_offline!: bool",
+                notify_title_change as "notify_title_change!: bool",
+                notify_category_change as "notify_category_change!: bool",
+                notify_reward_redemption as "notify_reward_redemption!: bool",
+                calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM discord_integrations
+            WHERE is_enabled = ?
+            "#,
+            true
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
     /// Find Discord integrations by channel id
     pub async fn find_by_channel_id(
         pool: &SqlitePool,
@@ -187,6 +279,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -194,6 +287,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM discord_integrations
@@ -222,6 +326,12 @@ impl DiscordIntegrationRepository {
         let discord_channel_name = update.discord_channel_name.or(current.discord_channel_name);
         let discord_webhook_url = update.discord_webhook_url.or(current.discord_webhook_url);
         let is_enabled = update.is_enabled.unwrap_or(current.is_enabled);
+        // Re-enabling clears the reason of an automatic disable
+        let disabled_reason = if is_enabled {
+            None
+        } else {
+            current.disabled_reason
+        };
         let notify_stream_online = update
             .notify_stream_online
             .unwrap_or(current.notify_stream_online);
@@ -240,6 +350,31 @@ impl DiscordIntegrationRepository {
         let calendar_sync_enabled = update
             .calendar_sync_enabled
             .unwrap_or(current.calendar_sync_enabled);
+        let auto_publish = update.auto_publish.unwrap_or(current.auto_publish);
+        let stream_thread_enabled = update
+            .stream_thread_enabled
+            .unwrap_or(current.stream_thread_enabled);
+        // Empty string clears the mention
+        let mention_stream_online = update
+            .mention_stream_online
+            .or(current.mention_stream_online)
+            .filter(|m| !m.is_empty());
+        let mention_stream_offline = update
+            .mention_stream_offline
+            .or(current.mention_stream_offline)
+            .filter(|m| !m.is_empty());
+        let mention_title_change = update
+            .mention_title_change
+            .or(current.mention_title_change)
+            .filter(|m| !m.is_empty());
+        let mention_category_change = update
+            .mention_category_change
+            .or(current.mention_category_change)
+            .filter(|m| !m.is_empty());
+        let mention_reward_redemption = update
+            .mention_reward_redemption
+            .or(current.mention_reward_redemption)
+            .filter(|m| !m.is_empty());
         let now = Utc::now().naive_utc();
 
         sqlx::query_as!(
@@ -250,12 +385,20 @@ impl DiscordIntegrationRepository {
                 discord_channel_name = ?,
                 discord_webhook_url = ?,
                 is_enabled = ?,
+                disabled_reason = ?,
                 notify_stream_online = ?,
                 notify_stream_offline = ?,
                 notify_title_change = ?,
                 notify_category_change = ?,
                 notify_reward_redemption = ?,
                 calendar_sync_enabled = ?,
+                auto_publish = ?,
+                stream_thread_enabled = ?,
+                mention_stream_online = ?,
+                mention_stream_offline = ?,
+                mention_title_change = ?,
+                mention_category_change = ?,
+                mention_reward_redemption = ?,
                 updated_at = ?
             WHERE id = ?
             RETURNING
@@ -266,6 +409,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -273,6 +417,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
@@ -280,20 +435,136 @@ impl DiscordIntegrationRepository {
             discord_channel_name,
             discord_webhook_url,
             is_enabled,
+            disabled_reason,
             notify_stream_online,
             notify_stream_offline,
             notify_title_change,
             notify_category_change,
             notify_reward_redemption,
             calendar_sync_enabled,
+            auto_publish,
+            stream_thread_enabled,
+            mention_stream_online,
+            mention_stream_offline,
+            mention_title_change,
+            mention_category_change,
+            mention_reward_redemption,
+            now,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Record the type of the integration's Discord channel
+    pub async fn set_channel_type(pool: &SqlitePool, id: &str, channel_type: u8) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE discord_integrations SET discord_channel_type = ? WHERE id = ?",
+            channel_type,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Replace the webhook of an integration (`None` switches to bot sends)
+    pub async fn set_webhook(
+        pool: &SqlitePool,
+        id: &str,
+        webhook_url: Option<&str>,
+        managed: bool,
+    ) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            UPDATE discord_integrations
+            SET discord_webhook_url = ?, discord_webhook_managed = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            webhook_url,
+            managed,
             now,
             id
         )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Disable an integration automatically and record why
+    pub async fn disable(pool: &SqlitePool, id: &str, reason: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            UPDATE discord_integrations
+            SET is_enabled = 0, disabled_reason = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Count a permanent delivery failure; returns the length of the current streak
+    pub async fn record_failure(pool: &SqlitePool, id: &str) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE discord_integrations
+            SET consecutive_failures = consecutive_failures + 1
+            WHERE id = ?
+            RETURNING consecutive_failures as "consecutive_failures!: i64"
+            "#,
+            id
+        )
         .fetch_one(pool)
         .await
         .map_err(AppError::Database)
     }
 
+    /// End the failure streak of an integration (after a successful send)
+    pub async fn reset_failures(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE discord_integrations SET consecutive_failures = 0 WHERE id = ? AND consecutive_failures > 0",
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Store the result of a health probe
+    pub async fn set_health(pool: &SqlitePool, id: &str, status: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            "UPDATE discord_integrations SET health_status = ?, health_checked_at = ? WHERE id = ?",
+            status,
+            now,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
     /// Delete Discord integration
     pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<()> {
         sqlx::query!("DELETE FROM discord_integrations WHERE id = ?", id)
@@ -317,6 +588,7 @@ impl DiscordIntegrationRepository {
                 discord_guild_name as "discord_guild_name?: String",
                 discord_channel_name as "discord_channel_name?: String",
                 discord_webhook_url as "discord_webhook_url?: String",
+                discord_webhook_managed as "discord_webhook_managed!: bool",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -324,6 +596,17 @@ impl DiscordIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 calendar_sync_enabled as "calendar_sync_enabled!: bool",
+                auto_publish as "auto_publish!: bool",
+                discord_channel_type as "discord_channel_type?: u8",
+                stream_thread_enabled as "stream_thread_enabled!: bool",
+                mention_stream_online as "mention_stream_online?: String",
+                mention_stream_offline as "mention_stream_offline?: String",
+                mention_title_change as "mention_title_change?: String",
+                mention_category_change as "mention_category_change?: String",
+                mention_reward_redemption as "mention_reward_redemption?: String",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM discord_integrations
@@ -335,4 +618,16 @@ impl DiscordIntegrationRepository {
         .await
         .map_err(AppError::Database)
     }
+
+    /// Distinct guild ids that have at least one integration
+    pub async fn list_guild_ids(pool: &SqlitePool) -> AppResult<Vec<String>> {
+        let rows = sqlx::query!(
+            r#"SELECT DISTINCT discord_guild_id as "discord_guild_id!: String" FROM discord_integrations"#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|r| r.discord_guild_id).collect())
+    }
 }
@@ -0,0 +1,95 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::db::models::DiscordStreamThread;
+use crate::error::{AppError, AppResult};
+
+// ============================================================================
+// Discord Stream Thread Repository
+// ============================================================================
+
+pub struct DiscordStreamThreadRepository;
+
+impl DiscordStreamThreadRepository {
+    /// Record the stream.online message of a new broadcast, replacing the previous one.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        integration_id: &str,
+        broadcaster_login: &str,
+        channel_id: &str,
+        message_id: &str,
+        thread_id: Option<&str>,
+    ) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO discord_stream_threads (
+                integration_id, broadcaster_login, channel_id, message_id, thread_id, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(integration_id, broadcaster_login) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                message_id = excluded.message_id,
+                thread_id = excluded.thread_id,
+                created_at = excluded.created_at
+            "#,
+            integration_id,
+            broadcaster_login,
+            channel_id,
+            message_id,
+            thread_id,
+            now
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Current broadcast of `broadcaster_login` for an integration.
+    pub async fn find(
+        pool: &SqlitePool,
+        integration_id: &str,
+        broadcaster_login: &str,
+    ) -> AppResult<Option<DiscordStreamThread>> {
+        sqlx::query_as!(
+            DiscordStreamThread,
+            r#"
+            SELECT
+                integration_id as "integration_id!: String",
+                broadcaster_login as "broadcaster_login!: String",
+                channel_id as "channel_id!: String",
+                message_id as "message_id!: String",
+                thread_id as "thread_id?: String",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM discord_stream_threads
+            WHERE integration_id = ? AND broadcaster_login = ?
+            "#,
+            integration_id,
+            broadcaster_login
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Forget the broadcast (stream ended).
+    pub async fn delete(
+        pool: &SqlitePool,
+        integration_id: &str,
+        broadcaster_login: &str,
+    ) -> AppResult<()> {
+        sqlx::query!(
+            "DELETE FROM discord_stream_threads WHERE integration_id = ? AND broadcaster_login = ?",
+            integration_id,
+            broadcaster_login
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
@@ -140,6 +140,44 @@ impl EventSubSubscriptionRepository {
         .map_err(AppError::Database)
     }
 
+    /// Number of subscriptions per user and status, as `(user_id, status, count)`
+    pub async fn status_counts_by_user(pool: &SqlitePool) -> AppResult<Vec<(String, String, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                user_id as "user_id!: String",
+                status as "status!: String",
+                COUNT(*) as "count!: i64"
+            FROM eventsub_subscriptions
+            GROUP BY user_id, status
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.user_id, r.status, r.count))
+            .collect())
+    }
+
+    /// Number of subscriptions per status, as `(status, count)`
+    pub async fn status_counts(pool: &SqlitePool) -> AppResult<Vec<(String, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT status as "status!: String", COUNT(*) as "count!: i64"
+            FROM eventsub_subscriptions
+            GROUP BY status
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+    }
+
     /// Delete a subscription
     pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<()> {
         sqlx::query!("DELETE FROM eventsub_subscriptions WHERE id = ?", id)
@@ -1,20 +1,37 @@
+pub mod api_token;
+pub mod audit_event;
 pub mod discord_integration;
+pub mod discord_stream_thread;
 pub mod eventsub_subscription;
 pub mod notification_log_repository;
 pub mod notification_queue_repository;
 pub mod notification_settings;
+pub mod session;
 pub mod settings_shares;
+pub mod share_invite;
 pub mod synced_calendar_repository;
 pub mod telegram_integration;
+pub mod telegram_link_code;
+pub mod telegram_pending_chat;
 pub mod user;
+pub mod watched_channel;
+pub mod watched_channel_subscription;
 
+pub use api_token::ApiTokenRepository;
+pub use audit_event::AuditEventRepository;
 pub use discord_integration::DiscordIntegrationRepository;
+pub use discord_stream_thread::DiscordStreamThreadRepository;
 pub use eventsub_subscription::EventSubSubscriptionRepository;
 pub use notification_log_repository::NotificationLogRepository;
 pub use notification_queue_repository::NotificationQueueRepository;
 pub use notification_settings::NotificationSettingsRepository;
-
+pub use session::SessionRepository;
 pub use settings_shares::SettingsShareRepository;
+pub use share_invite::ShareInviteRepository;
 pub use synced_calendar_repository::SyncedCalendarRepository;
 pub use telegram_integration::TelegramIntegrationRepository;
+pub use telegram_link_code::TelegramLinkCodeRepository;
+pub use telegram_pending_chat::TelegramPendingChatRepository;
 pub use user::UserRepository;
+pub use watched_channel::WatchedChannelRepository;
+pub use watched_channel_subscription::WatchedChannelSubscriptionRepository;
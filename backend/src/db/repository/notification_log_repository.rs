@@ -105,6 +105,35 @@ impl NotificationLogRepository {
         .map_err(AppError::Database)
     }
 
+    /// All notifications of a user, newest first.
+    pub async fn find_all_by_user_id(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> AppResult<Vec<NotificationLog>> {
+        sqlx::query_as!(
+            NotificationLog,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                notification_type as "notification_type!: String",
+                destination_type as "destination_type!: String",
+                destination_id as "destination_id!: String",
+                content as "content!: String",
+                status as "status!: String",
+                error_message as "error_message?: String",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM notification_history
+            WHERE user_id = ?
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
     /// Count notifications for a user with optional filters.
     pub async fn count_by_user_id_with_filters(
         pool: &SqlitePool,
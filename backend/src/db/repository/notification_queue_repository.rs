@@ -42,6 +42,11 @@ impl NotificationQueueRepository {
                 destination_type,
                 destination_id,
                 webhook_url,
+                stream_url,
+                message_thread_id,
+                discord_mention,
+                discord_auto_publish,
+                discord_thread_id,
                 attempts,
                 max_attempts,
                 next_attempt_at,
@@ -50,7 +55,7 @@ impl NotificationQueueRepository {
                 status,
                 created_at,
                 updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING
                 id as "id!: String",
                 notification_log_id as "notification_log_id?: String",
@@ -61,6 +66,11 @@ impl NotificationQueueRepository {
                 destination_type as "destination_type!: String",
                 destination_id as "destination_id!: String",
                 webhook_url as "webhook_url?: String",
+                stream_url as "stream_url?: String",
+                message_thread_id as "message_thread_id?: i32",
+                discord_mention as "discord_mention?: String",
+                discord_auto_publish as "discord_auto_publish!: bool",
+                discord_thread_id as "discord_thread_id?: String",
                 attempts as "attempts!: i32",
                 max_attempts as "max_attempts!: i32",
                 next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -79,6 +89,11 @@ impl NotificationQueueRepository {
             task.destination_type,
             task.destination_id,
             task.webhook_url,
+            task.stream_url,
+            task.message_thread_id,
+            task.discord_mention,
+            task.discord_auto_publish,
+            task.discord_thread_id,
             0i32, // attempts
             max_attempts,
             next_attempt_at,
@@ -134,6 +149,11 @@ impl NotificationQueueRepository {
                     destination_type as "destination_type!: String",
                     destination_id as "destination_id!: String",
                     webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
                     attempts as "attempts!: i32",
                     max_attempts as "max_attempts!: i32",
                     next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -178,6 +198,11 @@ impl NotificationQueueRepository {
                 destination_type as "destination_type!: String",
                 destination_id as "destination_id!: String",
                 webhook_url as "webhook_url?: String",
+                stream_url as "stream_url?: String",
+                message_thread_id as "message_thread_id?: i32",
+                discord_mention as "discord_mention?: String",
+                discord_auto_publish as "discord_auto_publish!: bool",
+                discord_thread_id as "discord_thread_id?: String",
                 attempts as "attempts!: i32",
                 max_attempts as "max_attempts!: i32",
                 next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -227,6 +252,11 @@ impl NotificationQueueRepository {
                     destination_type as "destination_type!: String",
                     destination_id as "destination_id!: String",
                     webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
                     attempts as "attempts!: i32",
                     max_attempts as "max_attempts!: i32",
                     next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -269,6 +299,11 @@ impl NotificationQueueRepository {
                     destination_type as "destination_type!: String",
                     destination_id as "destination_id!: String",
                     webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
                     attempts as "attempts!: i32",
                     max_attempts as "max_attempts!: i32",
                     next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -288,8 +323,7 @@ impl NotificationQueueRepository {
     }
 
     /// Fetch a task by id.
-    #[allow(dead_code)]
-    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> AppResult<NotificationTask> {
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> AppResult<Option<NotificationTask>> {
         sqlx::query_as!(
             NotificationTask,
             r#"
@@ -303,6 +337,11 @@ impl NotificationQueueRepository {
                     destination_type as "destination_type!: String",
                     destination_id as "destination_id!: String",
                     webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
                     attempts as "attempts!: i32",
                     max_attempts as "max_attempts!: i32",
                     next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
@@ -316,8 +355,341 @@ impl NotificationQueueRepository {
                 "#,
             id
         )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// List a user's tasks that are waiting for a retry ('pending') or gave up ('dead'),
+    /// newest first. `status` narrows the list to one of the two.
+    pub async fn list_for_user(
+        pool: &SqlitePool,
+        user_id: &str,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<NotificationTask>> {
+        sqlx::query_as!(
+            NotificationTask,
+            r#"
+                SELECT
+                    id as "id!: String",
+                    notification_log_id as "notification_log_id?: String",
+                    user_id as "user_id!: String",
+                    notification_type as "notification_type!: String",
+                    content_json as "content_json!: String",
+                    message as "message!: String",
+                    destination_type as "destination_type!: String",
+                    destination_id as "destination_id!: String",
+                    webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
+                    attempts as "attempts!: i32",
+                    max_attempts as "max_attempts!: i32",
+                    next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
+                    expires_at as "expires_at?: chrono::NaiveDateTime",
+                    last_error as "last_error?: String",
+                    status as "status!: String",
+                    created_at as "created_at!: chrono::NaiveDateTime",
+                    updated_at as "updated_at!: chrono::NaiveDateTime"
+                FROM notification_queue
+                WHERE user_id = ?
+                  AND status IN ('pending', 'dead')
+                  AND (? IS NULL OR status = ?)
+                ORDER BY created_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+            user_id,
+            status,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Count the tasks returned by `list_for_user`.
+    pub async fn count_for_user(
+        pool: &SqlitePool,
+        user_id: &str,
+        status: Option<&str>,
+    ) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM notification_queue
+            WHERE user_id = ?
+              AND status IN ('pending', 'dead')
+              AND (? IS NULL OR status = ?)
+            "#,
+            user_id,
+            status,
+            status
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Like `list_for_user`, across all users (instance administration).
+    /// `status` may be any task status.
+    pub async fn list_all(
+        pool: &SqlitePool,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<NotificationTask>> {
+        sqlx::query_as!(
+            NotificationTask,
+            r#"
+                SELECT
+                    id as "id!: String",
+                    notification_log_id as "notification_log_id?: String",
+                    user_id as "user_id!: String",
+                    notification_type as "notification_type!: String",
+                    content_json as "content_json!: String",
+                    message as "message!: String",
+                    destination_type as "destination_type!: String",
+                    destination_id as "destination_id!: String",
+                    webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
+                    attempts as "attempts!: i32",
+                    max_attempts as "max_attempts!: i32",
+                    next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
+                    expires_at as "expires_at?: chrono::NaiveDateTime",
+                    last_error as "last_error?: String",
+                    status as "status!: String",
+                    created_at as "created_at!: chrono::NaiveDateTime",
+                    updated_at as "updated_at!: chrono::NaiveDateTime"
+                FROM notification_queue
+                WHERE ? IS NULL OR status = ?
+                ORDER BY created_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+            status,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Count the tasks returned by `list_all`.
+    pub async fn count_all(pool: &SqlitePool, status: Option<&str>) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM notification_queue
+            WHERE ? IS NULL OR status = ?
+            "#,
+            status,
+            status
+        )
         .fetch_one(pool)
         .await
         .map_err(AppError::Database)
     }
+
+    /// Number of tasks per status, as `(status, count)`
+    pub async fn status_counts(pool: &SqlitePool) -> AppResult<Vec<(String, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT status as "status!: String", COUNT(*) as "count!: i64"
+            FROM notification_queue
+            GROUP BY status
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+    }
+
+    /// Move a dead task back to 'pending' with a fresh attempt budget, due now.
+    /// The TTL is cleared so the worker picks the task up even if it had expired.
+    /// Returns `None` if the task doesn't exist or isn't dead.
+    pub async fn requeue(pool: &SqlitePool, id: &str) -> AppResult<Option<NotificationTask>> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_as!(
+            NotificationTask,
+            r#"
+                UPDATE notification_queue
+                SET status = 'pending', attempts = 0, next_attempt_at = ?, expires_at = NULL,
+                    updated_at = ?
+                WHERE id = ? AND status = 'dead'
+                RETURNING
+                    id as "id!: String",
+                    notification_log_id as "notification_log_id?: String",
+                    user_id as "user_id!: String",
+                    notification_type as "notification_type!: String",
+                    content_json as "content_json!: String",
+                    message as "message!: String",
+                    destination_type as "destination_type!: String",
+                    destination_id as "destination_id!: String",
+                    webhook_url as "webhook_url?: String",
+                    stream_url as "stream_url?: String",
+                    message_thread_id as "message_thread_id?: i32",
+                    discord_mention as "discord_mention?: String",
+                    discord_auto_publish as "discord_auto_publish!: bool",
+                    discord_thread_id as "discord_thread_id?: String",
+                    attempts as "attempts!: i32",
+                    max_attempts as "max_attempts!: i32",
+                    next_attempt_at as "next_attempt_at!: chrono::NaiveDateTime",
+                    expires_at as "expires_at?: chrono::NaiveDateTime",
+                    last_error as "last_error?: String",
+                    status as "status!: String",
+                    created_at as "created_at!: chrono::NaiveDateTime",
+                    updated_at as "updated_at!: chrono::NaiveDateTime"
+                "#,
+            now,
+            now,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Delete pending or dead tasks of a user by id. Tasks being processed are left alone.
+    /// Returns the number of deleted tasks.
+    pub async fn discard(pool: &SqlitePool, user_id: &str, ids: &[String]) -> AppResult<u64> {
+        let mut tx = pool.begin().await.map_err(AppError::Database)?;
+        let mut deleted = 0;
+
+        for id in ids {
+            let result = sqlx::query!(
+                r#"
+                DELETE FROM notification_queue
+                WHERE id = ? AND user_id = ? AND status IN ('pending', 'dead')
+                "#,
+                id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+            deleted += result.rows_affected();
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(deleted)
+    }
+
+    /// Delete a user's finished ('dead' or 'succeeded') tasks last updated before `cutoff`.
+    /// Returns the number of deleted tasks.
+    pub async fn purge_finished_before(
+        pool: &SqlitePool,
+        user_id: &str,
+        cutoff: chrono::NaiveDateTime,
+    ) -> AppResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM notification_queue
+            WHERE user_id = ? AND status IN ('dead', 'succeeded') AND updated_at < ?
+            "#,
+            user_id,
+            cutoff
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Point queued tasks at a recreated webhook. Returns the number of updated tasks.
+    pub async fn replace_webhook_url(
+        pool: &SqlitePool,
+        old_url: &str,
+        new_url: &str,
+    ) -> AppResult<u64> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE notification_queue
+            SET webhook_url = ?, updated_at = ?
+            WHERE webhook_url = ? AND status IN ('pending', 'processing')
+            "#,
+            new_url,
+            now,
+            old_url
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn discard_and_purge_only_touch_own_finished_tasks() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        // Only the columns touched by the cleanup queries
+        sqlx::query(
+            r#"
+            CREATE TABLE notification_queue (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+            INSERT INTO notification_queue (id, user_id, status, updated_at) VALUES
+                ('pending', 'u1', 'pending', '2000-01-01 00:00:00'),
+                ('processing', 'u1', 'processing', '2000-01-01 00:00:00'),
+                ('dead', 'u1', 'dead', '2000-01-01 00:00:00'),
+                ('old-dead', 'u1', 'dead', '2000-01-01 00:00:00'),
+                ('new-dead', 'u1', 'dead', '2100-01-01 00:00:00'),
+                ('old-succeeded', 'u1', 'succeeded', '2000-01-01 00:00:00'),
+                ('other-user', 'u2', 'dead', '2000-01-01 00:00:00');
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let ids = ["pending", "processing", "dead", "other-user"].map(String::from);
+        assert_eq!(
+            NotificationQueueRepository::discard(&pool, "u1", &ids).await?,
+            2
+        );
+
+        let cutoff = chrono::NaiveDate::from_ymd_opt(2050, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            NotificationQueueRepository::purge_finished_before(&pool, "u1", cutoff).await?,
+            2
+        );
+
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM notification_queue ORDER BY id")
+                .fetch_all(&pool)
+                .await?;
+        assert_eq!(remaining, vec!["new-dead", "other-user", "processing"]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,243 @@
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+
+use crate::db::models::Session;
+use crate::error::{AppError, AppResult};
+
+/// `last_used_at` is only written when it is older than this, to avoid a write per request
+const TOUCH_INTERVAL_SECONDS: i64 = 5 * 60;
+
+// ============================================================================
+// Session Repository
+// ============================================================================
+
+pub struct SessionRepository;
+
+impl SessionRepository {
+    /// Store a new session. Expired sessions of the same user are cleaned up on the way.
+    pub async fn create(
+        pool: &SqlitePool,
+        id: &str,
+        user_id: &str,
+        token_hash: &str,
+        expires_at: chrono::NaiveDateTime,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> AppResult<Session> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            "DELETE FROM sessions WHERE user_id = ? AND expires_at <= ?",
+            user_id,
+            now
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO sessions (id, user_id, token_hash, expires_at, user_agent, ip_address, created_at, last_used_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: String",
+                user_id as "user_id!: String",
+                token_hash as "token_hash!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                user_agent as "user_agent?: String",
+                ip_address as "ip_address?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                last_used_at as "last_used_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            user_id,
+            token_hash,
+            expires_at,
+            user_agent,
+            ip_address,
+            now,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Find an unexpired session by the hash of its token
+    pub async fn find_active_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> AppResult<Option<Session>> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            Session,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                token_hash as "token_hash!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                user_agent as "user_agent?: String",
+                ip_address as "ip_address?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                last_used_at as "last_used_at!: chrono::NaiveDateTime"
+            FROM sessions
+            WHERE token_hash = ? AND expires_at > ?
+            "#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Record that a session was used (at most every few minutes)
+    pub async fn touch(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+        let stale_before = now - Duration::seconds(TOUCH_INTERVAL_SECONDS);
+
+        sqlx::query!(
+            "UPDATE sessions SET last_used_at = ? WHERE id = ? AND last_used_at < ?",
+            now,
+            id,
+            stale_before
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Unexpired sessions of a user, most recently used first
+    pub async fn list_active_for_user(pool: &SqlitePool, user_id: &str) -> AppResult<Vec<Session>> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            Session,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                token_hash as "token_hash!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                user_agent as "user_agent?: String",
+                ip_address as "ip_address?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                last_used_at as "last_used_at!: chrono::NaiveDateTime"
+            FROM sessions
+            WHERE user_id = ? AND expires_at > ?
+            ORDER BY last_used_at DESC
+            "#,
+            user_id,
+            now
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Revoke one session of a user. Returns false if there was no such session.
+    pub async fn delete_for_user(pool: &SqlitePool, id: &str, user_id: &str) -> AppResult<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM sessions WHERE id = ? AND user_id = ?",
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke every session of a user except `keep_id`. Returns the number of revoked sessions.
+    pub async fn delete_others(pool: &SqlitePool, user_id: &str, keep_id: &str) -> AppResult<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM sessions WHERE user_id = ? AND id != ?",
+            user_id,
+            keep_id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn revoked_and_expired_sessions_are_not_active() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE sessions (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                token_hash TEXT UNIQUE NOT NULL,
+                expires_at DATETIME NOT NULL,
+                user_agent TEXT,
+                ip_address TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let later = Utc::now().naive_utc() + Duration::hours(1);
+        let earlier = Utc::now().naive_utc() - Duration::hours(1);
+        SessionRepository::create(&pool, "a", "u1", "hash-a", later, Some("ua"), None).await?;
+        SessionRepository::create(&pool, "b", "u1", "hash-b", later, None, None).await?;
+        SessionRepository::create(&pool, "c", "u1", "hash-c", earlier, None, None).await?;
+
+        assert!(
+            SessionRepository::find_active_by_token_hash(&pool, "hash-a")
+                .await?
+                .is_some()
+        );
+        assert!(
+            SessionRepository::find_active_by_token_hash(&pool, "hash-c")
+                .await?
+                .is_none()
+        );
+        assert_eq!(
+            SessionRepository::list_active_for_user(&pool, "u1")
+                .await?
+                .len(),
+            2
+        );
+
+        // Another user can't revoke the session
+        assert!(!SessionRepository::delete_for_user(&pool, "a", "u2").await?);
+
+        assert_eq!(SessionRepository::delete_others(&pool, "u1", "a").await?, 2);
+        assert!(
+            SessionRepository::find_active_by_token_hash(&pool, "hash-b")
+                .await?
+                .is_none()
+        );
+
+        assert!(SessionRepository::delete_for_user(&pool, "a", "u1").await?);
+        assert!(
+            SessionRepository::find_active_by_token_hash(&pool, "hash-a")
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
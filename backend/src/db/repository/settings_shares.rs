@@ -1,9 +1,33 @@
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
-use crate::db::models::SettingsShare;
+use crate::db::models::{SettingsShare, ShareRole};
 use crate::error::{AppError, AppResult};
 
+// Intermediate structure for reading from DB (with String for role)
+pub(super) struct SettingsShareRow {
+    pub(super) id: String,
+    pub(super) owner_user_id: String,
+    pub(super) grantee_user_id: String,
+    pub(super) role: String,
+    pub(super) created_at: chrono::NaiveDateTime,
+    pub(super) updated_at: chrono::NaiveDateTime,
+}
+
+impl From<SettingsShareRow> for SettingsShare {
+    fn from(row: SettingsShareRow) -> Self {
+        SettingsShare {
+            id: row.id,
+            owner_user_id: row.owner_user_id,
+            grantee_user_id: row.grantee_user_id,
+            // Unknown roles fall back to the least privileged one
+            role: ShareRole::from_str(&row.role).unwrap_or(ShareRole::Viewer),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
 // ============================================================================
 // Settings Share Repository
 // ============================================================================
@@ -16,34 +40,36 @@ impl SettingsShareRepository {
         pool: &SqlitePool,
         owner_user_id: &str,
         grantee_user_id: &str,
-        can_manage: bool,
+        role: ShareRole,
     ) -> AppResult<SettingsShare> {
         let id = Uuid::new_v4().to_string();
+        let role = role.as_str();
         let now = chrono::Utc::now().naive_utc();
 
         sqlx::query_as!(
-            SettingsShare,
+            SettingsShareRow,
             r#"
             INSERT INTO settings_shares (
-                id, owner_user_id, grantee_user_id, can_manage, created_at, updated_at
+                id, owner_user_id, grantee_user_id, role, created_at, updated_at
             ) VALUES (?, ?, ?, ?, ?, ?)
             RETURNING
                 id as "id!: String",
                 owner_user_id as "owner_user_id!: String",
                 grantee_user_id as "grantee_user_id!: String",
-                can_manage as "can_manage!: bool",
+                role as "role!: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
             id,
             owner_user_id,
             grantee_user_id,
-            can_manage,
+            role,
             now,
             now
         )
         .fetch_one(pool)
         .await
+        .map(SettingsShare::from)
         .map_err(AppError::Database)
     }
 
@@ -54,13 +80,13 @@ impl SettingsShareRepository {
         grantee_user_id: &str,
     ) -> AppResult<Option<SettingsShare>> {
         sqlx::query_as!(
-            SettingsShare,
+            SettingsShareRow,
             r#"
             SELECT
                 id as "id!: String",
                 owner_user_id as "owner_user_id!: String",
                 grantee_user_id as "grantee_user_id!: String",
-                can_manage as "can_manage!: bool",
+                role as "role!: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM settings_shares
@@ -72,49 +98,52 @@ impl SettingsShareRepository {
         )
         .fetch_optional(pool)
         .await
+        .map(|row| row.map(SettingsShare::from))
         .map_err(AppError::Database)
     }
 
-    /// Update the `can_manage` flag for an existing share.
-    pub async fn update_can_manage(
+    /// Change the role of an existing share.
+    pub async fn update_role(
         pool: &SqlitePool,
         owner_user_id: &str,
         grantee_user_id: &str,
-        can_manage: bool,
-    ) -> AppResult<SettingsShare> {
+        role: ShareRole,
+    ) -> AppResult<Option<SettingsShare>> {
         let now = chrono::Utc::now().naive_utc();
+        let role = role.as_str();
 
         sqlx::query_as!(
-            SettingsShare,
+            SettingsShareRow,
             r#"
             UPDATE settings_shares
-            SET can_manage = ?, updated_at = ?
+            SET role = ?, updated_at = ?
             WHERE owner_user_id = ? AND grantee_user_id = ?
             RETURNING
                 id as "id!: String",
                 owner_user_id as "owner_user_id!: String",
                 grantee_user_id as "grantee_user_id!: String",
-                can_manage as "can_manage!: bool",
+                role as "role!: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
-            can_manage,
+            role,
             now,
             owner_user_id,
             grantee_user_id
         )
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await
+        .map(|row| row.map(SettingsShare::from))
         .map_err(AppError::Database)
     }
 
-    /// Delete a share (revoke access).
+    /// Delete a share (revoke access). Returns whether a share was removed.
     pub async fn delete(
         pool: &SqlitePool,
         owner_user_id: &str,
         grantee_user_id: &str,
-    ) -> AppResult<()> {
-        sqlx::query!(
+    ) -> AppResult<bool> {
+        let result = sqlx::query!(
             "DELETE FROM settings_shares WHERE owner_user_id = ? AND grantee_user_id = ?",
             owner_user_id,
             grantee_user_id
@@ -123,7 +152,7 @@ impl SettingsShareRepository {
         .await
         .map_err(AppError::Database)?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     /// List shares for an owner along with grantee user info.
@@ -138,7 +167,7 @@ impl SettingsShareRepository {
                 s.id as "share_id!: String",
                 s.owner_user_id as "owner_user_id!: String",
                 s.grantee_user_id as "grantee_user_id!: String",
-                s.can_manage as "can_manage!: i64",
+                s.role as "role!: String",
                 s.created_at as "share_created_at!: chrono::NaiveDateTime",
                 s.updated_at as "share_updated_at!: chrono::NaiveDateTime",
                 u.twitch_login as "grantee_login!: String",
@@ -160,7 +189,7 @@ impl SettingsShareRepository {
                 id: r.share_id,
                 owner_user_id: r.owner_user_id,
                 grantee_user_id: r.grantee_user_id,
-                can_manage: r.can_manage != 0,
+                role: ShareRole::from_str(&r.role).unwrap_or(ShareRole::Viewer),
                 created_at: r.share_created_at,
                 updated_at: r.share_updated_at,
             };
@@ -181,7 +210,7 @@ impl SettingsShareRepository {
                 s.id as "share_id!: String",
                 s.owner_user_id as "owner_user_id!: String",
                 s.grantee_user_id as "grantee_user_id!: String",
-                s.can_manage as "can_manage!: i64",
+                s.role as "role!: String",
                 s.created_at as "share_created_at!: chrono::NaiveDateTime",
                 s.updated_at as "share_updated_at!: chrono::NaiveDateTime",
                 u.twitch_login as "owner_login!: String",
@@ -203,7 +232,7 @@ impl SettingsShareRepository {
                 id: r.share_id,
                 owner_user_id: r.owner_user_id,
                 grantee_user_id: r.grantee_user_id,
-                can_manage: r.can_manage != 0,
+                role: ShareRole::from_str(&r.role).unwrap_or(ShareRole::Viewer),
                 created_at: r.share_created_at,
                 updated_at: r.share_updated_at,
             };
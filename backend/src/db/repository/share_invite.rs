@@ -0,0 +1,502 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::settings_shares::SettingsShareRow;
+use crate::db::models::{
+    AcceptedShareInvite, SettingsShare, ShareInvite, SharePermission, ShareRole,
+};
+use crate::error::{AppError, AppResult};
+
+// Intermediate structure for reading from DB (with String for role)
+struct ShareInviteRow {
+    id: String,
+    token_hash: String,
+    owner_user_id: String,
+    created_by: String,
+    role: String,
+    expires_at: chrono::NaiveDateTime,
+    created_at: chrono::NaiveDateTime,
+}
+
+impl From<ShareInviteRow> for ShareInvite {
+    fn from(row: ShareInviteRow) -> Self {
+        ShareInvite {
+            id: row.id,
+            token_hash: row.token_hash,
+            owner_user_id: row.owner_user_id,
+            created_by: row.created_by,
+            // Unknown roles fall back to the least privileged one
+            role: ShareRole::from_str(&row.role).unwrap_or(ShareRole::Viewer),
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ============================================================================
+// Share Invite Repository
+// ============================================================================
+
+pub struct ShareInviteRepository;
+
+impl ShareInviteRepository {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: &str,
+        token_hash: &str,
+        owner_user_id: &str,
+        created_by: &str,
+        role: ShareRole,
+        expires_at: chrono::NaiveDateTime,
+    ) -> AppResult<ShareInvite> {
+        let now = Utc::now().naive_utc();
+        let role = role.as_str();
+
+        sqlx::query_as!(
+            ShareInviteRow,
+            r#"
+            INSERT INTO share_invites (id, token_hash, owner_user_id, created_by, role, expires_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: String",
+                token_hash as "token_hash!: String",
+                owner_user_id as "owner_user_id!: String",
+                created_by as "created_by!: String",
+                role as "role!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            token_hash,
+            owner_user_id,
+            created_by,
+            role,
+            expires_at,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map(ShareInvite::from)
+        .map_err(AppError::Database)
+    }
+
+    /// Invites of an owner that can still be accepted, newest first
+    pub async fn list_active_for_owner(
+        pool: &SqlitePool,
+        owner_user_id: &str,
+    ) -> AppResult<Vec<ShareInvite>> {
+        let now = Utc::now().naive_utc();
+
+        let rows = sqlx::query_as!(
+            ShareInviteRow,
+            r#"
+            SELECT
+                id as "id!: String",
+                token_hash as "token_hash!: String",
+                owner_user_id as "owner_user_id!: String",
+                created_by as "created_by!: String",
+                role as "role!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM share_invites
+            WHERE owner_user_id = ? AND expires_at > ?
+            ORDER BY created_at DESC
+            "#,
+            owner_user_id,
+            now
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(ShareInvite::from).collect())
+    }
+
+    /// Revoke an invite. Returns the deleted invite, or `None` if it does not belong to the owner.
+    pub async fn delete_for_owner(
+        pool: &SqlitePool,
+        id: &str,
+        owner_user_id: &str,
+    ) -> AppResult<Option<ShareInvite>> {
+        sqlx::query_as!(
+            ShareInviteRow,
+            r#"
+            DELETE FROM share_invites
+            WHERE id = ? AND owner_user_id = ?
+            RETURNING
+                id as "id!: String",
+                token_hash as "token_hash!: String",
+                owner_user_id as "owner_user_id!: String",
+                created_by as "created_by!: String",
+                role as "role!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            owner_user_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(ShareInvite::from))
+        .map_err(AppError::Database)
+    }
+
+    /// Remove the pending invites a grantee created for the owner, e.g. after the grantee lost
+    /// the right to manage shares. Returns the number of deleted rows.
+    pub async fn delete_created_by(
+        pool: &SqlitePool,
+        owner_user_id: &str,
+        created_by: &str,
+    ) -> AppResult<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM share_invites WHERE owner_user_id = ? AND created_by = ?",
+            owner_user_id,
+            created_by
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Consume an invite on behalf of `invitee_user_id` and grant its role, in a single
+    /// transaction: a new share is created, or the role of an existing one raised. An invite
+    /// never takes away permissions the invitee already has.
+    /// Returns `None` if the invite is unknown, expired, already used, belongs to the
+    /// invitee themselves, or was created by a grantee who can no longer manage the owner's
+    /// shares (such an invite is used up all the same).
+    pub async fn accept(
+        pool: &SqlitePool,
+        token_hash: &str,
+        invitee_user_id: &str,
+    ) -> AppResult<Option<AcceptedShareInvite>> {
+        let now = Utc::now().naive_utc();
+
+        let mut tx = pool.begin().await.map_err(AppError::Database)?;
+
+        let Some(invite) = sqlx::query_as!(
+            ShareInviteRow,
+            r#"
+            DELETE FROM share_invites
+            WHERE token_hash = ? AND expires_at > ? AND owner_user_id != ?
+            RETURNING
+                id as "id!: String",
+                token_hash as "token_hash!: String",
+                owner_user_id as "owner_user_id!: String",
+                created_by as "created_by!: String",
+                role as "role!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            token_hash,
+            now,
+            invitee_user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .map(ShareInvite::from) else {
+            return Ok(None);
+        };
+
+        if invite.created_by != invite.owner_user_id {
+            let creator_role = sqlx::query_scalar!(
+                r#"
+                SELECT role as "role!: String"
+                FROM settings_shares
+                WHERE owner_user_id = ? AND grantee_user_id = ?
+                "#,
+                invite.owner_user_id,
+                invite.created_by
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+            let creator_can_share = creator_role
+                .and_then(|role| ShareRole::from_str(&role))
+                .is_some_and(|role| role.allows(SharePermission::ManageShares));
+            if !creator_can_share {
+                tx.commit().await.map_err(AppError::Database)?;
+                return Ok(None);
+            }
+        }
+
+        let existing = sqlx::query_as!(
+            SettingsShareRow,
+            r#"
+            SELECT
+                id as "id!: String",
+                owner_user_id as "owner_user_id!: String",
+                grantee_user_id as "grantee_user_id!: String",
+                role as "role!: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM settings_shares
+            WHERE owner_user_id = ? AND grantee_user_id = ?
+            "#,
+            invite.owner_user_id,
+            invitee_user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .map(SettingsShare::from);
+
+        let role = invite.role.as_str();
+        let previous_role = existing.as_ref().map(|share| share.role);
+        let share = match existing {
+            Some(share) if share.role.includes(invite.role) => share,
+            Some(_) => sqlx::query_as!(
+                SettingsShareRow,
+                r#"
+                UPDATE settings_shares
+                SET role = ?, updated_at = ?
+                WHERE owner_user_id = ? AND grantee_user_id = ?
+                RETURNING
+                    id as "id!: String",
+                    owner_user_id as "owner_user_id!: String",
+                    grantee_user_id as "grantee_user_id!: String",
+                    role as "role!: String",
+                    created_at as "created_at!: chrono::NaiveDateTime",
+                    updated_at as "updated_at!: chrono::NaiveDateTime"
+                "#,
+                role,
+                now,
+                invite.owner_user_id,
+                invitee_user_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::Database)?
+            .into(),
+            None => {
+                let id = Uuid::new_v4().to_string();
+                sqlx::query_as!(
+                    SettingsShareRow,
+                    r#"
+                    INSERT INTO settings_shares (
+                        id, owner_user_id, grantee_user_id, role, created_at, updated_at
+                    ) VALUES (?, ?, ?, ?, ?, ?)
+                    RETURNING
+                        id as "id!: String",
+                        owner_user_id as "owner_user_id!: String",
+                        grantee_user_id as "grantee_user_id!: String",
+                        role as "role!: String",
+                        created_at as "created_at!: chrono::NaiveDateTime",
+                        updated_at as "updated_at!: chrono::NaiveDateTime"
+                    "#,
+                    id,
+                    invite.owner_user_id,
+                    invitee_user_id,
+                    role,
+                    now,
+                    now
+                )
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(AppError::Database)?
+                .into()
+            }
+        };
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(Some(AcceptedShareInvite {
+            invite,
+            share,
+            previous_role,
+        }))
+    }
+
+    /// Remove expired invites. Returns the number of deleted rows.
+    pub async fn delete_expired(pool: &SqlitePool) -> AppResult<u64> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query!("DELETE FROM share_invites WHERE expires_at <= ?", now)
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn accept_is_single_use_and_skips_expired_and_own_invites() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE share_invites (
+                id TEXT PRIMARY KEY,
+                token_hash TEXT NOT NULL UNIQUE,
+                owner_user_id TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                role TEXT NOT NULL,
+                expires_at DATETIME NOT NULL,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE settings_shares (
+                id TEXT PRIMARY KEY,
+                owner_user_id TEXT NOT NULL,
+                grantee_user_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let now = Utc::now().naive_utc();
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+
+        ShareInviteRepository::create(
+            &pool,
+            "i1",
+            "h1",
+            "owner",
+            "owner",
+            ShareRole::TemplateEditor,
+            now + Duration::hours(1),
+        )
+        .await
+        .map_err(err)?;
+        ShareInviteRepository::create(
+            &pool,
+            "i2",
+            "h2",
+            "owner",
+            "owner",
+            ShareRole::Viewer,
+            now - Duration::hours(1),
+        )
+        .await
+        .map_err(err)?;
+
+        // The owner can't accept their own invite, and doing so doesn't use it up
+        assert!(ShareInviteRepository::accept(&pool, "h1", "owner")
+            .await
+            .map_err(err)?
+            .is_none());
+
+        let accepted = ShareInviteRepository::accept(&pool, "h1", "guest")
+            .await
+            .map_err(err)?
+            .expect("invite should be accepted");
+        assert_eq!(accepted.invite.owner_user_id, "owner");
+        assert_eq!(accepted.previous_role, None);
+        assert_eq!(accepted.share.grantee_user_id, "guest");
+        assert_eq!(accepted.share.role, ShareRole::TemplateEditor);
+
+        // Single use
+        assert!(ShareInviteRepository::accept(&pool, "h1", "other")
+            .await
+            .map_err(err)?
+            .is_none());
+
+        // A lower role doesn't downgrade the existing share, but the invite is used up
+        ShareInviteRepository::create(
+            &pool,
+            "i3",
+            "h3",
+            "owner",
+            "owner",
+            ShareRole::Viewer,
+            now + Duration::hours(1),
+        )
+        .await
+        .map_err(err)?;
+        let lower = ShareInviteRepository::accept(&pool, "h3", "guest")
+            .await
+            .map_err(err)?
+            .expect("invite should be accepted");
+        assert_eq!(lower.previous_role, Some(ShareRole::TemplateEditor));
+        assert_eq!(lower.share.id, accepted.share.id);
+        assert_eq!(lower.share.role, ShareRole::TemplateEditor);
+        assert_eq!(lower.share.updated_at, accepted.share.updated_at);
+
+        // A higher role raises it
+        ShareInviteRepository::create(
+            &pool,
+            "i3b",
+            "h3b",
+            "owner",
+            "owner",
+            ShareRole::Manager,
+            now + Duration::hours(1),
+        )
+        .await
+        .map_err(err)?;
+        let again = ShareInviteRepository::accept(&pool, "h3b", "guest")
+            .await
+            .map_err(err)?
+            .expect("invite should be accepted");
+        assert_eq!(again.previous_role, Some(ShareRole::TemplateEditor));
+        assert_eq!(again.share.id, accepted.share.id);
+        assert_eq!(again.share.role, ShareRole::Manager);
+
+        // Invites created by a grantee only work while the grantee can manage shares
+        for (id, hash) in [("i4", "h4"), ("i5", "h5"), ("i6", "h6")] {
+            ShareInviteRepository::create(
+                &pool,
+                id,
+                hash,
+                "owner",
+                "guest",
+                ShareRole::Viewer,
+                now + Duration::hours(1),
+            )
+            .await
+            .map_err(err)?;
+        }
+        assert!(ShareInviteRepository::accept(&pool, "h4", "friend")
+            .await
+            .map_err(err)?
+            .is_some());
+        sqlx::query("UPDATE settings_shares SET role = 'viewer' WHERE grantee_user_id = 'guest'")
+            .execute(&pool)
+            .await?;
+        assert!(ShareInviteRepository::accept(&pool, "h5", "friend2")
+            .await
+            .map_err(err)?
+            .is_none());
+        assert_eq!(
+            ShareInviteRepository::delete_created_by(&pool, "owner", "guest")
+                .await
+                .map_err(err)?,
+            1
+        );
+
+        // Expired
+        assert!(ShareInviteRepository::accept(&pool, "h2", "guest")
+            .await
+            .map_err(err)?
+            .is_none());
+        assert!(ShareInviteRepository::list_active_for_owner(&pool, "owner")
+            .await
+            .map_err(err)?
+            .is_empty());
+        assert_eq!(
+            ShareInviteRepository::delete_expired(&pool)
+                .await
+                .map_err(err)?,
+            1
+        );
+
+        Ok(())
+    }
+}
@@ -118,6 +118,41 @@ impl SyncedCalendarRepository {
         Ok(rows)
     }
 
+    /// Find all synced calendar events of a user, soonest first.
+    pub async fn find_by_user_id(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> AppResult<Vec<SyncedCalendarEvent>> {
+        let rows = sqlx::query_as!(
+            SyncedCalendarEvent,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                twitch_segment_id as "twitch_segment_id!: String",
+                discord_integration_id as "discord_integration_id?: String",
+                discord_event_id as "discord_event_id?: String",
+                title as "title!: String",
+                start_time as "start_time!: chrono::NaiveDateTime",
+                end_time as "end_time?: chrono::NaiveDateTime",
+                category_name as "category_name?: String",
+                is_recurring as "is_recurring!: bool",
+                last_synced_at as "last_synced_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM synced_calendar_events
+            WHERE user_id = ?
+            ORDER BY start_time ASC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
     /// Find a single synced calendar event by twitch segment id and discord integration id.
     pub async fn find_by_twitch_segment_and_integration(
         pool: &SqlitePool,
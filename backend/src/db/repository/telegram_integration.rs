@@ -14,6 +14,7 @@ struct RowTelegramIntegration {
     telegram_chat_id: String,
     telegram_chat_title: Option<String>,
     telegram_chat_type: Option<String>,
+    message_thread_id: Option<i32>,
     is_enabled: bool,
     notify_stream_online: bool,
     notify_stream_offline: bool,
@@ -21,6 +22,9 @@ struct RowTelegramIntegration {
     notify_category_change: bool,
     notify_reward_redemption: bool,
     last_telegram_message_id: Option<i32>,
+    disabled_reason: Option<String>,
+    health_status: Option<String>,
+    health_checked_at: Option<chrono::NaiveDateTime>,
     created_at: chrono::NaiveDateTime,
     updated_at: chrono::NaiveDateTime,
 }
@@ -36,6 +40,7 @@ impl From<RowTelegramIntegration> for TelegramIntegration {
                 .telegram_chat_type
                 .as_deref()
                 .and_then(ChatType::from_str),
+            message_thread_id: row.message_thread_id,
             is_enabled: row.is_enabled,
             notify_stream_online: row.notify_stream_online,
             notify_stream_offline: row.notify_stream_offline,
@@ -43,6 +48,9 @@ impl From<RowTelegramIntegration> for TelegramIntegration {
             notify_category_change: row.notify_category_change,
             notify_reward_redemption: row.notify_reward_redemption,
             last_telegram_message_id: row.last_telegram_message_id,
+            disabled_reason: row.disabled_reason,
+            health_status: row.health_status,
+            health_checked_at: row.health_checked_at,
             created_at: row.created_at,
             updated_at: row.updated_at,
         }
@@ -83,17 +91,18 @@ impl TelegramIntegrationRepository {
             r#"
             INSERT INTO telegram_integrations (
                 id, user_id, telegram_chat_id, telegram_chat_title, telegram_chat_type,
-                is_enabled, notify_stream_online, notify_stream_offline,
+                message_thread_id, is_enabled, notify_stream_online, notify_stream_offline,
                 notify_title_change, notify_category_change, notify_reward_redemption,
                 created_at, updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING
                 id as "id!: String",
                 user_id as "user_id!: String",
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -101,6 +110,9 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
@@ -109,6 +121,7 @@ impl TelegramIntegrationRepository {
             integration.telegram_chat_id,
             integration.telegram_chat_title,
             chat_type_str,
+            integration.message_thread_id,
             true,
             true,
             false,
@@ -135,6 +148,7 @@ impl TelegramIntegrationRepository {
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -142,6 +156,9 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM telegram_integrations
@@ -169,6 +186,7 @@ impl TelegramIntegrationRepository {
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -176,6 +194,9 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM telegram_integrations
@@ -204,6 +225,7 @@ impl TelegramIntegrationRepository {
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -211,6 +233,9 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM telegram_integrations
@@ -225,16 +250,25 @@ impl TelegramIntegrationRepository {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
-    pub async fn exists(pool: &SqlitePool, chat_id: &str, user_id: &str) -> AppResult<bool> {
+    /// Check whether the user already has an integration for the chat topic
+    /// (`message_thread_id = None` means no topic / "General").
+    pub async fn exists(
+        pool: &SqlitePool,
+        chat_id: &str,
+        message_thread_id: Option<i32>,
+        user_id: &str,
+    ) -> AppResult<bool> {
         let row = sqlx::query!(
             r#"
             SELECT 1 as "exists!: i64"
             FROM telegram_integrations
             WHERE telegram_chat_id = ? AND user_id = ?
+              AND COALESCE(message_thread_id, 0) = COALESCE(?, 0)
             LIMIT 1
             "#,
             chat_id,
-            user_id
+            user_id,
+            message_thread_id
         )
         .fetch_optional(pool)
         .await
@@ -255,6 +289,12 @@ impl TelegramIntegrationRepository {
         // Bind update values into local variables to avoid creating temporaries
         // that would be borrowed across the query call.
         let is_enabled = update.is_enabled.unwrap_or(current.is_enabled);
+        // Re-enabling clears the reason of an automatic disable
+        let disabled_reason = if is_enabled {
+            None
+        } else {
+            current.disabled_reason
+        };
         let notify_stream_online = update
             .notify_stream_online
             .unwrap_or(current.notify_stream_online);
@@ -277,6 +317,7 @@ impl TelegramIntegrationRepository {
             r#"
             UPDATE telegram_integrations
             SET is_enabled = ?,
+                disabled_reason = ?,
                 notify_stream_online = ?,
                 notify_stream_offline = ?,
                 notify_title_change = ?,
@@ -290,6 +331,7 @@ impl TelegramIntegrationRepository {
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -297,10 +339,14 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             "#,
             is_enabled,
+            disabled_reason,
             notify_stream_online,
             notify_stream_offline,
             notify_title_change,
@@ -333,6 +379,191 @@ impl TelegramIntegrationRepository {
         Ok(())
     }
 
+    /// Disable every integration targeting `chat_id` and record why.
+    /// Returns the number of affected integrations.
+    pub async fn disable_for_chat(
+        pool: &SqlitePool,
+        chat_id: &str,
+        reason: &str,
+    ) -> AppResult<u64> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE telegram_integrations
+            SET is_enabled = 0, disabled_reason = ?, updated_at = ?
+            WHERE telegram_chat_id = ?
+            "#,
+            reason,
+            now,
+            chat_id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Disable an integration automatically and record why
+    pub async fn disable(pool: &SqlitePool, id: &str, reason: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            UPDATE telegram_integrations
+            SET is_enabled = 0, disabled_reason = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Count a permanent delivery failure; returns the length of the current streak
+    pub async fn record_failure(pool: &SqlitePool, id: &str) -> AppResult<i64> {
+        sqlx::query_scalar!(
+            r#"
+            UPDATE telegram_integrations
+            SET consecutive_failures = consecutive_failures + 1
+            WHERE id = ?
+            RETURNING consecutive_failures as "consecutive_failures!: i64"
+            "#,
+            id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// End the failure streak of an integration (after a successful send)
+    pub async fn reset_failures(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE telegram_integrations SET consecutive_failures = 0 WHERE id = ? AND consecutive_failures > 0",
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Store the result of a health probe
+    pub async fn set_health(pool: &SqlitePool, id: &str, status: &str) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            "UPDATE telegram_integrations SET health_status = ?, health_checked_at = ? WHERE id = ?",
+            status,
+            now,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Move every integration, discovered chat and pending queued delivery from a
+    /// group's old chat id to its new supergroup id and record the change in
+    /// `telegram_chat_migrations`, in a single transaction. Nothing is recorded if
+    /// no row referenced the old chat id. Returns the number of migrated integrations.
+    pub async fn migrate_chat(
+        pool: &SqlitePool,
+        old_chat_id: &str,
+        new_chat_id: &str,
+    ) -> AppResult<u64> {
+        let now = Utc::now().naive_utc();
+        let audit_id = Uuid::new_v4().to_string();
+        let chat_type = ChatType::Supergroup.as_str();
+
+        let mut tx = pool.begin().await.map_err(AppError::Database)?;
+
+        // Message ids from the old group are not valid in the supergroup
+        let updated = sqlx::query!(
+            r#"
+            UPDATE telegram_integrations
+            SET telegram_chat_id = ?,
+                telegram_chat_type = ?,
+                last_telegram_message_id = NULL,
+                updated_at = ?
+            WHERE telegram_chat_id = ?
+            "#,
+            new_chat_id,
+            chat_type,
+            now,
+            old_chat_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        let pending_chats = sqlx::query!(
+            r#"
+            UPDATE OR REPLACE telegram_pending_chats
+            SET chat_id = ?, chat_type = ?, updated_at = ?
+            WHERE chat_id = ?
+            "#,
+            new_chat_id,
+            chat_type,
+            now,
+            old_chat_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        let queued = sqlx::query!(
+            r#"
+            UPDATE notification_queue
+            SET destination_id = ?, updated_at = ?
+            WHERE destination_type = 'telegram' AND destination_id = ? AND status = 'pending'
+            "#,
+            new_chat_id,
+            now,
+            old_chat_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected();
+
+        if updated == 0 && pending_chats == 0 && queued == 0 {
+            tx.rollback().await.map_err(AppError::Database)?;
+            return Ok(0);
+        }
+
+        let updated_count = updated as i64;
+        sqlx::query!(
+            r#"
+            INSERT INTO telegram_chat_migrations (id, old_chat_id, new_chat_id, integrations_updated, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            audit_id,
+            old_chat_id,
+            new_chat_id,
+            updated_count,
+            now
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
+
     pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<()> {
         sqlx::query!("DELETE FROM telegram_integrations WHERE id = ?", id)
             .execute(pool)
@@ -355,6 +586,7 @@ impl TelegramIntegrationRepository {
                 telegram_chat_id as "telegram_chat_id!: String",
                 telegram_chat_title as "telegram_chat_title?: String",
                 telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
                 is_enabled as "is_enabled!: bool",
                 notify_stream_online as "notify_stream_online!: bool",
                 notify_stream_offline as "notify_stream_offline!: bool",
@@ -362,6 +594,9 @@ impl TelegramIntegrationRepository {
                 notify_category_change as "notify_category_change!: bool",
                 notify_reward_redemption as "notify_reward_redemption!: bool",
                 last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM telegram_integrations
@@ -376,4 +611,172 @@ impl TelegramIntegrationRepository {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    /// Find all enabled Telegram integrations (for the health probe)
+    pub async fn find_all_enabled(pool: &SqlitePool) -> AppResult<Vec<TelegramIntegration>> {
+        let rows = sqlx::query_as!(
+            RowTelegramIntegration,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                telegram_chat_id as "telegram_chat_id!: String",
+                telegram_chat_title as "telegram_chat_title?: String",
+                telegram_chat_type as "telegram_chat_type?: String",
+                message_thread_id as "message_thread_id?: i32",
+                is_enabled as "is_enabled!: bool",
+                notify_stream_online as "notify_stream_online!: bool",
+                notify_stream_offline as "notify_stream_offline!: bool",
+                notify_title_change as "notify_title_change!: bool",
+                notify_category_change as "notify_category_change!: bool",
+                notify_reward_redemption as "notify_reward_redemption!: bool",
+                last_telegram_message_id as "last_telegram_message_id?: i32",
+                disabled_reason as "disabled_reason?: String",
+                health_status as "health_status?: String",
+                health_checked_at as "health_checked_at?: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM telegram_integrations
+            WHERE is_enabled = ?
+            "#,
+            true
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn failure_streak_counts_and_resets() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        // Only the columns touched by the streak queries
+        sqlx::query(
+            r#"
+            CREATE TABLE telegram_integrations (
+                id TEXT PRIMARY KEY,
+                is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                disabled_reason TEXT,
+                consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                updated_at DATETIME
+            );
+            INSERT INTO telegram_integrations (id) VALUES ('a');
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        assert_eq!(
+            TelegramIntegrationRepository::record_failure(&pool, "a").await?,
+            1
+        );
+        assert_eq!(
+            TelegramIntegrationRepository::record_failure(&pool, "a").await?,
+            2
+        );
+
+        TelegramIntegrationRepository::reset_failures(&pool, "a").await?;
+        assert_eq!(
+            TelegramIntegrationRepository::record_failure(&pool, "a").await?,
+            1
+        );
+
+        TelegramIntegrationRepository::disable(&pool, "a", DISABLED_REASON_TARGET_GONE).await?;
+        let (enabled, reason): (bool, Option<String>) = sqlx::query_as(
+            "SELECT is_enabled, disabled_reason FROM telegram_integrations WHERE id = 'a'",
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert!(!enabled);
+        assert_eq!(reason.as_deref(), Some(DISABLED_REASON_TARGET_GONE));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn migrate_chat_moves_pending_tasks_and_skips_noop() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        // Only the columns touched by the migration queries
+        sqlx::query(
+            r#"
+            CREATE TABLE telegram_integrations (
+                id TEXT PRIMARY KEY,
+                telegram_chat_id TEXT NOT NULL,
+                telegram_chat_type TEXT,
+                last_telegram_message_id INTEGER,
+                updated_at DATETIME
+            );
+            CREATE TABLE telegram_pending_chats (
+                chat_id TEXT PRIMARY KEY,
+                chat_type TEXT,
+                updated_at DATETIME
+            );
+            CREATE TABLE notification_queue (
+                id TEXT PRIMARY KEY,
+                destination_type TEXT NOT NULL,
+                destination_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                updated_at DATETIME
+            );
+            CREATE TABLE telegram_chat_migrations (
+                id TEXT PRIMARY KEY,
+                old_chat_id TEXT NOT NULL,
+                new_chat_id TEXT NOT NULL,
+                integrations_updated INTEGER NOT NULL,
+                created_at DATETIME
+            );
+            INSERT INTO telegram_integrations (id, telegram_chat_id) VALUES ('a', '-1');
+            INSERT INTO notification_queue (id, destination_type, destination_id, status)
+            VALUES ('p', 'telegram', '-1', 'pending'),
+                   ('d', 'telegram', '-1', 'dead'),
+                   ('x', 'discord', '-1', 'pending');
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+        assert_eq!(
+            TelegramIntegrationRepository::migrate_chat(&pool, "-1", "-1001")
+                .await
+                .map_err(err)?,
+            1
+        );
+
+        let moved: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM notification_queue WHERE destination_id = '-1001' ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(moved, vec!["p".to_string()]);
+
+        // Nothing references the old id any more
+        assert_eq!(
+            TelegramIntegrationRepository::migrate_chat(&pool, "-1", "-1001")
+                .await
+                .map_err(err)?,
+            0
+        );
+        let audits: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM telegram_chat_migrations")
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(audits, 1);
+
+        Ok(())
+    }
 }
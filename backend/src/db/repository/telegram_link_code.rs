@@ -0,0 +1,107 @@
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+
+use crate::db::models::TelegramLinkCode;
+use crate::error::{AppError, AppResult};
+
+// ============================================================================
+// Telegram Link Code Repository
+// ============================================================================
+
+pub struct TelegramLinkCodeRepository;
+
+impl TelegramLinkCodeRepository {
+    /// Store a new link code for `user_id`, valid for `ttl_seconds`.
+    pub async fn create(
+        pool: &SqlitePool,
+        code: &str,
+        user_id: &str,
+        created_by: &str,
+        ttl_seconds: i64,
+    ) -> AppResult<TelegramLinkCode> {
+        let now = Utc::now().naive_utc();
+        let expires_at = now + Duration::seconds(ttl_seconds);
+
+        sqlx::query_as!(
+            TelegramLinkCode,
+            r#"
+            INSERT INTO telegram_link_codes (code, user_id, created_by, expires_at, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING
+                code as "code!: String",
+                user_id as "user_id!: String",
+                created_by as "created_by!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            code,
+            user_id,
+            created_by,
+            expires_at,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Look up a code without using it up. Returns `None` if the code is unknown or expired.
+    pub async fn find_valid(pool: &SqlitePool, code: &str) -> AppResult<Option<TelegramLinkCode>> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            TelegramLinkCode,
+            r#"
+            SELECT
+                code as "code!: String",
+                user_id as "user_id!: String",
+                created_by as "created_by!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            FROM telegram_link_codes
+            WHERE code = ? AND expires_at > ?
+            "#,
+            code,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Atomically consume a code. Returns `None` if the code is unknown or expired.
+    pub async fn consume(pool: &SqlitePool, code: &str) -> AppResult<Option<TelegramLinkCode>> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            TelegramLinkCode,
+            r#"
+            DELETE FROM telegram_link_codes
+            WHERE code = ? AND expires_at > ?
+            RETURNING
+                code as "code!: String",
+                user_id as "user_id!: String",
+                created_by as "created_by!: String",
+                expires_at as "expires_at!: chrono::NaiveDateTime",
+                created_at as "created_at!: chrono::NaiveDateTime"
+            "#,
+            code,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Remove expired codes. Returns the number of deleted rows.
+    pub async fn delete_expired(pool: &SqlitePool) -> AppResult<u64> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query!("DELETE FROM telegram_link_codes WHERE expires_at <= ?", now)
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::db::models::{ChatType, TelegramPendingChat, UpsertTelegramPendingChat};
+use crate::error::{AppError, AppResult};
+
+// Intermediate structure for reading from DB (with String for chat_type)
+#[derive(sqlx::FromRow)]
+struct RowTelegramPendingChat {
+    chat_id: String,
+    chat_title: Option<String>,
+    chat_type: String,
+    added_by_telegram_user_id: Option<String>,
+    bot_status: String,
+    created_at: chrono::NaiveDateTime,
+    updated_at: chrono::NaiveDateTime,
+}
+
+impl From<RowTelegramPendingChat> for TelegramPendingChat {
+    fn from(row: RowTelegramPendingChat) -> Self {
+        TelegramPendingChat {
+            chat_id: row.chat_id,
+            chat_title: row.chat_title,
+            chat_type: ChatType::from_str(&row.chat_type),
+            added_by_telegram_user_id: row.added_by_telegram_user_id,
+            bot_status: row.bot_status,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+// ============================================================================
+// Telegram Pending Chat Repository
+// ============================================================================
+
+pub struct TelegramPendingChatRepository;
+
+impl TelegramPendingChatRepository {
+    /// Insert or refresh a discovered chat.
+    pub async fn upsert(pool: &SqlitePool, chat: UpsertTelegramPendingChat) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+        let chat_type = chat.chat_type.as_str();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO telegram_pending_chats (
+                chat_id, chat_title, chat_type, added_by_telegram_user_id, bot_status,
+                created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(chat_id) DO UPDATE SET
+                chat_title = excluded.chat_title,
+                chat_type = excluded.chat_type,
+                added_by_telegram_user_id = excluded.added_by_telegram_user_id,
+                bot_status = excluded.bot_status,
+                updated_at = excluded.updated_at
+            "#,
+            chat.chat_id,
+            chat.chat_title,
+            chat_type,
+            chat.added_by_telegram_user_id,
+            chat.bot_status,
+            now,
+            now
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// List chats the given Telegram user added the bot to.
+    pub async fn find_by_added_by(
+        pool: &SqlitePool,
+        telegram_user_id: &str,
+    ) -> AppResult<Vec<TelegramPendingChat>> {
+        let rows = sqlx::query_as!(
+            RowTelegramPendingChat,
+            r#"
+            SELECT
+                chat_id as "chat_id!: String",
+                chat_title as "chat_title?: String",
+                chat_type as "chat_type!: String",
+                added_by_telegram_user_id as "added_by_telegram_user_id?: String",
+                bot_status as "bot_status!: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM telegram_pending_chats
+            WHERE added_by_telegram_user_id = ?
+            ORDER BY updated_at DESC
+            "#,
+            telegram_user_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Forget a chat (the bot left or was removed).
+    pub async fn delete(pool: &SqlitePool, chat_id: &str) -> AppResult<()> {
+        sqlx::query!(
+            "DELETE FROM telegram_pending_chats WHERE chat_id = ?",
+            chat_id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use chrono::Utc;
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
+use crate::crypto;
 use crate::db::models::*;
 use crate::error::{AppError, AppResult};
 
@@ -12,6 +13,13 @@ use crate::error::{AppError, AppResult};
 
 pub struct UserRepository;
 
+/// Decrypt the token columns of a user row (tokens are encrypted at rest, see `crypto`)
+fn decrypt_tokens(mut user: User) -> AppResult<User> {
+    user.twitch_access_token = crypto::decrypt_token(&user.twitch_access_token)?;
+    user.twitch_refresh_token = crypto::decrypt_token(&user.twitch_refresh_token)?;
+    Ok(user)
+}
+
 impl UserRepository {
     pub async fn find_by_id(pool: &SqlitePool, id: &str) -> AppResult<Option<User>> {
         sqlx::query_as!(
@@ -34,6 +42,9 @@ impl UserRepository {
                 discord_username as "discord_username?: String",
                 discord_avatar_url as "discord_avatar_url?: String",
                 lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM users
@@ -43,7 +54,9 @@ impl UserRepository {
         )
         .fetch_optional(pool)
         .await
-        .map_err(AppError::Database)
+        .map_err(AppError::Database)?
+        .map(decrypt_tokens)
+        .transpose()
     }
 
     pub async fn find_by_twitch_id(pool: &SqlitePool, twitch_id: &str) -> AppResult<Option<User>> {
@@ -67,6 +80,9 @@ impl UserRepository {
                 discord_username as "discord_username?: String",
                 discord_avatar_url as "discord_avatar_url?: String",
                 lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM users
@@ -76,7 +92,9 @@ impl UserRepository {
         )
         .fetch_optional(pool)
         .await
-        .map_err(AppError::Database)
+        .map_err(AppError::Database)?
+        .map(decrypt_tokens)
+        .transpose()
     }
 
     pub async fn find_by_login(pool: &SqlitePool, login: &str) -> AppResult<Option<User>> {
@@ -100,6 +118,9 @@ impl UserRepository {
                 discord_username as "discord_username?: String",
                 discord_avatar_url as "discord_avatar_url?: String",
                 lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM users
@@ -109,7 +130,9 @@ impl UserRepository {
         )
         .fetch_optional(pool)
         .await
-        .map_err(AppError::Database)
+        .map_err(AppError::Database)?
+        .map(decrypt_tokens)
+        .transpose()
     }
 
     /// Search users by twitch login or display name (case-insensitive).
@@ -141,6 +164,9 @@ impl UserRepository {
                 discord_username as "discord_username?: String",
                 discord_avatar_url as "discord_avatar_url?: String",
                 lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM users
@@ -154,7 +180,10 @@ impl UserRepository {
         )
         .fetch_all(pool)
         .await
-        .map_err(AppError::Database)
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(decrypt_tokens)
+        .collect()
     }
 
     pub async fn list_all(pool: &SqlitePool) -> AppResult<Vec<User>> {
@@ -178,6 +207,9 @@ impl UserRepository {
                 discord_username as "discord_username?: String",
                 discord_avatar_url as "discord_avatar_url?: String",
                 lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
                 created_at as "created_at!: chrono::NaiveDateTime",
                 updated_at as "updated_at!: chrono::NaiveDateTime"
             FROM users
@@ -186,7 +218,10 @@ impl UserRepository {
         )
         .fetch_all(pool)
         .await
-        .map_err(AppError::Database)
+        .map_err(AppError::Database)?
+        .into_iter()
+        .map(decrypt_tokens)
+        .collect()
     }
 
     pub async fn update_tokens(
@@ -197,6 +232,8 @@ impl UserRepository {
         token_expires_at: chrono::NaiveDateTime,
     ) -> AppResult<()> {
         let now = Utc::now();
+        let access_token = crypto::encrypt_token(access_token)?;
+        let refresh_token = crypto::encrypt_token(refresh_token)?;
         sqlx::query!(
             r#"
             UPDATE users
@@ -220,6 +257,57 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Rewrite stored tokens that are plaintext or sealed with an older key so every row
+    /// uses the current encryption key. Returns the number of rewritten rows.
+    pub async fn reencrypt_tokens(pool: &SqlitePool) -> AppResult<u64> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: String",
+                twitch_access_token as "twitch_access_token!: String",
+                twitch_refresh_token as "twitch_refresh_token!: String"
+            FROM users
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        let mut updated = 0;
+        for row in rows {
+            if !crypto::token_needs_reencryption(&row.twitch_access_token)
+                && !crypto::token_needs_reencryption(&row.twitch_refresh_token)
+            {
+                continue;
+            }
+
+            let access_token =
+                crypto::encrypt_token(&crypto::decrypt_token(&row.twitch_access_token)?)?;
+            let refresh_token =
+                crypto::encrypt_token(&crypto::decrypt_token(&row.twitch_refresh_token)?)?;
+
+            // Only the token columns change, so `updated_at` is left alone
+            sqlx::query!(
+                r#"
+                UPDATE users
+                SET
+                    twitch_access_token = ?,
+                    twitch_refresh_token = ?
+                WHERE id = ?
+                "#,
+                access_token,
+                refresh_token,
+                row.id
+            )
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+            updated += 1;
+        }
+
+        Ok(updated)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn upsert_by_twitch_id(
         pool: &SqlitePool,
@@ -235,6 +323,8 @@ impl UserRepository {
     ) -> AppResult<User> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
+        let encrypted_access_token = crypto::encrypt_token(twitch_access_token)?;
+        let encrypted_refresh_token = crypto::encrypt_token(twitch_refresh_token)?;
 
         // Check if user exists
         let existing_user = Self::find_by_twitch_id(pool, twitch_id).await?;
@@ -278,6 +368,9 @@ impl UserRepository {
                     discord_username as "discord_username?: String",
                     discord_avatar_url as "discord_avatar_url?: String",
                     lang as "lang?: String",
+                    is_admin as "is_admin!: bool",
+                    suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                    suspended_reason as "suspended_reason?: String",
                     created_at as "created_at!: chrono::NaiveDateTime",
                     updated_at as "updated_at!: chrono::NaiveDateTime"
                 "#,
@@ -323,6 +416,9 @@ impl UserRepository {
                     discord_username as "discord_username?: String",
                     discord_avatar_url as "discord_avatar_url?: String",
                     lang as "lang?: String",
+                    is_admin as "is_admin!: bool",
+                    suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                    suspended_reason as "suspended_reason?: String",
                     created_at as "created_at!: chrono::NaiveDateTime",
                     updated_at as "updated_at!: chrono::NaiveDateTime"
                 "#,
@@ -332,8 +428,8 @@ impl UserRepository {
                 twitch_display_name,
                 twitch_email,
                 twitch_profile_image_url,
-                twitch_access_token,
-                twitch_refresh_token,
+                encrypted_access_token,
+                encrypted_refresh_token,
                 twitch_token_expires_at,
                 None::<String>,
                 None::<String>,
@@ -350,7 +446,7 @@ impl UserRepository {
             .map_err(AppError::Database)?
         };
 
-        Ok(result_user)
+        decrypt_tokens(result_user)
     }
 
     pub async fn set_telegram_info(
@@ -477,6 +573,159 @@ impl UserRepository {
 
         Ok(())
     }
+
+    /// Page through all users (optionally matching `query` by login or display name),
+    /// oldest first
+    pub async fn list_page(
+        pool: &SqlitePool,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<User>> {
+        let pattern = query.map(|q| format!("%{}%", q.to_lowercase()));
+
+        let users = sqlx::query_as!(
+            User,
+            r#"
+            SELECT
+                id as "id!: String",
+                twitch_id as "twitch_id!: String",
+                twitch_login as "twitch_login!: String",
+                twitch_display_name as "twitch_display_name!: String",
+                twitch_email as "twitch_email!: String",
+                twitch_profile_image_url as "twitch_profile_image_url!: String",
+                twitch_access_token as "twitch_access_token!: String",
+                twitch_refresh_token as "twitch_refresh_token!: String",
+                twitch_token_expires_at as "twitch_token_expires_at!: chrono::NaiveDateTime",
+                telegram_user_id as "telegram_user_id?: String",
+                telegram_username as "telegram_username?: String",
+                telegram_photo_url as "telegram_photo_url?: String",
+                discord_user_id as "discord_user_id?: String",
+                discord_username as "discord_username?: String",
+                discord_avatar_url as "discord_avatar_url?: String",
+                lang as "lang?: String",
+                is_admin as "is_admin!: bool",
+                suspended_at as "suspended_at?: chrono::NaiveDateTime",
+                suspended_reason as "suspended_reason?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM users
+            WHERE ? IS NULL OR LOWER(twitch_login) LIKE ? OR LOWER(twitch_display_name) LIKE ?
+            ORDER BY created_at ASC
+            LIMIT ? OFFSET ?
+            "#,
+            pattern,
+            pattern,
+            pattern,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        users.into_iter().map(decrypt_tokens).collect()
+    }
+
+    /// Count the users returned by `list_page`
+    pub async fn count(pool: &SqlitePool, query: Option<&str>) -> AppResult<i64> {
+        let pattern = query.map(|q| format!("%{}%", q.to_lowercase()));
+
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM users
+            WHERE ? IS NULL OR LOWER(twitch_login) LIKE ? OR LOWER(twitch_display_name) LIKE ?
+            "#,
+            pattern,
+            pattern,
+            pattern
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Make exactly the users with the given Twitch ids administrators:
+    /// listed users are granted the role, everyone else loses it.
+    /// Returns the number of users whose role changed.
+    pub async fn sync_admins(pool: &SqlitePool, twitch_ids: &[String]) -> AppResult<u64> {
+        let ids = serde_json::to_string(twitch_ids)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET is_admin = (twitch_id IN (SELECT value FROM json_each(?1)))
+            WHERE is_admin != (twitch_id IN (SELECT value FROM json_each(?1)))
+            "#,
+            ids
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn set_admin(pool: &SqlitePool, id: &str, is_admin: bool) -> AppResult<()> {
+        sqlx::query!("UPDATE users SET is_admin = ? WHERE id = ?", is_admin, id)
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Suspend (`suspended_at` set) or unsuspend (`None`) a user.
+    /// Returns `false` if the user does not exist.
+    pub async fn set_suspension(
+        pool: &SqlitePool,
+        id: &str,
+        suspended_at: Option<chrono::NaiveDateTime>,
+        reason: Option<&str>,
+    ) -> AppResult<bool> {
+        let now = Utc::now().naive_utc();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET suspended_at = ?, suspended_reason = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+            suspended_at,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a user. Rows referencing the user are removed by `ON DELETE CASCADE`; the
+    /// owner's audit trail has no foreign key and is removed in the same transaction
+    /// (the append-only trigger allows it once the user row is gone).
+    /// Returns `false` if the user did not exist.
+    pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<bool> {
+        let mut tx = pool.begin().await.map_err(AppError::Database)?;
+
+        let deleted = sqlx::query!("DELETE FROM users WHERE id = ?", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?
+            .rows_affected();
+
+        sqlx::query!("DELETE FROM audit_events WHERE owner_user_id = ?", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(deleted > 0)
+    }
 }
 
 #[cfg(test)]
@@ -515,6 +764,9 @@ mod tests {
                 discord_username TEXT,
                 discord_avatar_url TEXT,
                 lang TEXT DEFAULT 'ru',
+                is_admin BOOLEAN NOT NULL DEFAULT 0,
+                suspended_at DATETIME,
+                suspended_reason TEXT,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
             );
@@ -560,4 +812,110 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn sync_admins_matches_listed_ids_exactly() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                twitch_id TEXT NOT NULL,
+                is_admin BOOLEAN NOT NULL DEFAULT 0
+            );
+            INSERT INTO users (id, twitch_id, is_admin)
+            VALUES ('a', '100', 0), ('b', '200', 1), ('c', '300', 0);
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+        let admins = |pool: &SqlitePool| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, String>("SELECT id FROM users WHERE is_admin ORDER BY id")
+                    .fetch_all(&pool)
+                    .await
+            }
+        };
+
+        // 'a' and 'c' granted, 'b' is no longer listed
+        let ids = vec!["100".to_string(), "300".to_string(), "999".to_string()];
+        assert_eq!(
+            UserRepository::sync_admins(&pool, &ids)
+                .await
+                .map_err(err)?,
+            3
+        );
+        assert_eq!(admins(&pool).await?, vec!["a".to_string(), "c".to_string()]);
+
+        // Already in sync
+        assert_eq!(
+            UserRepository::sync_admins(&pool, &ids)
+                .await
+                .map_err(err)?,
+            0
+        );
+
+        // An empty list leaves no administrators
+        assert_eq!(
+            UserRepository::sync_admins(&pool, &[]).await.map_err(err)?,
+            2
+        );
+        assert!(admins(&pool).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_removes_audit_trail() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (id TEXT PRIMARY KEY);
+            CREATE TABLE audit_events (
+                id TEXT PRIMARY KEY,
+                actor_user_id TEXT NOT NULL,
+                owner_user_id TEXT NOT NULL,
+                entity TEXT NOT NULL,
+                entity_id TEXT,
+                action TEXT NOT NULL,
+                details TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO users (id) VALUES ('gone'), ('other');
+            INSERT INTO audit_events (id, actor_user_id, owner_user_id, entity, action) VALUES
+                ('a1', 'gone', 'gone', 'settings', 'update'),
+                ('a2', 'gone', 'other', 'settings', 'update');
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(include_str!(
+            "../../../migrations/022_make_audit_events_append_only.sql"
+        ))
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+        assert!(UserRepository::delete(&pool, "gone").await.map_err(err)?);
+        assert!(!UserRepository::delete(&pool, "gone").await.map_err(err)?);
+
+        // Changes the user made to other accounts stay in those accounts' logs
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM audit_events")
+            .fetch_all(&pool)
+            .await?;
+        assert_eq!(remaining, vec!["a2".to_string()]);
+
+        Ok(())
+    }
 }
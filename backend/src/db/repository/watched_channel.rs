@@ -0,0 +1,179 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::models::{CreateWatchedChannel, WatchedChannel};
+use crate::error::{AppError, AppResult};
+
+// ============================================================================
+// Watched Channel Repository
+// ============================================================================
+
+pub struct WatchedChannelRepository;
+
+impl WatchedChannelRepository {
+    /// Start watching a broadcaster for the given user.
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: &str,
+        channel: CreateWatchedChannel,
+    ) -> AppResult<WatchedChannel> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            WatchedChannel,
+            r#"
+            INSERT INTO watched_channels (
+                id,
+                user_id,
+                broadcaster_id,
+                broadcaster_login,
+                broadcaster_display_name,
+                broadcaster_profile_image_url,
+                created_at,
+                updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: String",
+                user_id as "user_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                broadcaster_login as "broadcaster_login!: String",
+                broadcaster_display_name as "broadcaster_display_name!: String",
+                broadcaster_profile_image_url as "broadcaster_profile_image_url?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            user_id,
+            channel.broadcaster_id,
+            channel.broadcaster_login,
+            channel.broadcaster_display_name,
+            channel.broadcaster_profile_image_url,
+            now,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Find a watched channel by id.
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> AppResult<Option<WatchedChannel>> {
+        sqlx::query_as!(
+            WatchedChannel,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                broadcaster_login as "broadcaster_login!: String",
+                broadcaster_display_name as "broadcaster_display_name!: String",
+                broadcaster_profile_image_url as "broadcaster_profile_image_url?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM watched_channels
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// List channels watched by a user.
+    pub async fn find_by_user_id(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> AppResult<Vec<WatchedChannel>> {
+        sqlx::query_as!(
+            WatchedChannel,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                broadcaster_login as "broadcaster_login!: String",
+                broadcaster_display_name as "broadcaster_display_name!: String",
+                broadcaster_profile_image_url as "broadcaster_profile_image_url?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM watched_channels
+            WHERE user_id = ?
+            ORDER BY broadcaster_login ASC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// List every watch entry for a broadcaster (used to fan out EventSub events).
+    pub async fn find_by_broadcaster_id(
+        pool: &SqlitePool,
+        broadcaster_id: &str,
+    ) -> AppResult<Vec<WatchedChannel>> {
+        sqlx::query_as!(
+            WatchedChannel,
+            r#"
+            SELECT
+                id as "id!: String",
+                user_id as "user_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                broadcaster_login as "broadcaster_login!: String",
+                broadcaster_display_name as "broadcaster_display_name!: String",
+                broadcaster_profile_image_url as "broadcaster_profile_image_url?: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM watched_channels
+            WHERE broadcaster_id = ?
+            "#,
+            broadcaster_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Distinct broadcaster ids watched by at least one user.
+    pub async fn list_broadcaster_ids(pool: &SqlitePool) -> AppResult<Vec<String>> {
+        let rows = sqlx::query!(
+            r#"SELECT DISTINCT broadcaster_id as "broadcaster_id!: String" FROM watched_channels"#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|r| r.broadcaster_id).collect())
+    }
+
+    /// Check whether the user already watches the broadcaster.
+    pub async fn exists(pool: &SqlitePool, user_id: &str, broadcaster_id: &str) -> AppResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!: i64" FROM watched_channels
+            WHERE user_id = ? AND broadcaster_id = ?
+            "#,
+            user_id,
+            broadcaster_id
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.count > 0)
+    }
+
+    /// Stop watching a channel.
+    pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        sqlx::query!("DELETE FROM watched_channels WHERE id = ?", id)
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::models::WatchedChannelSubscription;
+use crate::error::{AppError, AppResult};
+
+// ============================================================================
+// Watched Channel Subscription Repository
+// ============================================================================
+
+pub struct WatchedChannelSubscriptionRepository;
+
+impl WatchedChannelSubscriptionRepository {
+    /// Record an EventSub subscription created for a watched broadcaster.
+    pub async fn create(
+        pool: &SqlitePool,
+        broadcaster_id: &str,
+        twitch_subscription_id: &str,
+        subscription_type: &str,
+        status: &str,
+    ) -> AppResult<WatchedChannelSubscription> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().naive_utc();
+
+        sqlx::query_as!(
+            WatchedChannelSubscription,
+            r#"
+            INSERT INTO watched_channel_subscriptions (
+                id,
+                twitch_subscription_id,
+                broadcaster_id,
+                subscription_type,
+                status,
+                created_at,
+                updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                id as "id!: String",
+                twitch_subscription_id as "twitch_subscription_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                subscription_type as "subscription_type!: String",
+                status as "status!: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            "#,
+            id,
+            twitch_subscription_id,
+            broadcaster_id,
+            subscription_type,
+            status,
+            now,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// List all watched-channel subscriptions.
+    pub async fn list_all(pool: &SqlitePool) -> AppResult<Vec<WatchedChannelSubscription>> {
+        sqlx::query_as!(
+            WatchedChannelSubscription,
+            r#"
+            SELECT
+                id as "id!: String",
+                twitch_subscription_id as "twitch_subscription_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                subscription_type as "subscription_type!: String",
+                status as "status!: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM watched_channel_subscriptions
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Broadcaster ids that have watched-channel subscriptions and have since registered.
+    pub async fn list_registered_broadcaster_ids(pool: &SqlitePool) -> AppResult<Vec<String>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT broadcaster_id as "broadcaster_id!: String"
+            FROM watched_channel_subscriptions
+            WHERE broadcaster_id IN (SELECT twitch_id FROM users)
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Find a subscription by Twitch subscription id
+    pub async fn find_by_twitch_subscription_id(
+        pool: &SqlitePool,
+        twitch_subscription_id: &str,
+    ) -> AppResult<Option<WatchedChannelSubscription>> {
+        sqlx::query_as!(
+            WatchedChannelSubscription,
+            r#"
+            SELECT
+                id as "id!: String",
+                twitch_subscription_id as "twitch_subscription_id!: String",
+                broadcaster_id as "broadcaster_id!: String",
+                subscription_type as "subscription_type!: String",
+                status as "status!: String",
+                created_at as "created_at!: chrono::NaiveDateTime",
+                updated_at as "updated_at!: chrono::NaiveDateTime"
+            FROM watched_channel_subscriptions
+            WHERE twitch_subscription_id = ?
+            "#,
+            twitch_subscription_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Update subscription status
+    pub async fn update_status(
+        pool: &SqlitePool,
+        twitch_subscription_id: &str,
+        new_status: &str,
+    ) -> AppResult<()> {
+        let now = Utc::now().naive_utc();
+
+        sqlx::query!(
+            r#"
+            UPDATE watched_channel_subscriptions
+            SET status = ?, updated_at = ?
+            WHERE twitch_subscription_id = ?
+            "#,
+            new_status,
+            now,
+            twitch_subscription_id
+        )
+        .execute(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Number of subscriptions per status, as `(status, count)`
+    pub async fn status_counts(pool: &SqlitePool) -> AppResult<Vec<(String, i64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT status as "status!: String", COUNT(*) as "count!: i64"
+            FROM watched_channel_subscriptions
+            GROUP BY status
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+    }
+
+    /// Delete a subscription row
+    pub async fn delete(pool: &SqlitePool, id: &str) -> AppResult<()> {
+        sqlx::query!("DELETE FROM watched_channel_subscriptions WHERE id = ?", id)
+            .execute(pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn lists_broadcasters_that_registered() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                twitch_id TEXT NOT NULL UNIQUE
+            );
+            CREATE TABLE watched_channel_subscriptions (
+                id TEXT PRIMARY KEY,
+                twitch_subscription_id TEXT NOT NULL UNIQUE,
+                broadcaster_id TEXT NOT NULL,
+                subscription_type TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+            INSERT INTO users (id, twitch_id) VALUES ('u1', 'registered');
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let err = |e| anyhow::anyhow!(format!("{:?}", e));
+
+        for (twitch_id, broadcaster, sub_type) in [
+            ("t1", "registered", "stream.online"),
+            ("t2", "registered", "stream.offline"),
+            ("t3", "watched", "stream.online"),
+        ] {
+            WatchedChannelSubscriptionRepository::create(
+                &pool,
+                broadcaster,
+                twitch_id,
+                sub_type,
+                "enabled",
+            )
+            .await
+            .map_err(err)?;
+        }
+
+        assert_eq!(
+            WatchedChannelSubscriptionRepository::list_registered_broadcaster_ids(&pool)
+                .await
+                .map_err(err)?,
+            vec!["registered".to_string()]
+        );
+
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use axum::{
     Json,
 };
 use serde::Serialize;
+use std::time::Duration;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -41,10 +42,10 @@ pub enum AppError {
     TwitchApi(String),
 
     #[error("Telegram error: {0}")]
-    Telegram(String),
+    Telegram(TelegramError),
 
     #[error("Discord error: {0}")]
-    Discord(String),
+    Discord(DiscordError),
 
     #[error("Configuration error: {0}")]
     Config(String),
@@ -56,6 +57,184 @@ pub enum AppError {
     ServiceUnavailable(String),
 }
 
+/// How a failed notification delivery should be handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryErrorKind {
+    /// Rate limited; retry once `retry_after` (if known) has passed
+    RateLimited { retry_after: Option<Duration> },
+    /// Network failure or server-side error; retry with backoff
+    Transient,
+    /// Invalid bot token or missing access/permissions in the target
+    PermissionDenied,
+    /// The chat, channel or webhook no longer exists, or the bot was removed from it
+    TargetGone,
+    /// Rejected for any other reason (invalid content, ...); retrying won't help
+    Rejected,
+}
+
+impl DeliveryErrorKind {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Transient)
+    }
+}
+
+/// Failure talking to the Telegram Bot API
+#[derive(Debug)]
+pub struct TelegramError {
+    pub kind: DeliveryErrorKind,
+    pub message: String,
+}
+
+impl TelegramError {
+    /// Classify a failed Bot API request; `context` describes what was attempted
+    pub fn request(context: &str, error: &teloxide::RequestError) -> Self {
+        use teloxide::{ApiError, RequestError};
+
+        let kind = match error {
+            RequestError::RetryAfter(retry_after) => DeliveryErrorKind::RateLimited {
+                retry_after: Some(*retry_after),
+            },
+            RequestError::Network(_) | RequestError::Io(_) | RequestError::InvalidJson { .. } => {
+                DeliveryErrorKind::Transient
+            }
+            RequestError::Api(
+                ApiError::BotBlocked
+                | ApiError::BotKicked
+                | ApiError::BotKickedFromSupergroup
+                | ApiError::ChatNotFound
+                | ApiError::GroupDeactivated
+                | ApiError::UserDeactivated,
+            ) => DeliveryErrorKind::TargetGone,
+            // `NotFound` is teloxide's name for "Unauthorized" (invalid bot token)
+            RequestError::Api(
+                ApiError::NotFound
+                | ApiError::NotEnoughRightsToPostMessages
+                | ApiError::CantInitiateConversation,
+            ) => DeliveryErrorKind::PermissionDenied,
+            // Descriptions teloxide has no variant for
+            RequestError::Api(ApiError::Unknown(description)) => {
+                if description.starts_with("Forbidden: bot was kicked")
+                    || description.starts_with("Forbidden: bot is not a member")
+                {
+                    DeliveryErrorKind::TargetGone
+                } else if description.starts_with("Forbidden") {
+                    DeliveryErrorKind::PermissionDenied
+                } else {
+                    DeliveryErrorKind::Rejected
+                }
+            }
+            _ => DeliveryErrorKind::Rejected,
+        };
+
+        Self {
+            kind,
+            message: format!("{}: {}", context, error),
+        }
+    }
+}
+
+impl std::fmt::Display for TelegramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<TelegramError> for AppError {
+    fn from(error: TelegramError) -> Self {
+        AppError::Telegram(error)
+    }
+}
+
+/// Failure talking to the Discord API
+#[derive(Debug)]
+pub struct DiscordError {
+    /// HTTP status of Discord's response; `None` when the failure happened locally
+    pub status: Option<u16>,
+    /// Discord's JSON error code (e.g. 10015 Unknown Webhook)
+    pub code: Option<u64>,
+    /// Wait requested by a 429 response
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl DiscordError {
+    /// Error response from the Discord API
+    pub fn api(status: u16, body: &str) -> Self {
+        let json = serde_json::from_str::<serde_json::Value>(body).ok();
+        let code = json.as_ref().and_then(|j| j.get("code")?.as_u64());
+        let retry_after = json
+            .as_ref()
+            .and_then(|j| j.get("retry_after")?.as_f64())
+            .map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        let message = json
+            .as_ref()
+            .and_then(|j| j.get("message")?.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| body.to_string());
+
+        Self {
+            status: Some(status),
+            code,
+            retry_after,
+            message,
+        }
+    }
+
+    /// Request not sent because its rate limit bucket resets too far in the future
+    pub fn rate_limited(
+        key: &crate::services::discord_rate_limit::RouteKey,
+        wait: Duration,
+    ) -> Self {
+        Self {
+            status: Some(429),
+            code: None,
+            retry_after: Some(wait),
+            message: format!(
+                "Rate limit for {} ({}) resets in {}s",
+                key.route,
+                key.major,
+                wait.as_secs()
+            ),
+        }
+    }
+
+    pub fn kind(&self) -> DeliveryErrorKind {
+        match self.status {
+            Some(429) => DeliveryErrorKind::RateLimited {
+                retry_after: self.retry_after,
+            },
+            Some(status) if status >= 500 => DeliveryErrorKind::Transient,
+            // Unknown Channel / Guild / Webhook
+            Some(404) => DeliveryErrorKind::TargetGone,
+            // Invalid token, Missing Access, Missing Permissions
+            Some(401 | 403) => DeliveryErrorKind::PermissionDenied,
+            _ => DeliveryErrorKind::Rejected,
+        }
+    }
+}
+
+impl std::fmt::Display for DiscordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.status, self.code) {
+            (Some(status), Some(code)) => {
+                write!(
+                    f,
+                    "Discord API error ({}, code {}): {}",
+                    status, code, self.message
+                )
+            }
+            (Some(status), None) => write!(f, "Discord API error ({}): {}", status, self.message),
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+impl From<DiscordError> for AppError {
+    fn from(error: DiscordError) -> Self {
+        AppError::Discord(error)
+    }
+}
+
 #[derive(Serialize)]
 struct ErrorResponse {
     error: ErrorBody,
@@ -115,13 +294,13 @@ impl IntoResponse for AppError {
                 tracing::error!("Twitch API error: {}", msg);
                 (StatusCode::BAD_GATEWAY, "TWITCH_API_ERROR", msg.clone())
             }
-            AppError::Telegram(msg) => {
-                tracing::error!("Telegram error: {}", msg);
-                (StatusCode::BAD_GATEWAY, "TELEGRAM_ERROR", msg.clone())
+            AppError::Telegram(e) => {
+                tracing::error!("Telegram error: {}", e);
+                (StatusCode::BAD_GATEWAY, "TELEGRAM_ERROR", e.to_string())
             }
-            AppError::Discord(msg) => {
-                tracing::error!("Discord error: {}", msg);
-                (StatusCode::BAD_GATEWAY, "DISCORD_ERROR", msg.clone())
+            AppError::Discord(e) => {
+                tracing::error!("Discord error: {}", e);
+                (StatusCode::BAD_GATEWAY, "DISCORD_ERROR", e.to_string())
             }
             AppError::Config(msg) => {
                 tracing::error!("Configuration error: {}", msg);
@@ -159,6 +338,36 @@ impl IntoResponse for AppError {
 }
 
 impl AppError {
+    /// Telegram failure that is not a Bot API error (invalid ids, setup, ...)
+    pub fn telegram(message: impl Into<String>) -> Self {
+        AppError::Telegram(TelegramError {
+            kind: DeliveryErrorKind::Rejected,
+            message: message.into(),
+        })
+    }
+
+    /// Discord failure without an API response (client setup, parsing, ...)
+    pub fn discord(message: impl Into<String>) -> Self {
+        AppError::Discord(DiscordError {
+            status: None,
+            code: None,
+            retry_after: None,
+            message: message.into(),
+        })
+    }
+
+    /// Classify a failed notification delivery
+    pub fn delivery_kind(&self) -> DeliveryErrorKind {
+        match self {
+            AppError::Telegram(e) => e.kind,
+            AppError::Discord(e) => e.kind(),
+            AppError::Request(_) => DeliveryErrorKind::Transient,
+            AppError::RateLimited => DeliveryErrorKind::RateLimited { retry_after: None },
+            AppError::ServiceUnavailable(_) => DeliveryErrorKind::Transient,
+            _ => DeliveryErrorKind::Rejected,
+        }
+    }
+
     pub fn with_details(self, details: serde_json::Value) -> AppErrorWithDetails {
         AppErrorWithDetails {
             error: self,
@@ -223,3 +432,98 @@ impl From<AppError> for AppErrorWithDetails {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::{ApiError, RequestError};
+
+    #[test]
+    fn test_discord_error_kinds() {
+        let rate_limited = DiscordError::api(
+            429,
+            r#"{"message": "You are being rate limited.", "retry_after": 2.5, "global": false}"#,
+        );
+        assert_eq!(
+            rate_limited.kind(),
+            DeliveryErrorKind::RateLimited {
+                retry_after: Some(Duration::from_millis(2500))
+            }
+        );
+
+        assert_eq!(
+            DiscordError::api(502, "Bad Gateway").kind(),
+            DeliveryErrorKind::Transient
+        );
+        assert_eq!(
+            DiscordError::api(403, r#"{"message": "Missing Permissions", "code": 50013}"#).kind(),
+            DeliveryErrorKind::PermissionDenied
+        );
+
+        let gone = DiscordError::api(404, r#"{"message": "Unknown Channel", "code": 10003}"#);
+        assert_eq!(gone.kind(), DeliveryErrorKind::TargetGone);
+        assert_eq!(gone.code, Some(10003));
+        assert_eq!(gone.message, "Unknown Channel");
+
+        assert_eq!(
+            DiscordError::api(400, r#"{"message": "Invalid Form Body", "code": 50035}"#).kind(),
+            DeliveryErrorKind::Rejected
+        );
+    }
+
+    #[test]
+    fn test_telegram_error_kinds() {
+        let kind = |e: RequestError| TelegramError::request("Failed to send message", &e).kind;
+
+        assert_eq!(
+            kind(RequestError::RetryAfter(Duration::from_secs(7))),
+            DeliveryErrorKind::RateLimited {
+                retry_after: Some(Duration::from_secs(7))
+            }
+        );
+        assert_eq!(
+            kind(RequestError::Io(std::io::Error::other("connection reset"))),
+            DeliveryErrorKind::Transient
+        );
+        assert_eq!(
+            kind(RequestError::Api(ApiError::NotEnoughRightsToPostMessages)),
+            DeliveryErrorKind::PermissionDenied
+        );
+        assert_eq!(
+            kind(RequestError::Api(ApiError::BotKicked)),
+            DeliveryErrorKind::TargetGone
+        );
+        assert_eq!(
+            kind(RequestError::Api(ApiError::Unknown(
+                "Forbidden: bot was kicked from the channel chat".to_string()
+            ))),
+            DeliveryErrorKind::TargetGone
+        );
+        assert_eq!(
+            kind(RequestError::Api(ApiError::MessageIsTooLong)),
+            DeliveryErrorKind::Rejected
+        );
+    }
+
+    #[test]
+    fn test_only_rate_limits_and_transient_errors_are_retryable() {
+        assert!(AppError::from(DiscordError::api(429, ""))
+            .delivery_kind()
+            .is_retryable());
+        assert!(AppError::from(DiscordError::api(503, ""))
+            .delivery_kind()
+            .is_retryable());
+        assert!(!AppError::from(DiscordError::api(403, ""))
+            .delivery_kind()
+            .is_retryable());
+        assert!(!AppError::from(DiscordError::api(404, ""))
+            .delivery_kind()
+            .is_retryable());
+        assert!(!AppError::discord("Failed to parse message response")
+            .delivery_kind()
+            .is_retryable());
+        assert!(!AppError::telegram("Invalid chat_id")
+            .delivery_kind()
+            .is_retryable());
+    }
+}
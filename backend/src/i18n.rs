@@ -33,6 +33,9 @@ const RU_JSON: &str = r#"
   "validation.chat_id.group_invalid": "Неверный Chat ID для группы. Ожидается отрицательное число (например, -123456789).",
   "validation.chat_id.supergroup_invalid": "Неверный Chat ID для супергруппы/канала. Ожидается формат -100<цифры> (например, -1001234567890).",
   "validation.telegram_bot_not_configured": "Telegram bot не настроен на сервере, невозможно проверить права администратора. Пожалуйста, настройте бота.",
+  "validation.telegram_chat_not_forum": "В этом чате не включены темы (форум). Укажите тему только для супергрупп с темами.",
+  "validation.telegram_topic_not_found": "Тема не найдена в этом чате или бот не может в неё писать",
+  "validation.discord_role_not_found": "Роль для упоминания не найдена на этом сервере",
   "validation.must_be_admin": "Вы должны быть администратором в этом чате, чтобы добавить интеграцию",
   "validation.admin_check_failed": "Не удалось проверить права администратора. Убедитесь, что бот добавлен в чат и повторите попытку.",
   "errors.no_share_manage": "У вас нет прав управлять интеграциями для этого пользователя",
@@ -61,6 +64,55 @@ const RU_JSON: &str = r#"
   "discord.unlinked": "Discord успешно отключён",
   "auth.token_refreshed": "Токен успешно обновлён",
   "error.unsupported_language": "Неподдерживаемый язык: {lang}",
+  "telegram_bot.not_linked": "Этот чат не привязан ни к одному каналу. Сгенерируйте код в веб-интерфейсе и отправьте /link <код>.",
+  "chat_commands.status_live": "🔴 {streamer} в эфире\n{title}\n🎮 {game}\n{url}",
+  "chat_commands.status_offline": "⚫ {streamer} сейчас не в эфире",
+  "telegram_bot.muted_suffix": "(уведомления приостановлены)",
+  "chat_commands.schedule_header": "📅 Расписание {streamer}:",
+  "chat_commands.schedule_empty": "📅 У {streamer} нет запланированных стримов",
+  "telegram_bot.link_usage": "Использование: /link <код>. Код можно получить в веб-интерфейсе.",
+  "telegram_bot.link_invalid_code": "Код недействителен или истёк. Сгенерируйте новый в веб-интерфейсе.",
+  "telegram_bot.linked": "✅ Чат привязан к каналу {streamer}",
+  "telegram_bot.already_linked": "Этот чат уже привязан к каналу {streamer}",
+  "telegram_bot.must_be_admin": "Эту команду могут использовать только администраторы чата",
+  "telegram_bot.muted": "🔕 Уведомления в этом чате приостановлены",
+  "telegram_bot.unmuted": "🔔 Уведомления в этом чате возобновлены",
+  "chat_commands.error": "Не удалось выполнить команду. Попробуйте позже.",
+  "discord_bot.not_linked": "К этому каналу не привязано ни одной интеграции.",
+  "discord_bot.must_manage": "Нужны права «Управление сервером» или «Администратор».",
+  "discord_bot.test_sent": "Тестовое уведомление отправлено.",
+  "discord_bot.toggled_on": "🔔 Уведомления «{event}» включены.",
+  "discord_bot.toggled_off": "🔕 Уведомления «{event}» выключены.",
+  "discord_bot.unknown_command": "Неизвестная команда.",
+  "integration_health.telegram_chat": "Telegram-чат «{name}»",
+  "integration_health.discord_channel": "Discord-канал #{name}",
+  "integration_health.reason.target_gone": "чат или канал больше не существует, либо бот был из него удалён",
+  "integration_health.reason.permission_denied": "у бота нет прав на отправку сообщений",
+  "integration_health.disabled": "⚠️ Уведомления в {destination} отключены после {count} неудачных отправок подряд: {reason}.\n\nИсправьте проблему и снова включите интеграцию в веб-интерфейсе — сначала будет отправлено тестовое сообщение.",
+  "validation.reenable_test_failed": "Тестовое уведомление не отправлено, интеграция остаётся отключённой: {err}",
+  "not_found.queue_task": "Задача очереди не найдена",
+  "validation.queue_status_invalid": "Статус должен быть «pending» или «dead»",
+  "validation.queue_task_not_dead": "Повторно поставить в очередь можно только задачи со статусом «dead»",
+  "validation.queue_purge_age_invalid": "Возраст должен быть от 1 до 3650 дней",
+  "not_found.session": "Сеанс не найден",
+  "auth.session_revoked": "Сеанс завершён",
+  "auth.sessions_revoked": "Остальные сеансы завершены",
+  "not_found.share": "Доступ не найден",
+  "validation.share_role_required": "Укажите роль: viewer, template_editor, integration_manager или manager",
+  "not_found.share_invite": "Приглашение не найдено",
+  "validation.invite_ttl_invalid": "Срок действия приглашения должен быть от 1 часа до 30 дней",
+  "account.deleted": "Аккаунт и все его данные удалены",
+  "not_found.api_token": "API-токен не найден",
+  "auth.api_token_revoked": "API-токен отозван",
+  "admin.resynced": "Подписки EventSub синхронизированы",
+  "admin.suspended": "Аккаунт приостановлен",
+  "admin.unsuspended": "Аккаунт восстановлен",
+  "validation.cannot_suspend_admin": "Нельзя приостановить аккаунт администратора",
+  "validation.admin_queue_status_invalid": "Статус должен быть «pending», «processing», «succeeded» или «dead»",
+  "validation.api_token_name_invalid": "Укажите название токена (не длиннее 100 символов)",
+  "validation.api_token_scopes_invalid": "Укажите права токена: read:notifications, write:settings, manage:integrations",
+  "validation.api_token_ttl_invalid": "Срок действия токена должен быть от 1 до 365 дней",
+  "validation.api_token_limit": "Достигнуто максимальное количество API-токенов",
   "app.name": "Уведомления Twitch"
 }
 "#;
@@ -74,6 +126,9 @@ const EN_JSON: &str = r#"
   "validation.chat_id.group_invalid": "Invalid chat ID for a group. Expected a negative number (e.g., -123456789).",
   "validation.chat_id.supergroup_invalid": "Invalid chat ID for a supergroup/channel. Expected format -100<digits> (e.g., -1001234567890).",
   "validation.telegram_bot_not_configured": "Telegram bot is not configured on the server; cannot check admin permissions. Please configure the bot.",
+  "validation.telegram_chat_not_forum": "Topics are not enabled in this chat. A topic can only be set for forum supergroups.",
+  "validation.telegram_topic_not_found": "Topic not found in this chat or the bot cannot post to it",
+  "validation.discord_role_not_found": "The role to mention was not found in this server",
   "validation.must_be_admin": "You must be an administrator in this chat to add an integration",
   "validation.admin_check_failed": "Failed to verify admin permissions. Ensure the bot is added to the chat and try again.",
   "errors.no_share_manage": "You do not have permission to manage integrations for this user",
@@ -102,6 +157,55 @@ const EN_JSON: &str = r#"
   "discord.unlinked": "Discord unlinked",
   "auth.token_refreshed": "Token refreshed successfully",
   "error.unsupported_language": "Unsupported language: {lang}",
+  "telegram_bot.not_linked": "This chat is not linked to any channel. Generate a code in the web UI and send /link <code>.",
+  "chat_commands.status_live": "🔴 {streamer} is live\n{title}\n🎮 {game}\n{url}",
+  "chat_commands.status_offline": "⚫ {streamer} is offline",
+  "telegram_bot.muted_suffix": "(notifications paused)",
+  "chat_commands.schedule_header": "📅 {streamer}'s schedule:",
+  "chat_commands.schedule_empty": "📅 {streamer} has no scheduled streams",
+  "telegram_bot.link_usage": "Usage: /link <code>. You can get a code in the web UI.",
+  "telegram_bot.link_invalid_code": "The code is invalid or expired. Generate a new one in the web UI.",
+  "telegram_bot.linked": "✅ Chat linked to {streamer}'s channel",
+  "telegram_bot.already_linked": "This chat is already linked to {streamer}'s channel",
+  "telegram_bot.must_be_admin": "Only chat administrators can use this command",
+  "telegram_bot.muted": "🔕 Notifications in this chat are paused",
+  "telegram_bot.unmuted": "🔔 Notifications in this chat are resumed",
+  "chat_commands.error": "Failed to run the command. Please try again later.",
+  "discord_bot.not_linked": "No integrations are linked to this channel.",
+  "discord_bot.must_manage": "You need the Manage Server or Administrator permission.",
+  "discord_bot.test_sent": "Test notification sent.",
+  "discord_bot.toggled_on": "🔔 \"{event}\" notifications enabled.",
+  "discord_bot.toggled_off": "🔕 \"{event}\" notifications disabled.",
+  "discord_bot.unknown_command": "Unknown command.",
+  "integration_health.telegram_chat": "Telegram chat \"{name}\"",
+  "integration_health.discord_channel": "Discord channel #{name}",
+  "integration_health.reason.target_gone": "the chat or channel no longer exists, or the bot was removed from it",
+  "integration_health.reason.permission_denied": "the bot has no permission to post there",
+  "integration_health.disabled": "⚠️ Notifications to {destination} were turned off after {count} failed deliveries in a row: {reason}.\n\nFix the problem and turn the integration back on in the web UI — a test message is sent first.",
+  "validation.reenable_test_failed": "The test notification failed, so the integration stays disabled: {err}",
+  "not_found.queue_task": "Queue task not found",
+  "validation.queue_status_invalid": "Status must be \"pending\" or \"dead\"",
+  "validation.queue_task_not_dead": "Only dead tasks can be requeued",
+  "validation.queue_purge_age_invalid": "Age must be between 1 and 3650 days",
+  "not_found.session": "Session not found",
+  "auth.session_revoked": "Session revoked",
+  "auth.sessions_revoked": "Other sessions revoked",
+  "not_found.share": "Share not found",
+  "validation.share_role_required": "A role is required: viewer, template_editor, integration_manager or manager",
+  "not_found.share_invite": "Invite not found",
+  "validation.invite_ttl_invalid": "Invite lifetime must be between 1 hour and 30 days",
+  "account.deleted": "Your account and all its data have been deleted",
+  "not_found.api_token": "API token not found",
+  "auth.api_token_revoked": "API token revoked",
+  "admin.resynced": "EventSub subscriptions synchronized",
+  "admin.suspended": "Account suspended",
+  "admin.unsuspended": "Account unsuspended",
+  "validation.cannot_suspend_admin": "Administrator accounts can't be suspended",
+  "validation.admin_queue_status_invalid": "Status must be \"pending\", \"processing\", \"succeeded\" or \"dead\"",
+  "validation.api_token_name_invalid": "Token name is required (up to 100 characters)",
+  "validation.api_token_scopes_invalid": "Token scopes must be chosen from read:notifications, write:settings, manage:integrations",
+  "validation.api_token_ttl_invalid": "Token lifetime must be between 1 and 365 days",
+  "validation.api_token_limit": "You have reached the maximum number of API tokens",
   "app.name": "Twitch Notifications"
 }
 "#;
@@ -18,9 +18,11 @@ use tower_governor::key_extractor::SmartIpKeyExtractor;
 use tower_governor::{GovernorError, GovernorLayer};
 
 mod config;
+mod crypto;
 mod db;
 mod error;
 mod i18n;
+mod metrics;
 mod routes;
 mod services;
 
@@ -49,6 +51,7 @@ async fn main() -> anyhow::Result<()> {
     // Load configuration
     dotenvy::dotenv().ok();
     let config = Config::from_env()?;
+    crypto::init(&config.encryption.keys)?;
 
     tracing::info!("Starting Twitch Notifications Service");
 
@@ -278,6 +281,10 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         // Health check
         .route("/health", get(routes::health::health_check))
+        // Readiness: dependency checks, 503 when one is failing
+        .route("/health/ready", get(routes::health::readiness_check))
+        // Prometheus metrics
+        .route("/metrics", get(routes::metrics::metrics_handler))
         // Auth routes (apply rate limiting for public auth endpoints)
         .nest("/api/auth", routes::auth::router().layer(auth_rate_layer))
         // User routes (search, etc.)
@@ -288,6 +295,14 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api/notifications", routes::notifications::router())
         // Integration routes (Telegram, Discord)
         .nest("/api/integrations", routes::integrations::router())
+        // Account data export and deletion
+        .nest("/api/account", routes::account::router())
+        // Instance administration
+        .nest("/api/admin", routes::admin::router())
+        // Audit log of configuration changes
+        .nest("/api/audit", routes::audit::router())
+        // Watched channels (follow mode)
+        .nest("/api/watched-channels", routes::watched_channels::router())
         // Calendar sync endpoints (manual trigger / status)
         .nest("/api/calendar", routes::calendar::router())
         // Twitch EventSub webhooks (apply rate limiting)
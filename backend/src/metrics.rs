@@ -0,0 +1,392 @@
+//! Prometheus metrics.
+//!
+//! Counters, gauges and histograms live in a process-wide registry (like the cipher in
+//! `crypto`) so services can record them without threading a handle through every call.
+//! Values that mirror database state (queue depth, EventSub subscriptions) are refreshed
+//! by the `/metrics` handler right before rendering.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// `Content-Type` of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds (seconds) of the send latency buckets
+const SEND_DURATION_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+// ============================================================================
+// Metric Definitions
+// ============================================================================
+
+pub const WEBHOOKS_TOTAL: Metric = Metric {
+    name: "twitch_notifications_webhooks_total",
+    help: "EventSub webhook messages by subscription type and outcome",
+    kind: MetricKind::Counter,
+};
+
+pub const NOTIFICATION_SENDS_TOTAL: Metric = Metric {
+    name: "twitch_notifications_notification_sends_total",
+    help: "Notification send attempts by destination and status",
+    kind: MetricKind::Counter,
+};
+
+pub const NOTIFICATION_SEND_DURATION: Metric = Metric {
+    name: "twitch_notifications_notification_send_duration_seconds",
+    help: "Time spent sending a notification, by destination",
+    kind: MetricKind::Histogram,
+};
+
+pub const QUEUE_TASKS: Metric = Metric {
+    name: "twitch_notifications_queue_tasks",
+    help: "Notification retry queue tasks by status",
+    kind: MetricKind::Gauge,
+};
+
+pub const API_REQUESTS_TOTAL: Metric = Metric {
+    name: "twitch_notifications_api_requests_total",
+    help: "Requests sent to the Twitch, Discord and Telegram APIs",
+    kind: MetricKind::Counter,
+};
+
+pub const API_RATE_LIMITED_TOTAL: Metric = Metric {
+    name: "twitch_notifications_api_rate_limited_total",
+    help: "Requests answered with 429 by the Twitch, Discord and Telegram APIs",
+    kind: MetricKind::Counter,
+};
+
+pub const TOKEN_REFRESH_FAILURES_TOTAL: Metric = Metric {
+    name: "twitch_notifications_token_refresh_failures_total",
+    help: "Failed Twitch token refreshes by token kind (user or app)",
+    kind: MetricKind::Counter,
+};
+
+pub const EVENTSUB_SUBSCRIPTIONS: Metric = Metric {
+    name: "twitch_notifications_eventsub_subscriptions",
+    help: "EventSub subscriptions by owner (user or watched channel) and status",
+    kind: MetricKind::Gauge,
+};
+
+pub const WORKER_RUNS_TOTAL: Metric = Metric {
+    name: "twitch_notifications_worker_runs_total",
+    help: "Iterations of the background workers",
+    kind: MetricKind::Counter,
+};
+
+pub const WORKER_HEARTBEAT: Metric = Metric {
+    name: "twitch_notifications_worker_last_heartbeat_timestamp_seconds",
+    help: "Unix time of the last iteration of each background worker",
+    kind: MetricKind::Gauge,
+};
+
+/// Everything exposed by `render`, in output order
+const ALL_METRICS: &[&Metric] = &[
+    &WEBHOOKS_TOTAL,
+    &NOTIFICATION_SENDS_TOTAL,
+    &NOTIFICATION_SEND_DURATION,
+    &QUEUE_TASKS,
+    &API_REQUESTS_TOTAL,
+    &API_RATE_LIMITED_TOTAL,
+    &TOKEN_REFRESH_FAILURES_TOTAL,
+    &EVENTSUB_SUBSCRIPTIONS,
+    &WORKER_RUNS_TOTAL,
+    &WORKER_HEARTBEAT,
+];
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+enum Series {
+    Value(f64),
+    Histogram {
+        /// Non-cumulative count per bucket of `SEND_DURATION_BUCKETS`, plus `+Inf`
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+/// Metric name -> label set -> value
+static REGISTRY: Mutex<BTreeMap<&'static str, BTreeMap<Labels, Series>>> =
+    Mutex::new(BTreeMap::new());
+
+fn lock() -> MutexGuard<'static, BTreeMap<&'static str, BTreeMap<Labels, Series>>> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn labels(pairs: &[(&'static str, &str)]) -> Labels {
+    pairs.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
+
+pub fn inc(metric: &Metric, pairs: &[(&'static str, &str)]) {
+    let mut registry = lock();
+    let series = registry
+        .entry(metric.name)
+        .or_default()
+        .entry(labels(pairs))
+        .or_insert(Series::Value(0.0));
+    if let Series::Value(v) = series {
+        *v += 1.0;
+    }
+}
+
+pub fn set(metric: &Metric, pairs: &[(&'static str, &str)], value: f64) {
+    lock()
+        .entry(metric.name)
+        .or_default()
+        .insert(labels(pairs), Series::Value(value));
+}
+
+/// Drop every series of `metric`, e.g. before setting gauges whose label values
+/// may have disappeared since the last scrape
+pub fn clear(metric: &Metric) {
+    lock().remove(metric.name);
+}
+
+pub fn observe(metric: &Metric, pairs: &[(&'static str, &str)], value: f64) {
+    let mut registry = lock();
+    let series = registry
+        .entry(metric.name)
+        .or_default()
+        .entry(labels(pairs))
+        .or_insert_with(|| Series::Histogram {
+            buckets: vec![0; SEND_DURATION_BUCKETS.len() + 1],
+            sum: 0.0,
+            count: 0,
+        });
+    if let Series::Histogram {
+        buckets,
+        sum,
+        count,
+    } = series
+    {
+        let index = SEND_DURATION_BUCKETS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(SEND_DURATION_BUCKETS.len());
+        buckets[index] += 1;
+        *sum += value;
+        *count += 1;
+    }
+}
+
+// ============================================================================
+// Recording Helpers
+// ============================================================================
+
+/// Count an EventSub webhook message
+pub fn record_webhook(subscription_type: &str, outcome: &str) {
+    inc(
+        &WEBHOOKS_TOTAL,
+        &[
+            ("subscription_type", subscription_type),
+            ("outcome", outcome),
+        ],
+    );
+}
+
+/// Count a notification send and its latency
+pub fn record_send(destination: &str, success: bool, elapsed: Duration) {
+    let status = if success { "sent" } else { "failed" };
+    inc(
+        &NOTIFICATION_SENDS_TOTAL,
+        &[("destination", destination), ("status", status)],
+    );
+    observe(
+        &NOTIFICATION_SEND_DURATION,
+        &[("destination", destination)],
+        elapsed.as_secs_f64(),
+    );
+}
+
+/// Count a request to an external API (`twitch`, `discord` or `telegram`)
+pub fn record_api_request(service: &str, rate_limited: bool) {
+    inc(&API_REQUESTS_TOTAL, &[("service", service)]);
+    if rate_limited {
+        inc(&API_RATE_LIMITED_TOTAL, &[("service", service)]);
+    }
+}
+
+/// Count a failed Twitch token refresh (`user` or `app` token)
+pub fn record_token_refresh_failure(kind: &str) {
+    inc(&TOKEN_REFRESH_FAILURES_TOTAL, &[("kind", kind)]);
+}
+
+/// Record an iteration of a background worker
+pub fn record_worker_heartbeat(worker: &str) {
+    inc(&WORKER_RUNS_TOTAL, &[("worker", worker)]);
+    set(
+        &WORKER_HEARTBEAT,
+        &[("worker", worker)],
+        chrono::Utc::now().timestamp() as f64,
+    );
+}
+
+/// Unix time of the last iteration of a background worker
+pub fn last_worker_heartbeat(worker: &str) -> Option<i64> {
+    match lock()
+        .get(WORKER_HEARTBEAT.name)?
+        .get(&labels(&[("worker", worker)]))?
+    {
+        Series::Value(v) => Some(*v as i64),
+        Series::Histogram { .. } => None,
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    render_metrics(ALL_METRICS)
+}
+
+fn render_metrics(metrics: &[&Metric]) -> String {
+    let registry = lock();
+    let mut out = String::new();
+
+    for metric in metrics {
+        let _ = writeln!(out, "# HELP {} {}", metric.name, metric.help);
+        let _ = writeln!(out, "# TYPE {} {}", metric.name, metric.kind.as_str());
+
+        let Some(family) = registry.get(metric.name) else {
+            continue;
+        };
+        for (labels, series) in family {
+            match series {
+                Series::Value(v) => {
+                    let _ = writeln!(out, "{}{} {}", metric.name, format_labels(labels, None), v);
+                }
+                Series::Histogram {
+                    buckets,
+                    sum,
+                    count,
+                } => {
+                    let mut cumulative = 0;
+                    for (i, n) in buckets.iter().enumerate() {
+                        cumulative += n;
+                        let le = SEND_DURATION_BUCKETS
+                            .get(i)
+                            .map(|b| b.to_string())
+                            .unwrap_or_else(|| "+Inf".to_string());
+                        let _ = writeln!(
+                            out,
+                            "{}_bucket{} {}",
+                            metric.name,
+                            format_labels(labels, Some(&le)),
+                            cumulative
+                        );
+                    }
+                    let _ = writeln!(
+                        out,
+                        "{}_sum{} {}",
+                        metric.name,
+                        format_labels(labels, None),
+                        sum
+                    );
+                    let _ = writeln!(
+                        out,
+                        "{}_count{} {}",
+                        metric.name,
+                        format_labels(labels, None),
+                        count
+                    );
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_renders_with_escaped_labels() {
+        const TEST_COUNTER: Metric = Metric {
+            name: "test_counter_total",
+            help: "Test counter",
+            kind: MetricKind::Counter,
+        };
+
+        inc(&TEST_COUNTER, &[("kind", "a\"b")]);
+        inc(&TEST_COUNTER, &[("kind", "a\"b")]);
+
+        let out = render_metrics(&[&TEST_COUNTER]);
+        assert!(out.contains("# TYPE test_counter_total counter\n"));
+        assert!(out.contains("test_counter_total{kind=\"a\\\"b\"} 2\n"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        const TEST_HISTOGRAM: Metric = Metric {
+            name: "test_duration_seconds",
+            help: "Test histogram",
+            kind: MetricKind::Histogram,
+        };
+
+        observe(&TEST_HISTOGRAM, &[("destination", "x")], 0.2);
+        observe(&TEST_HISTOGRAM, &[("destination", "x")], 60.0);
+
+        let out = render_metrics(&[&TEST_HISTOGRAM]);
+        assert!(out.contains("test_duration_seconds_bucket{destination=\"x\",le=\"0.1\"} 0\n"));
+        assert!(out.contains("test_duration_seconds_bucket{destination=\"x\",le=\"0.25\"} 1\n"));
+        assert!(out.contains("test_duration_seconds_bucket{destination=\"x\",le=\"+Inf\"} 2\n"));
+        assert!(out.contains("test_duration_seconds_count{destination=\"x\"} 2\n"));
+    }
+}
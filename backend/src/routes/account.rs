@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get},
+    Json, Router,
+};
+
+use crate::error::AppResult;
+use crate::routes::auth::AuthUser;
+use crate::services::account::AccountService;
+use crate::AppState;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/export", get(export_account))
+        .route("/", delete(delete_account))
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Download everything stored about the current user as a JSON archive
+async fn export_account(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+) -> AppResult<impl IntoResponse> {
+    let filename = format!("twitch-notifications-{}.json", user.twitch_login);
+    let export = AccountService::export(&state, user).await?;
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )],
+        Json(export),
+    ))
+}
+
+/// Delete the current user's account: revoke Twitch tokens, remove EventSub
+/// subscriptions and Discord events, and delete all stored data
+async fn delete_account(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+) -> AppResult<Json<serde_json::Value>> {
+    AccountService::delete_account(&state, &user).await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("account.deleted") }),
+    ))
+}
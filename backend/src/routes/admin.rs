@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{
+    EventSubSubscriptionRepository, NotificationQueueRepository, User, UserRepository,
+};
+use crate::error::{AppError, AppResult};
+use crate::routes::auth::AdminUser;
+use crate::routes::notifications::QueueListResponse;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_SUSPEND, AUDIT_ACTION_UNSUSPEND, AUDIT_ENTITY_ACCOUNT,
+};
+use crate::services::subscriptions::SubscriptionManager;
+use crate::AppState;
+
+/// Longest stored suspension reason
+const MAX_SUSPENSION_REASON_LENGTH: usize = 500;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/users/:id/resync", post(resync_user))
+        .route(
+            "/users/:id/suspend",
+            post(suspend_user).delete(unsuspend_user),
+        )
+        .route("/queue", get(list_queue))
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UserListQuery {
+    /// Filter by Twitch login or display name
+    pub q: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserListResponse {
+    pub items: Vec<AdminUserResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminUserResponse {
+    pub id: String,
+    pub twitch_id: String,
+    pub twitch_login: String,
+    pub twitch_display_name: String,
+    pub is_admin: bool,
+    pub suspended_at: Option<NaiveDateTime>,
+    pub suspended_reason: Option<String>,
+    /// Number of EventSub subscriptions per status (e.g. `{"enabled": 4}`)
+    pub eventsub: BTreeMap<String, i64>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AdminUserResponse {
+    fn new(user: User, eventsub: BTreeMap<String, i64>) -> Self {
+        Self {
+            id: user.id,
+            twitch_id: user.twitch_id,
+            twitch_login: user.twitch_login,
+            twitch_display_name: user.twitch_display_name,
+            is_admin: user.is_admin,
+            suspended_at: user.suspended_at,
+            suspended_reason: user.suspended_reason,
+            eventsub,
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuspendRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminQueueQuery {
+    /// `pending`, `processing`, `succeeded` or `dead`; all tasks if omitted
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminQueueResponse {
+    /// Number of tasks per status across all users
+    pub counts: BTreeMap<String, i64>,
+    #[serde(flatten)]
+    pub tasks: QueueListResponse,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List all users with the state of their EventSub subscriptions
+async fn list_users(
+    State(state): State<Arc<AppState>>,
+    AdminUser(_admin): AdminUser,
+    Query(query): Query<UserListQuery>,
+) -> AppResult<Json<AdminUserListResponse>> {
+    let q = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    let users = UserRepository::list_page(&state.db, q, per_page, offset).await?;
+    let total = UserRepository::count(&state.db, q).await?;
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let mut eventsub: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+    for (user_id, status, count) in
+        EventSubSubscriptionRepository::status_counts_by_user(&state.db).await?
+    {
+        eventsub.entry(user_id).or_default().insert(status, count);
+    }
+
+    Ok(Json(AdminUserListResponse {
+        items: users
+            .into_iter()
+            .map(|user| {
+                let counts = eventsub.remove(&user.id).unwrap_or_default();
+                AdminUserResponse::new(user, counts)
+            })
+            .collect(),
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
+
+/// Re-create a user's EventSub subscriptions
+async fn resync_user(
+    State(state): State<Arc<AppState>>,
+    AdminUser(admin): AdminUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user = find_user(&state, &id).await?;
+
+    SubscriptionManager::sync_for_user(&state, &user).await?;
+    tracing::info!(
+        "Admin {} resynced EventSub subscriptions of user {}",
+        admin.id,
+        user.id
+    );
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("admin.resynced") }),
+    ))
+}
+
+/// Suspend an account: its notifications are no longer dispatched
+async fn suspend_user(
+    State(state): State<Arc<AppState>>,
+    AdminUser(admin): AdminUser,
+    Path(id): Path<String>,
+    Json(request): Json<SuspendRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user = find_user(&state, &id).await?;
+    if user.is_admin {
+        return Err(AppError::Validation(crate::i18n::t(
+            "validation.cannot_suspend_admin",
+        )));
+    }
+
+    let reason: Option<String> = request
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|r| r.chars().take(MAX_SUSPENSION_REASON_LENGTH).collect());
+
+    UserRepository::set_suspension(
+        &state.db,
+        &user.id,
+        Some(Utc::now().naive_utc()),
+        reason.as_deref(),
+    )
+    .await?;
+
+    AuditService::record(
+        &state,
+        &admin.id,
+        &user.id,
+        AUDIT_ENTITY_ACCOUNT,
+        Some(&user.id),
+        AUDIT_ACTION_SUSPEND,
+        serde_json::json!({ "reason": reason }),
+    )
+    .await;
+    tracing::info!("Admin {} suspended user {}", admin.id, user.id);
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("admin.suspended") }),
+    ))
+}
+
+/// Lift a suspension
+async fn unsuspend_user(
+    State(state): State<Arc<AppState>>,
+    AdminUser(admin): AdminUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    let user = find_user(&state, &id).await?;
+
+    UserRepository::set_suspension(&state.db, &user.id, None, None).await?;
+
+    AuditService::record(
+        &state,
+        &admin.id,
+        &user.id,
+        AUDIT_ENTITY_ACCOUNT,
+        Some(&user.id),
+        AUDIT_ACTION_UNSUSPEND,
+        serde_json::json!({ "reason": user.suspended_reason }),
+    )
+    .await;
+    tracing::info!("Admin {} unsuspended user {}", admin.id, user.id);
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("admin.unsuspended") }),
+    ))
+}
+
+/// Inspect the notification queue of all users
+async fn list_queue(
+    State(state): State<Arc<AppState>>,
+    AdminUser(_admin): AdminUser,
+    Query(query): Query<AdminQueueQuery>,
+) -> AppResult<Json<AdminQueueResponse>> {
+    let status = query.status.as_deref();
+    if !matches!(
+        status,
+        None | Some("pending") | Some("processing") | Some("succeeded") | Some("dead")
+    ) {
+        return Err(AppError::Validation(crate::i18n::t(
+            "validation.admin_queue_status_invalid",
+        )));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    let tasks = NotificationQueueRepository::list_all(&state.db, status, per_page, offset).await?;
+    let total = NotificationQueueRepository::count_all(&state.db, status).await?;
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+    let counts = NotificationQueueRepository::status_counts(&state.db)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(Json(AdminQueueResponse {
+        counts,
+        tasks: QueueListResponse {
+            items: tasks.into_iter().map(Into::into).collect(),
+            total,
+            page,
+            per_page,
+            total_pages,
+        },
+    }))
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+async fn find_user(state: &Arc<AppState>, id: &str) -> AppResult<User> {
+    UserRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.user")))
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{AuditEvent, AuditEventRepository, SharePermission};
+use crate::error::{AppError, AppResult};
+use crate::routes::auth::AuthUser;
+use crate::services::integrations::IntegrationService;
+use crate::AppState;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(list_audit_events))
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    /// Owner whose audit log to read; defaults to the current user
+    pub user_id: Option<String>,
+    /// Only events for this entity (e.g. "discord_integration")
+    pub entity: Option<String>,
+    /// Only events by this user
+    pub actor_id: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditListResponse {
+    pub items: Vec<AuditEventResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditEventResponse {
+    pub id: String,
+    pub actor_user_id: String,
+    /// Twitch login of the actor (absent if the account no longer exists, or the
+    /// change was made by a bot command or the system)
+    pub actor_login: Option<String>,
+    pub owner_user_id: String,
+    pub entity: String,
+    pub entity_id: Option<String>,
+    pub action: String,
+    pub details: Option<serde_json::Value>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AuditEventResponse {
+    fn new(event: AuditEvent, actor_login: Option<String>) -> Self {
+        Self {
+            id: event.id,
+            actor_user_id: event.actor_user_id,
+            actor_login,
+            owner_user_id: event.owner_user_id,
+            entity: event.entity,
+            entity_id: event.entity_id,
+            action: event.action,
+            details: event
+                .details
+                .and_then(|details| serde_json::from_str(&details).ok()),
+            created_at: event.created_at,
+        }
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List configuration changes to an owner's account (owner or grantee with the manager role)
+async fn list_audit_events(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<AuditQuery>,
+) -> AppResult<Json<AuditListResponse>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+
+    if !IntegrationService::check_access(&state, &owner_id, &user.id, SharePermission::ManageShares)
+        .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to read the audit log of owner {} without manage rights",
+            user.id,
+            owner_id
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let entity = query.entity.as_deref().filter(|e| !e.is_empty());
+    let actor_id = query.actor_id.as_deref().filter(|a| !a.is_empty());
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    let events = AuditEventRepository::list_for_owner(
+        &state.db, &owner_id, entity, actor_id, per_page, offset,
+    )
+    .await?;
+    let total =
+        AuditEventRepository::count_for_owner(&state.db, &owner_id, entity, actor_id).await?;
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    Ok(Json(AuditListResponse {
+        items: events
+            .into_iter()
+            .map(|(event, actor_login)| AuditEventResponse::new(event, actor_login))
+            .collect(),
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
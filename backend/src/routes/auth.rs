@@ -1,18 +1,29 @@
 use std::sync::Arc;
 
-use crate::db::UserRepository;
+use std::net::SocketAddr;
+
+use crate::db::{ApiToken, ApiTokenRepository, ApiTokenScope, SessionRepository, UserRepository};
 use crate::error::AppError;
-use crate::services::auth::AuthService;
+use crate::services::api_tokens::ApiTokenService;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_LINK, AUDIT_ACTION_UNLINK, AUDIT_ENTITY_DISCORD_ACCOUNT,
+    AUDIT_ENTITY_TELEGRAM_ACCOUNT,
+};
+use crate::services::auth::{AuthService, ClientInfo};
 use crate::services::twitch::TwitchService;
 use crate::AppState;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
     response::{IntoResponse, Redirect},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
+/// Longest user agent stored with a session
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/login", get(login))
@@ -20,6 +31,13 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/me", get(me).put(update_me))
         .route("/refresh", post(refresh_token))
         .route("/logout", post(logout))
+        .route(
+            "/sessions",
+            get(list_sessions).delete(revoke_other_sessions),
+        )
+        .route("/sessions/:id", delete(revoke_session))
+        .route("/tokens", get(list_api_tokens).post(create_api_token))
+        .route("/tokens/:id", delete(revoke_api_token))
         .route("/telegram/link", post(telegram_link))
         .route("/telegram/unlink", post(telegram_unlink))
         .route("/telegram/photo/:id", get(get_telegram_photo))
@@ -37,6 +55,8 @@ pub fn router() -> Router<Arc<AppState>> {
 pub struct LoginQuery {
     redirect_to: Option<String>,
     lang: Option<String>,
+    /// Share invite token, accepted after the Twitch login completes
+    invite: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,6 +87,64 @@ pub struct UserResponse {
 
     // Preferred language (optional)
     pub lang: Option<String>,
+
+    /// Instance administrator (may use `/api/admin`)
+    pub is_admin: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    /// The session the request was made with
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    /// Any of `read:notifications`, `write:settings`, `manage:integrations`
+    pub scopes: Vec<String>,
+    /// Lifetime in days; the token doesn't expire if omitted
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: String,
+    pub name: String,
+    /// First characters of the token, to tell tokens apart
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// Returned once, when the token is created
+#[derive(Debug, Serialize)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+    pub token: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -96,7 +174,9 @@ async fn login(
         .map(|l| crate::i18n::normalize_language(&l))
         .filter(|l| crate::i18n::is_supported_language(l.as_str()));
 
-    let state_jwt = AuthService::generate_oauth_state(&state, redirect_to, lang)?;
+    let invite = query.invite.filter(|i| !i.is_empty());
+
+    let state_jwt = AuthService::generate_oauth_state(&state, redirect_to, lang, invite)?;
     let scopes = TwitchService::get_required_scopes();
     let auth_url = state.twitch.get_auth_url(&state_jwt, &scopes);
 
@@ -106,6 +186,8 @@ async fn login(
 /// Handle Twitch OAuth callback
 async fn callback(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: http::HeaderMap,
     Query(query): Query<CallbackQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     if let Some(error) = query.error {
@@ -128,24 +210,132 @@ async fn callback(
     })?;
 
     let oauth_state = AuthService::decode_oauth_state(&state, &state_encoded)?;
+    let client = client_info(&headers, addr);
     let (redirect_url, user_id) =
-        AuthService::handle_twitch_callback(&state, code, oauth_state).await?;
+        AuthService::handle_twitch_callback(&state, code, oauth_state, client).await?;
 
     tracing::info!("OAuth authentication successful for user: {}", user_id);
     Ok(Redirect::to(&redirect_url))
 }
 
-/// Logout - invalidate session
-async fn logout(State(_state): State<Arc<AppState>>) -> Result<Json<serde_json::Value>, AppError> {
-    // Currently this project uses stateless JWTs for auth. There's no server-side
-    // session to clear by default, but exposing a `/logout` endpoint ensures the
-    // frontend can call it without 404s and provides a place to implement server-
-    // side invalidation (e.g. token blacklist) in the future if needed.
+/// Logout - revoke the current session
+async fn logout(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+) -> Result<Json<serde_json::Value>, AppError> {
+    SessionRepository::delete_for_user(&state.db, &auth.session.id, &auth.user.id).await?;
+    tracing::info!(
+        "User {} logged out of session {}",
+        auth.user.id,
+        auth.session.id
+    );
+
     Ok(Json(
         serde_json::json!({ "message": crate::i18n::t("auth.logged_out") }),
     ))
 }
 
+/// List the current user's active sessions (devices)
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+) -> Result<Json<Vec<SessionResponse>>, AppError> {
+    let sessions = SessionRepository::list_active_for_user(&state.db, &auth.user.id).await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|s| SessionResponse {
+                current: s.id == auth.session.id,
+                id: s.id,
+                user_agent: s.user_agent,
+                ip_address: s.ip_address,
+                created_at: s.created_at,
+                last_used_at: s.last_used_at,
+                expires_at: s.expires_at,
+            })
+            .collect(),
+    ))
+}
+
+/// Revoke one of the current user's sessions
+async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !SessionRepository::delete_for_user(&state.db, &id, &auth.user.id).await? {
+        return Err(AppError::NotFound(crate::i18n::t("not_found.session")));
+    }
+    tracing::info!("User {} revoked session {}", auth.user.id, id);
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("auth.session_revoked") }),
+    ))
+}
+
+/// Revoke all sessions of the current user except the one making the request
+async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let revoked =
+        SessionRepository::delete_others(&state.db, &auth.user.id, &auth.session.id).await?;
+    tracing::info!("User {} revoked {} other session(s)", auth.user.id, revoked);
+
+    Ok(Json(serde_json::json!({
+        "message": crate::i18n::t("auth.sessions_revoked"),
+        "revoked": revoked
+    })))
+}
+
+/// List the current user's API tokens. Token management needs a browser session:
+/// an API token can't create or revoke tokens.
+async fn list_api_tokens(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+) -> Result<Json<Vec<ApiTokenResponse>>, AppError> {
+    let tokens = ApiTokenRepository::list_for_user(&state.db, &auth.user.id).await?;
+
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+/// Create an API token; the token itself is only returned in this response
+async fn create_api_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> Result<Json<CreatedApiTokenResponse>, AppError> {
+    let (api_token, token) = ApiTokenService::create_token(
+        &state,
+        &auth.user.id,
+        &request.name,
+        &request.scopes,
+        request.expires_in_days,
+    )
+    .await?;
+    tracing::info!("User {} created API token {}", auth.user.id, api_token.id);
+
+    Ok(Json(CreatedApiTokenResponse {
+        api_token: api_token.into(),
+        token,
+    }))
+}
+
+/// Revoke one of the current user's API tokens
+async fn revoke_api_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthSession,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    ApiTokenService::revoke_token(&state, &auth.user.id, &id).await?;
+    tracing::info!("User {} revoked API token {}", auth.user.id, id);
+
+    Ok(Json(
+        serde_json::json!({ "message": crate::i18n::t("auth.api_token_revoked") }),
+    ))
+}
+
 /// Link Telegram login (created via the Telegram Login Widget).
 async fn telegram_link(
     State(state): State<Arc<AppState>>,
@@ -179,7 +369,18 @@ async fn telegram_link(
     payload.insert("auth_date".to_string(), request.auth_date.to_string());
     payload.insert("hash".to_string(), request.hash.clone());
 
-    AuthService::handle_telegram_link(&state, user.id, payload).await?;
+    AuthService::handle_telegram_link(&state, user.id.clone(), payload).await?;
+
+    AuditService::record(
+        &state,
+        &user.id,
+        &user.id,
+        AUDIT_ENTITY_TELEGRAM_ACCOUNT,
+        Some(&request.id),
+        AUDIT_ACTION_LINK,
+        serde_json::json!({ "telegram_username": request.username }),
+    )
+    .await;
 
     Ok(Json(
         serde_json::json!({ "message": crate::i18n::t("telegram.linked") }),
@@ -291,6 +492,7 @@ async fn me(
         discord_username: user.discord_username.clone(),
         discord_avatar_url: user.discord_avatar_url.clone(),
         lang: user.lang.clone(),
+        is_admin: user.is_admin,
     }))
 }
 
@@ -347,6 +549,7 @@ async fn update_me(
         discord_username: updated.discord_username.clone(),
         discord_avatar_url: updated.discord_avatar_url.clone(),
         lang: updated.lang.clone(),
+        is_admin: updated.is_admin,
     }))
 }
 
@@ -355,7 +558,19 @@ async fn telegram_unlink(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    AuthService::unlink_telegram(&state, user.id).await?;
+    AuthService::unlink_telegram(&state, user.id.clone()).await?;
+
+    AuditService::record(
+        &state,
+        &user.id,
+        &user.id,
+        AUDIT_ENTITY_TELEGRAM_ACCOUNT,
+        user.telegram_user_id.as_deref(),
+        AUDIT_ACTION_UNLINK,
+        serde_json::json!({ "telegram_username": user.telegram_username }),
+    )
+    .await;
+
     Ok(Json(
         serde_json::json!({ "message": crate::i18n::t("telegram.unlinked") }),
     ))
@@ -413,8 +628,22 @@ async fn discord_callback(
     })?;
 
     let discord_state = AuthService::decode_discord_oauth_state(&state, &state_encoded)?;
+    let user_id = discord_state.user_id.clone();
     let redirect_url = AuthService::handle_discord_callback(&state, code, discord_state).await?;
 
+    if let Some(user) = UserRepository::find_by_id(&state.db, &user_id).await? {
+        AuditService::record(
+            &state,
+            &user.id,
+            &user.id,
+            AUDIT_ENTITY_DISCORD_ACCOUNT,
+            user.discord_user_id.as_deref(),
+            AUDIT_ACTION_LINK,
+            serde_json::json!({ "discord_username": user.discord_username }),
+        )
+        .await;
+    }
+
     Ok(Redirect::to(&redirect_url))
 }
 
@@ -422,7 +651,19 @@ async fn discord_unlink(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    AuthService::unlink_discord(&state, user.id).await?;
+    AuthService::unlink_discord(&state, user.id.clone()).await?;
+
+    AuditService::record(
+        &state,
+        &user.id,
+        &user.id,
+        AUDIT_ENTITY_DISCORD_ACCOUNT,
+        user.discord_user_id.as_deref(),
+        AUDIT_ACTION_UNLINK,
+        serde_json::json!({ "discord_username": user.discord_username }),
+    )
+    .await;
+
     Ok(Json(
         serde_json::json!({ "message": crate::i18n::t("discord.unlinked") }),
     ))
@@ -444,12 +685,27 @@ async fn refresh_token(
 // Helper functions
 // ============================================================================
 
-/// Get current user from a bearer token string
-pub async fn get_user_from_token(
-    state: &Arc<AppState>,
-    token: &str,
-) -> Result<crate::db::User, AppError> {
-    AuthService::get_user_from_token(state, token).await
+/// User agent and address of the client, preferring the address reported by a reverse proxy
+fn client_info(headers: &http::HeaderMap, addr: SocketAddr) -> ClientInfo {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let ip_address = header("x-forwarded-for")
+        .and_then(|v| v.split(',').next())
+        .map(|v| v.trim().to_string())
+        .or_else(|| header("x-real-ip").map(str::to_string))
+        .unwrap_or_else(|| addr.ip().to_string());
+
+    ClientInfo {
+        user_agent: header(http::header::USER_AGENT.as_str())
+            .map(|ua| ua.chars().take(MAX_USER_AGENT_LENGTH).collect()),
+        ip_address: Some(ip_address),
+    }
 }
 
 // ============================================================================
@@ -458,44 +714,118 @@ pub async fn get_user_from_token(
 
 use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
 
-/// Extractor for authenticated user
+/// Extractor for authenticated user (session JWT or API token)
 pub struct AuthUser(pub crate::db::User);
 
+/// Extractor for the authenticated user together with the session of the request
+pub struct AuthSession {
+    pub user: crate::db::User,
+    pub session: crate::db::Session,
+}
+
 #[async_trait]
-impl FromRequestParts<Arc<AppState>> for AuthUser {
+impl FromRequestParts<Arc<AppState>> for AuthSession {
     type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &Arc<AppState>,
     ) -> Result<Self, Self::Rejection> {
-        // Extract Authorization header (Bearer token)
-        let auth_header = parts
-            .headers
-            .get(http::header::AUTHORIZATION)
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| {
-                tracing::debug!("Missing or invalid Authorization header");
-                AppError::Unauthorized
-            })?;
+        let token = bearer_token(parts)?;
 
-        if !auth_header.to_ascii_lowercase().starts_with("bearer ") {
-            tracing::debug!("Authorization header doesn't start with 'Bearer '");
-            return Err(AppError::Unauthorized);
+        // The JWT must belong to a session that hasn't been revoked
+        let (user, session) = AuthService::authenticate(state, token).await.map_err(|e| {
+            tracing::debug!("Failed to authenticate token: {:?}", e);
+            e
+        })?;
+
+        tracing::debug!("Authenticated user: {} (session {})", user.id, session.id);
+        Ok(AuthSession { user, session })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(parts)?;
+
+        if ApiTokenService::is_api_token(token) {
+            // Scopes are checked against the full path, before nesting stripped it
+            let path = parts
+                .extensions
+                .get::<axum::extract::OriginalUri>()
+                .map(|uri| uri.path().to_string())
+                .unwrap_or_else(|| parts.uri.path().to_string());
+
+            let user = ApiTokenService::authenticate(state, token, &parts.method, &path)
+                .await
+                .map_err(|e| {
+                    tracing::debug!("Failed to authenticate API token: {:?}", e);
+                    e
+                })?;
+
+            tracing::debug!("Authenticated user: {} (API token)", user.id);
+            return Ok(AuthUser(user));
         }
 
-        let token = auth_header[7..].trim();
-        if token.is_empty() {
-            tracing::debug!("Empty bearer token in Authorization header");
-            return Err(AppError::Unauthorized);
+        AuthSession::from_request_parts(parts, state)
+            .await
+            .map(|auth| AuthUser(auth.user))
+    }
+}
+
+/// Extractor for an instance administrator. Requires a browser session: API tokens
+/// can't be used for administration.
+pub struct AdminUser(pub crate::db::User);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let auth = AuthSession::from_request_parts(parts, state).await?;
+
+        if !auth.user.is_admin {
+            tracing::warn!(
+                "Access denied: user {} attempted to use the admin API",
+                auth.user.id
+            );
+            return Err(AppError::Forbidden);
         }
 
-        let user = get_user_from_token(state, token).await.map_err(|e| {
-            tracing::debug!("Failed to get user from token: {:?}", e);
-            e
+        Ok(AdminUser(auth.user))
+    }
+}
+
+/// Extract the bearer token from the Authorization header
+fn bearer_token(parts: &Parts) -> Result<&str, AppError> {
+    let auth_header = parts
+        .headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            tracing::debug!("Missing or invalid Authorization header");
+            AppError::Unauthorized
         })?;
 
-        tracing::debug!("Authenticated user: {}", user.id);
-        Ok(AuthUser(user))
+    if !auth_header.to_ascii_lowercase().starts_with("bearer ") {
+        tracing::debug!("Authorization header doesn't start with 'Bearer '");
+        return Err(AppError::Unauthorized);
     }
+
+    let token = auth_header[7..].trim();
+    if token.is_empty() {
+        tracing::debug!("Empty bearer token in Authorization header");
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(token)
 }
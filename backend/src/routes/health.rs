@@ -1,6 +1,12 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use serde::Serialize;
 
+use crate::services::readiness::{ComponentCheck, ReadinessService};
+use crate::AppState;
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -8,6 +14,16 @@ pub struct HealthResponse {
     pub timestamp: String,
 }
 
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`
+    pub status: String,
+    pub version: String,
+    pub timestamp: String,
+    pub components: BTreeMap<&'static str, ComponentCheck>,
+    pub workers: BTreeMap<&'static str, ComponentCheck>,
+}
+
 pub async fn health_check() -> impl IntoResponse {
     let response = HealthResponse {
         status: "healthy".to_string(),
@@ -17,3 +33,26 @@ pub async fn health_check() -> impl IntoResponse {
 
     (StatusCode::OK, Json(response))
 }
+
+/// Readiness probe: 200 when every dependency is usable, 503 otherwise
+pub async fn readiness_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let report = ReadinessService::check(&state).await;
+    let ready = report.is_ready();
+
+    let response = ReadinessResponse {
+        status: if ready { "ready" } else { "not_ready" }.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        components: report.components,
+        workers: report.workers,
+    };
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        tracing::warn!("Readiness check failed");
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(response))
+}
@@ -9,11 +9,17 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
 use crate::db::{
-    ChatType, CreateDiscordIntegration, DiscordIntegration, TelegramIntegration,
+    ChatType, CreateDiscordIntegration, DiscordIntegration, SharePermission, TelegramIntegration,
+    TelegramIntegrationRepository, TelegramPendingChat, TelegramPendingChatRepository,
     UpdateDiscordIntegration, UpdateTelegramIntegration, UserRepository,
 };
-use crate::error::{AppError, AppErrorWithDetails, AppResult};
+use crate::error::{AppError, AppErrorWithDetails, AppResult, TelegramError};
 use crate::routes::auth::AuthUser;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_CREATE, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE,
+    AUDIT_ENTITY_DISCORD_INTEGRATION, AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+    AUDIT_ENTITY_TELEGRAM_LINK_CODE,
+};
 use crate::services::integrations::IntegrationService;
 use crate::AppState;
 
@@ -43,6 +49,9 @@ mod tests {
             discord_username: discord_id.map(|s| format!("user#1234-{}", s)),
             discord_avatar_url: discord_id.map(|s| format!("https://cdn/{}.png", s)),
             lang: None,
+            is_admin: false,
+            suspended_at: None,
+            suspended_reason: None,
             created_at: now,
             updated_at: now,
         }
@@ -99,6 +108,8 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/telegram/:id", delete(delete_telegram_integration))
         .route("/telegram/:id/test", post(test_telegram_integration))
         .route("/telegram/bot", get(get_telegram_bot_info))
+        .route("/telegram/link-code", post(create_telegram_link_code))
+        .route("/telegram/pending-chats", get(list_telegram_pending_chats))
         // Discord routes
         .route("/discord", get(list_discord_integrations))
         .route("/discord", post(create_discord_integration))
@@ -115,6 +126,7 @@ pub fn router() -> Router<Arc<AppState>> {
             "/discord/guilds/:guild_id/channels",
             get(list_discord_channels),
         )
+        .route("/discord/guilds/:guild_id/roles", get(list_discord_roles))
 }
 
 // ============================================================================
@@ -128,6 +140,8 @@ pub struct CreateTelegramRequest {
     pub telegram_chat_id: String,
     pub telegram_chat_title: Option<String>,
     pub telegram_chat_type: Option<String>,
+    /// Forum topic to post into (supergroups with topics only)
+    pub message_thread_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,12 +161,16 @@ pub struct TelegramIntegrationResponse {
     pub telegram_chat_id: String,
     pub telegram_chat_title: Option<String>,
     pub telegram_chat_type: Option<ChatType>,
+    pub message_thread_id: Option<i32>,
     pub is_enabled: bool,
     pub notify_stream_online: bool,
     pub notify_stream_offline: bool,
     pub notify_title_change: bool,
     pub notify_category_change: bool,
     pub notify_reward_redemption: bool,
+    pub disabled_reason: Option<String>,
+    pub health_status: Option<String>,
+    pub health_checked_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -164,12 +182,16 @@ impl From<TelegramIntegration> for TelegramIntegrationResponse {
             telegram_chat_id: integration.telegram_chat_id,
             telegram_chat_title: integration.telegram_chat_title,
             telegram_chat_type: integration.telegram_chat_type,
+            message_thread_id: integration.message_thread_id,
             is_enabled: integration.is_enabled,
             notify_stream_online: integration.notify_stream_online,
             notify_stream_offline: integration.notify_stream_offline,
             notify_title_change: integration.notify_title_change,
             notify_category_change: integration.notify_category_change,
             notify_reward_redemption: integration.notify_reward_redemption,
+            disabled_reason: integration.disabled_reason,
+            health_status: integration.health_status,
+            health_checked_at: integration.health_checked_at,
             created_at: integration.created_at,
             updated_at: integration.updated_at,
         }
@@ -199,6 +221,14 @@ pub struct UpdateDiscordRequest {
     pub notify_category_change: Option<bool>,
     pub notify_reward_redemption: Option<bool>,
     pub calendar_sync_enabled: Option<bool>,
+    pub auto_publish: Option<bool>,
+    pub stream_thread_enabled: Option<bool>,
+    /// Per-event mention: "" (none), "here" or a role id
+    pub mention_stream_online: Option<String>,
+    pub mention_stream_offline: Option<String>,
+    pub mention_title_change: Option<String>,
+    pub mention_category_change: Option<String>,
+    pub mention_reward_redemption: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -209,6 +239,7 @@ pub struct DiscordIntegrationResponse {
     pub discord_channel_id: String,
     pub discord_channel_name: Option<String>,
     pub discord_webhook_url: Option<String>,
+    pub discord_webhook_managed: bool,
     pub is_enabled: bool,
     pub notify_stream_online: bool,
     pub notify_stream_offline: bool,
@@ -216,6 +247,16 @@ pub struct DiscordIntegrationResponse {
     pub notify_category_change: bool,
     pub notify_reward_redemption: bool,
     pub calendar_sync_enabled: bool,
+    pub auto_publish: bool,
+    pub stream_thread_enabled: bool,
+    pub mention_stream_online: Option<String>,
+    pub mention_stream_offline: Option<String>,
+    pub mention_title_change: Option<String>,
+    pub mention_category_change: Option<String>,
+    pub mention_reward_redemption: Option<String>,
+    pub disabled_reason: Option<String>,
+    pub health_status: Option<String>,
+    pub health_checked_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -229,6 +270,7 @@ impl From<DiscordIntegration> for DiscordIntegrationResponse {
             discord_channel_id: integration.discord_channel_id,
             discord_channel_name: integration.discord_channel_name,
             discord_webhook_url: integration.discord_webhook_url,
+            discord_webhook_managed: integration.discord_webhook_managed,
             is_enabled: integration.is_enabled,
             notify_stream_online: integration.notify_stream_online,
             notify_stream_offline: integration.notify_stream_offline,
@@ -236,6 +278,16 @@ impl From<DiscordIntegration> for DiscordIntegrationResponse {
             notify_category_change: integration.notify_category_change,
             notify_reward_redemption: integration.notify_reward_redemption,
             calendar_sync_enabled: integration.calendar_sync_enabled,
+            auto_publish: integration.auto_publish,
+            stream_thread_enabled: integration.stream_thread_enabled,
+            mention_stream_online: integration.mention_stream_online,
+            mention_stream_offline: integration.mention_stream_offline,
+            mention_title_change: integration.mention_title_change,
+            mention_category_change: integration.mention_category_change,
+            mention_reward_redemption: integration.mention_reward_redemption,
+            disabled_reason: integration.disabled_reason,
+            health_status: integration.health_status,
+            health_checked_at: integration.health_checked_at,
             created_at: integration.created_at,
             updated_at: integration.updated_at,
         }
@@ -249,6 +301,12 @@ pub struct DiscordGuildResponse {
     pub icon: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DiscordRoleResponse {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct DiscordChannelResponse {
     pub id: String,
@@ -286,7 +344,8 @@ async fn list_telegram_integrations(
 ) -> AppResult<Json<Vec<TelegramIntegrationResponse>>> {
     let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
 
-    if !IntegrationService::check_access(&state, &owner_id, &user.id, false).await? {
+    if !IntegrationService::check_access(&state, &owner_id, &user.id, SharePermission::View).await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to list telegram integrations of owner {} without share",
             user.id,
@@ -311,7 +370,14 @@ async fn create_telegram_integration(
 ) -> AppResult<Json<TelegramIntegrationResponse>> {
     let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
 
-    if !IntegrationService::check_access(&state, &owner_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to create telegram integration for owner {} without manage rights",
             user.id,
@@ -356,15 +422,31 @@ async fn create_telegram_integration(
         }
     }
 
+    if let Some(thread_id) = request.message_thread_id {
+        IntegrationService::check_telegram_topic(&state, &chat_id, thread_id).await?;
+    }
+
     let created = IntegrationService::create_telegram_integration(
         &state,
         &owner_id,
         chat_id,
         request.telegram_chat_title,
         chat_type,
+        request.message_thread_id,
     )
     .await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &owner_id,
+        AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+        Some(&created.id),
+        AUDIT_ACTION_CREATE,
+        AuditService::snapshot(&created),
+    )
+    .await;
+
     Ok(Json(created.into()))
 }
 
@@ -378,7 +460,14 @@ async fn get_telegram_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &integration.user_id, &user.id, false).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &integration.user_id,
+        &user.id,
+        SharePermission::View,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to view telegram integration {} owned by {} without share",
             user.id,
@@ -402,7 +491,14 @@ async fn update_telegram_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &existing.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &existing.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to update telegram integration {} owned by {} without manage rights",
             user.id,
@@ -424,6 +520,17 @@ async fn update_telegram_integration(
 
     let updated = IntegrationService::update_telegram_integration(&state, &id, update).await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &existing.user_id,
+        AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+        Some(&id),
+        AUDIT_ACTION_UPDATE,
+        AuditService::diff(&existing, &updated),
+    )
+    .await;
+
     Ok(Json(updated.into()))
 }
 
@@ -437,7 +544,14 @@ async fn delete_telegram_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &existing.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &existing.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to delete telegram integration {} owned by {} without manage rights",
             user.id,
@@ -449,6 +563,17 @@ async fn delete_telegram_integration(
 
     IntegrationService::delete_telegram_integration(&state, &id).await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &existing.user_id,
+        AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+        Some(&id),
+        AUDIT_ACTION_DELETE,
+        AuditService::snapshot(&existing),
+    )
+    .await;
+
     Ok(Json(serde_json::json!({
         "message": crate::i18n::t("integration.deleted")
     })))
@@ -464,7 +589,14 @@ async fn test_telegram_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &integration.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &integration.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to test telegram integration {} owned by {} without manage rights",
             user.id,
@@ -474,23 +606,10 @@ async fn test_telegram_integration(
         return Err(AppError::Forbidden);
     }
 
-    let telegram_guard = state.telegram.read().await;
-    let telegram = telegram_guard.as_ref().ok_or_else(|| {
-        AppError::ServiceUnavailable("Telegram service not available".to_string())
-    })?;
-
     let owner = UserRepository::find_by_id(&state.db, &integration.user_id).await?;
     let owner_lang = owner.as_ref().and_then(|o| o.lang.as_deref());
-    let title = crate::i18n::tr(owner_lang, "messages.test_notification_title", None);
-    let body = crate::i18n::tr(owner_lang, "messages.test_notification_body", None);
-    let message = format!("<b>{}</b>\n\n{}", title, body);
-
-    match telegram
-        .send_message(crate::services::telegram::TelegramMessage {
-            chat_id: integration.telegram_chat_id.clone(),
-            text: message,
-            ..Default::default()
-        })
+
+    match IntegrationService::send_telegram_test_notification(&state, &integration, owner_lang)
         .await
     {
         Ok(_) => Ok(Json(TestNotificationResponse {
@@ -523,7 +642,8 @@ async fn list_discord_integrations(
 ) -> AppResult<Json<Vec<DiscordIntegrationResponse>>> {
     let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
 
-    if !IntegrationService::check_access(&state, &owner_id, &user.id, false).await? {
+    if !IntegrationService::check_access(&state, &owner_id, &user.id, SharePermission::View).await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to list discord integrations of owner {} without share",
             user.id,
@@ -548,7 +668,14 @@ async fn create_discord_integration(
 ) -> Result<Json<DiscordIntegrationResponse>, AppErrorWithDetails> {
     let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
 
-    if !IntegrationService::check_access(&state, &owner_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Отказ в доступе: пользователь {} попытался создать Discord-интеграцию от имени {} без прав на управление",
             user.id,
@@ -597,6 +724,17 @@ async fn create_discord_integration(
     let created =
         IntegrationService::create_discord_integration(&state, &owner_id, integration).await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &owner_id,
+        AUDIT_ENTITY_DISCORD_INTEGRATION,
+        Some(&created.id),
+        AUDIT_ACTION_CREATE,
+        AuditService::snapshot(&created),
+    )
+    .await;
+
     Ok(Json(created.into()))
 }
 
@@ -610,7 +748,14 @@ async fn get_discord_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &integration.user_id, &user.id, false).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &integration.user_id,
+        &user.id,
+        SharePermission::View,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to view discord integration {} owned by {} without share",
             user.id,
@@ -634,7 +779,14 @@ async fn update_discord_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &existing.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &existing.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to update discord integration {} owned by {} without manage rights",
             user.id,
@@ -655,10 +807,30 @@ async fn update_discord_integration(
         notify_category_change: request.notify_category_change,
         notify_reward_redemption: request.notify_reward_redemption,
         calendar_sync_enabled: request.calendar_sync_enabled,
+        auto_publish: request.auto_publish,
+        stream_thread_enabled: request.stream_thread_enabled,
+        mention_stream_online: request.mention_stream_online,
+        mention_stream_offline: request.mention_stream_offline,
+        mention_title_change: request.mention_title_change,
+        mention_category_change: request.mention_category_change,
+        mention_reward_redemption: request.mention_reward_redemption,
     };
 
+    IntegrationService::check_discord_mentions(&state, &existing.discord_guild_id, &update).await?;
+
     let updated = IntegrationService::update_discord_integration(&state, &id, update).await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &existing.user_id,
+        AUDIT_ENTITY_DISCORD_INTEGRATION,
+        Some(&id),
+        AUDIT_ACTION_UPDATE,
+        AuditService::diff(&existing, &updated),
+    )
+    .await;
+
     // Trigger calendar sync if enabled
     let enabled_now = request.calendar_sync_enabled.unwrap_or(false);
     if enabled_now && !existing.calendar_sync_enabled {
@@ -693,7 +865,14 @@ async fn delete_discord_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &existing.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &existing.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to delete discord integration {} owned by {} without manage rights",
             user.id,
@@ -705,6 +884,17 @@ async fn delete_discord_integration(
 
     IntegrationService::delete_discord_integration(&state, &id).await?;
 
+    AuditService::record(
+        &state,
+        &user.id,
+        &existing.user_id,
+        AUDIT_ENTITY_DISCORD_INTEGRATION,
+        Some(&id),
+        AUDIT_ACTION_DELETE,
+        AuditService::snapshot(&existing),
+    )
+    .await;
+
     Ok(Json(serde_json::json!({
         "message": crate::i18n::t("integration.deleted")
     })))
@@ -720,7 +910,14 @@ async fn test_discord_integration(
         .await?
         .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
 
-    if !IntegrationService::check_access(&state, &integration.user_id, &user.id, true).await? {
+    if !IntegrationService::check_access(
+        &state,
+        &integration.user_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
         tracing::warn!(
             "Access denied: user {} attempted to test discord integration {} owned by {} without manage rights",
             user.id,
@@ -730,41 +927,11 @@ async fn test_discord_integration(
         return Err(AppError::Forbidden);
     }
 
-    let discord_guard = state.discord.read().await;
-    let discord = discord_guard
-        .as_ref()
-        .ok_or_else(|| AppError::ServiceUnavailable("Discord service not available".to_string()))?;
-
-    use crate::services::discord::{colors, DiscordEmbed, DiscordMessage, WebhookMessage};
-
     let owner = UserRepository::find_by_id(&state.db, &integration.user_id).await?;
     let owner_lang = owner.as_ref().and_then(|o| o.lang.as_deref());
-    let title = crate::i18n::tr(owner_lang, "messages.test_notification_title", None);
-    let body = crate::i18n::tr(owner_lang, "messages.test_notification_body", None);
-    let embed = DiscordEmbed::new()
-        .title(&title)
-        .description(&body)
-        .color(colors::SUCCESS)
-        .timestamp(chrono::Utc::now().to_rfc3339());
-
-    let result = if let Some(ref webhook_url) = integration.discord_webhook_url {
-        let message = WebhookMessage {
-            content: None,
-            username: Some(crate::i18n::t("app.name")),
-            avatar_url: None,
-            embeds: Some(vec![embed]),
-        };
-        discord.send_webhook_message(webhook_url, message).await
-    } else {
-        let message = DiscordMessage {
-            content: None,
-            embeds: Some(vec![embed]),
-            tts: None,
-        };
-        discord
-            .send_message(&integration.discord_channel_id, message)
-            .await
-    };
+
+    let result =
+        IntegrationService::send_discord_test_notification(&state, &integration, owner_lang).await;
 
     match result {
         Ok(_) => Ok(Json(TestNotificationResponse {
@@ -853,6 +1020,136 @@ pub struct TelegramBotInfoResponse {
     pub id: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TelegramLinkCodeResponse {
+    pub code: String,
+    /// Ready-to-send command, e.g. `/link AbC123...`
+    pub command: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Generate a one-time code for linking a chat from Telegram via `/link <code>`
+/// (optionally on behalf of an owner via ?user_id=...)
+async fn create_telegram_link_code(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<OwnerQuery>,
+) -> AppResult<Json<TelegramLinkCodeResponse>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+
+    if !IntegrationService::check_access(
+        &state,
+        &owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to create telegram link code for owner {} without manage rights",
+            user.id,
+            owner_id
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let link_code =
+        IntegrationService::create_telegram_link_code(&state, &owner_id, &user.id).await?;
+
+    // The code itself is a short-lived secret and is not recorded
+    AuditService::record(
+        &state,
+        &user.id,
+        &owner_id,
+        AUDIT_ENTITY_TELEGRAM_LINK_CODE,
+        None,
+        AUDIT_ACTION_CREATE,
+        serde_json::json!({ "expires_at": link_code.expires_at }),
+    )
+    .await;
+
+    Ok(Json(TelegramLinkCodeResponse {
+        command: format!("/link {}", link_code.code),
+        code: link_code.code,
+        expires_at: link_code.expires_at,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct TelegramPendingChatResponse {
+    pub chat_id: String,
+    pub chat_title: Option<String>,
+    pub chat_type: Option<ChatType>,
+    pub bot_status: String,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<TelegramPendingChat> for TelegramPendingChatResponse {
+    fn from(chat: TelegramPendingChat) -> Self {
+        Self {
+            chat_id: chat.chat_id,
+            chat_title: chat.chat_title,
+            chat_type: chat.chat_type,
+            bot_status: chat.bot_status,
+            updated_at: chat.updated_at,
+        }
+    }
+}
+
+/// List chats the bot was added to (by the current user's or the owner's Telegram account)
+/// that are not linked to the owner yet. Used by the UI chat picker.
+async fn list_telegram_pending_chats(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<OwnerQuery>,
+) -> AppResult<Json<Vec<TelegramPendingChatResponse>>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+
+    if !IntegrationService::check_access(
+        &state,
+        &owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to list pending telegram chats for owner {} without manage rights",
+            user.id,
+            owner_id
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let owner = UserRepository::find_by_id(&state.db, &owner_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let mut telegram_ids: Vec<String> = Vec::new();
+    for tg_id in [user.telegram_user_id, owner.telegram_user_id]
+        .into_iter()
+        .flatten()
+    {
+        if !telegram_ids.contains(&tg_id) {
+            telegram_ids.push(tg_id);
+        }
+    }
+
+    let mut response = Vec::new();
+    for tg_id in telegram_ids {
+        for chat in TelegramPendingChatRepository::find_by_added_by(&state.db, &tg_id).await? {
+            if TelegramIntegrationRepository::exists(&state.db, &chat.chat_id, None, &owner_id)
+                .await?
+            {
+                continue;
+            }
+            response.push(chat.into());
+        }
+    }
+
+    Ok(Json(response))
+}
+
 /// Get basic info for the configured Telegram bot (username and id)
 async fn get_telegram_bot_info(
     State(state): State<Arc<AppState>>,
@@ -868,11 +1165,12 @@ async fn get_telegram_bot_info(
 
     use teloxide::prelude::Requester;
 
-    let me = tg
-        .get_bot()
-        .get_me()
-        .await
-        .map_err(|e| AppError::Telegram(format!("Failed to fetch Telegram bot info: {}", e)))?;
+    let me = tg.get_bot().get_me().await.map_err(|e| {
+        AppError::Telegram(TelegramError::request(
+            "Failed to fetch Telegram bot info",
+            &e,
+        ))
+    })?;
 
     Ok(Json(TelegramBotInfoResponse {
         username: me.username().to_string(),
@@ -936,6 +1234,75 @@ async fn list_discord_channels(
     Ok(Json(response))
 }
 
+/// List roles of a Discord guild that can be used as notification mentions
+/// (optionally on behalf of an owner via ?user_id=...). Requires the same guild
+/// manage permissions as creating an integration there.
+async fn list_discord_roles(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(guild_id): Path<String>,
+    Query(query): Query<OwnerQuery>,
+) -> AppResult<Json<Vec<DiscordRoleResponse>>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+
+    if !IntegrationService::check_access(
+        &state,
+        &owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to list roles of guild {} on behalf of {} without manage rights",
+            user.id,
+            guild_id,
+            owner_id
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let owner_user = UserRepository::find_by_id(&state.db, &owner_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.user")))?;
+    let discord_account_to_check =
+        IntegrationService::select_discord_account_to_check(&owner_id, &user, &owner_user)?;
+
+    if !IntegrationService::check_discord_manage_permissions(
+        &state,
+        &guild_id,
+        &discord_account_to_check,
+    )
+    .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to list roles of guild {} without manage permissions",
+            user.id,
+            guild_id
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    let discord_guard = state.discord.read().await;
+    let discord = discord_guard
+        .as_ref()
+        .ok_or_else(|| AppError::ServiceUnavailable("Discord service not available".to_string()))?;
+
+    let roles = discord.get_guild_roles(&guild_id).await?;
+
+    // Skip the implicit @everyone role (its id equals the guild id)
+    let response: Vec<DiscordRoleResponse> = roles
+        .into_iter()
+        .filter(|r| r.id != guild_id)
+        .map(|r| DiscordRoleResponse {
+            id: r.id,
+            name: r.name,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
 /// Get a Discord channel by ID
 async fn get_discord_channel(
     State(state): State<Arc<AppState>>,
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+
+use crate::db::{
+    EventSubSubscriptionRepository, NotificationQueueRepository,
+    WatchedChannelSubscriptionRepository,
+};
+use crate::error::AppResult;
+use crate::metrics::{self, EVENTSUB_SUBSCRIPTIONS, QUEUE_TASKS};
+use crate::AppState;
+
+/// Prometheus scrape endpoint
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> AppResult<impl IntoResponse> {
+    let queue = NotificationQueueRepository::status_counts(&state.db).await?;
+    let eventsub = EventSubSubscriptionRepository::status_counts(&state.db).await?;
+    let watched = WatchedChannelSubscriptionRepository::status_counts(&state.db).await?;
+
+    // Statuses without rows must not keep their last value
+    metrics::clear(&QUEUE_TASKS);
+    for (status, count) in queue {
+        metrics::set(&QUEUE_TASKS, &[("status", &status)], count as f64);
+    }
+
+    metrics::clear(&EVENTSUB_SUBSCRIPTIONS);
+    for (owner, counts) in [("user", eventsub), ("watched_channel", watched)] {
+        for (status, count) in counts {
+            metrics::set(
+                &EVENTSUB_SUBSCRIPTIONS,
+                &[("owner", owner), ("status", &status)],
+                count as f64,
+            );
+        }
+    }
+
+    Ok((
+        [(header::CONTENT_TYPE, metrics::CONTENT_TYPE)],
+        metrics::render(),
+    ))
+}
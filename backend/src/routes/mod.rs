@@ -1,8 +1,13 @@
+pub mod account;
+pub mod admin;
+pub mod audit;
 pub mod auth;
 pub mod calendar;
 pub mod health;
 pub mod integrations;
+pub mod metrics;
 pub mod notifications;
 pub mod settings;
 pub mod users;
+pub mod watched_channels;
 pub mod webhooks;
@@ -1,22 +1,29 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
-    routing::get,
+    extract::{Path, Query, State},
+    routing::{get, post},
     Json, Router,
 };
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::db::NotificationLogRepository;
-use crate::error::AppResult;
+use crate::db::{
+    NotificationLogRepository, NotificationQueueRepository, NotificationTask, SharePermission, User,
+};
+use crate::error::{AppError, AppResult};
 use crate::routes::auth::AuthUser;
+use crate::services::integrations::IntegrationService;
 use crate::AppState;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(list_notifications))
         .route("/stats", get(get_notification_stats))
+        // Retry queue and dead-letter tasks
+        .route("/queue", get(list_queue).delete(purge_queue))
+        .route("/queue/discard", post(discard_queue_tasks))
+        .route("/queue/:id/requeue", post(requeue_queue_task))
 }
 
 // ============================================================================
@@ -62,6 +69,89 @@ pub struct NotificationStatsResponse {
     pub by_destination: std::collections::HashMap<String, i64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    /// Owner of the queue; defaults to the current user
+    pub user_id: Option<String>,
+    /// "pending" or "dead"; both when omitted
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueOwnerQuery {
+    pub user_id: Option<String>,
+}
+
+/// Largest accepted `older_than_days`, keeps the purge cutoff within chrono's range
+const MAX_PURGE_AGE_DAYS: i64 = 3650;
+
+#[derive(Debug, Deserialize)]
+pub struct PurgeQueueQuery {
+    pub user_id: Option<String>,
+    /// Delete finished tasks last updated more than this many days ago
+    pub older_than_days: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscardQueueRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueListResponse {
+    pub items: Vec<QueueTaskResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueTaskResponse {
+    pub id: String,
+    pub user_id: String,
+    pub notification_type: String,
+    pub destination_type: String,
+    pub destination_id: String,
+    pub message: String,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<NotificationTask> for QueueTaskResponse {
+    fn from(task: NotificationTask) -> Self {
+        Self {
+            id: task.id,
+            user_id: task.user_id,
+            notification_type: task.notification_type,
+            destination_type: task.destination_type,
+            destination_id: task.destination_id,
+            message: task.message,
+            status: task.status,
+            attempts: task.attempts,
+            max_attempts: task.max_attempts,
+            last_error: task.last_error,
+            next_attempt_at: task.next_attempt_at,
+            expires_at: task.expires_at,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueDeletedResponse {
+    pub deleted: u64,
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
@@ -168,3 +258,133 @@ async fn get_notification_stats(
         by_destination: dest_counts,
     }))
 }
+
+/// Require the current user to be the queue owner or to hold a managing share
+async fn require_queue_access(state: &Arc<AppState>, owner_id: &str, user: &User) -> AppResult<()> {
+    if !IntegrationService::check_access(
+        state,
+        owner_id,
+        &user.id,
+        SharePermission::ManageIntegrations,
+    )
+    .await?
+    {
+        tracing::warn!(
+            "Access denied: user {} attempted to manage the notification queue of owner {} without manage rights",
+            user.id,
+            owner_id
+        );
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// List pending and dead notification tasks
+async fn list_queue(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<QueueQuery>,
+) -> AppResult<Json<QueueListResponse>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+    require_queue_access(&state, &owner_id, &user).await?;
+
+    let status = query.status.as_deref();
+    if !matches!(status, None | Some("pending") | Some("dead")) {
+        return Err(AppError::Validation(crate::i18n::t(
+            "validation.queue_status_invalid",
+        )));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let tasks =
+        NotificationQueueRepository::list_for_user(&state.db, &owner_id, status, per_page, offset)
+            .await?;
+    let total = NotificationQueueRepository::count_for_user(&state.db, &owner_id, status).await?;
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    Ok(Json(QueueListResponse {
+        items: tasks.into_iter().map(Into::into).collect(),
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
+
+/// Put a dead task back into the queue with its attempts reset
+async fn requeue_queue_task(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<QueueTaskResponse>> {
+    let task = NotificationQueueRepository::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.queue_task")))?;
+    require_queue_access(&state, &task.user_id, &user).await?;
+
+    if task.status != "dead" {
+        return Err(AppError::Validation(crate::i18n::t(
+            "validation.queue_task_not_dead",
+        )));
+    }
+
+    // The status is checked again in the update so a concurrent requeue can't apply twice
+    let task = NotificationQueueRepository::requeue(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::Validation(crate::i18n::t("validation.queue_task_not_dead")))?;
+
+    tracing::info!("User {} requeued notification task {}", user.id, id);
+    Ok(Json(task.into()))
+}
+
+/// Delete pending or dead tasks by id
+async fn discard_queue_tasks(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<QueueOwnerQuery>,
+    Json(request): Json<DiscardQueueRequest>,
+) -> AppResult<Json<QueueDeletedResponse>> {
+    let owner_id = query.user_id.unwrap_or_else(|| user.id.clone());
+    require_queue_access(&state, &owner_id, &user).await?;
+
+    let deleted = NotificationQueueRepository::discard(&state.db, &owner_id, &request.ids).await?;
+
+    tracing::info!(
+        "User {} discarded {} notification task(s) of owner {}",
+        user.id,
+        deleted,
+        owner_id
+    );
+    Ok(Json(QueueDeletedResponse { deleted }))
+}
+
+/// Delete finished (dead or succeeded) tasks older than the given number of days
+async fn purge_queue(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<PurgeQueueQuery>,
+) -> AppResult<Json<QueueDeletedResponse>> {
+    let owner_id = query.user_id.clone().unwrap_or_else(|| user.id.clone());
+    require_queue_access(&state, &owner_id, &user).await?;
+
+    if !(1..=MAX_PURGE_AGE_DAYS).contains(&query.older_than_days) {
+        return Err(AppError::Validation(crate::i18n::t(
+            "validation.queue_purge_age_invalid",
+        )));
+    }
+
+    let cutoff = Utc::now().naive_utc() - chrono::Duration::days(query.older_than_days);
+    let deleted =
+        NotificationQueueRepository::purge_finished_before(&state.db, &owner_id, cutoff).await?;
+
+    tracing::info!(
+        "User {} purged {} notification task(s) of owner {}",
+        user.id,
+        deleted,
+        owner_id
+    );
+    Ok(Json(QueueDeletedResponse { deleted }))
+}
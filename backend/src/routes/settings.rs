@@ -8,10 +8,20 @@ use axum::{
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
-use crate::db::{SettingsShareRepository, UserRepository};
+use crate::db::{
+    NotificationSettings, SettingsShare, SettingsShareRepository, ShareInvite,
+    ShareInviteRepository, SharePermission, ShareRole, UserRepository,
+};
 use crate::error::{AppError, AppResult};
+use crate::i18n::t;
 use crate::routes::auth::AuthUser;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_CREATE, AUDIT_ACTION_DELETE, AUDIT_ACTION_RESET,
+    AUDIT_ACTION_UPDATE, AUDIT_ENTITY_MESSAGES, AUDIT_ENTITY_SETTINGS, AUDIT_ENTITY_SHARE,
+};
+use crate::services::integrations::IntegrationService;
 use crate::services::settings::SettingsService;
+use crate::services::share_invites::{ShareInviteService, DEFAULT_INVITE_TTL_HOURS};
 use crate::AppState;
 
 pub fn router() -> Router<Arc<AppState>> {
@@ -22,6 +32,8 @@ pub fn router() -> Router<Arc<AppState>> {
         // Shared settings endpoints
         .route("/shared", get(list_shared).post(create_share))
         .route("/shared/incoming", get(list_incoming_shared))
+        .route("/invites", get(list_invites).post(create_invite))
+        .route("/invites/:invite_id", delete(revoke_invite))
         .route(
             "/shared/:grantee_id",
             delete(revoke_share).put(update_share),
@@ -33,6 +45,22 @@ pub fn router() -> Router<Arc<AppState>> {
             get(get_messages_for_user).put(update_messages_for_user),
         )
         .route("/:user_id/reset", put(reset_to_defaults_for_user))
+        .route(
+            "/:user_id/shared",
+            get(list_shared_for_user).post(create_share_for_user),
+        )
+        .route(
+            "/:user_id/shared/:grantee_id",
+            delete(revoke_share_for_user).put(update_share_for_user),
+        )
+        .route(
+            "/:user_id/invites",
+            get(list_invites_for_user).post(create_invite_for_user),
+        )
+        .route(
+            "/:user_id/invites/:invite_id",
+            delete(revoke_invite_for_user),
+        )
         .route(
             "/:user_id",
             get(get_settings_for_user).put(update_settings_for_user),
@@ -110,6 +138,97 @@ pub struct UpdateSettingsRequest {
     pub notify_reward_redemption: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CreateShareRequest {
+    pub twitch_login: String,
+    pub role: Option<ShareRole>,
+    /// Legacy flag, used when `role` is absent: `true` grants the manager role
+    pub can_manage: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateShareRequest {
+    pub role: Option<ShareRole>,
+    /// Legacy flag, used when `role` is absent: `true` grants the manager role
+    pub can_manage: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SharedUserResponse {
+    pub grantee_user_id: String,
+    pub grantee_login: String,
+    pub grantee_display_name: String,
+    pub role: ShareRole,
+    /// Whether the grantee has the manager role (kept for older clients)
+    pub can_manage: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl SharedUserResponse {
+    fn new(share: SettingsShare, grantee_login: String, grantee_display_name: String) -> Self {
+        Self {
+            grantee_user_id: share.grantee_user_id,
+            grantee_login,
+            grantee_display_name,
+            role: share.role,
+            can_manage: share.role == ShareRole::Manager,
+            created_at: share.created_at,
+            updated_at: share.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInviteRequest {
+    pub role: ShareRole,
+    /// Invite lifetime; defaults to `DEFAULT_INVITE_TTL_HOURS`
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InviteResponse {
+    pub id: String,
+    pub role: ShareRole,
+    pub created_by: String,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<ShareInvite> for InviteResponse {
+    fn from(invite: ShareInvite) -> Self {
+        Self {
+            id: invite.id,
+            role: invite.role,
+            created_by: invite.created_by,
+            expires_at: invite.expires_at,
+            created_at: invite.created_at,
+        }
+    }
+}
+
+/// A new invite. The token is only returned here; the server keeps just its hash.
+#[derive(Debug, Serialize)]
+pub struct CreatedInviteResponse {
+    #[serde(flatten)]
+    pub invite: InviteResponse,
+    pub token: String,
+    /// Link for the invitee: starts the Twitch login with the invite attached
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncomingShareResponse {
+    pub owner_user_id: String,
+    pub owner_twitch_login: String,
+    pub owner_display_name: String,
+    pub role: ShareRole,
+    /// Whether the current user has the manager role (kept for older clients)
+    pub can_manage: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
@@ -120,15 +239,7 @@ async fn get_messages(
     AuthUser(user): AuthUser,
 ) -> AppResult<Json<MessagesResponse>> {
     let settings = SettingsService::get_settings(&state, &user.id).await?;
-
-    Ok(Json(MessagesResponse {
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
-    }))
+    Ok(messages_response(settings))
 }
 
 /// Update notification message templates
@@ -137,25 +248,7 @@ async fn update_messages(
     AuthUser(user): AuthUser,
     Json(request): Json<UpdateMessagesRequest>,
 ) -> AppResult<Json<MessagesResponse>> {
-    let settings = SettingsService::update_messages(
-        &state,
-        &user.id,
-        request.stream_online_message,
-        request.stream_offline_message,
-        request.stream_title_change_message,
-        request.stream_category_change_message,
-        request.reward_redemption_message,
-    )
-    .await?;
-
-    Ok(Json(MessagesResponse {
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
-    }))
+    apply_messages_update(&state, &user.id, &user.id, request).await
 }
 
 /// Reset settings to defaults
@@ -163,16 +256,7 @@ async fn reset_to_defaults(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
 ) -> AppResult<Json<MessagesResponse>> {
-    let settings = SettingsService::reset_to_defaults(&state, &user.id).await?;
-
-    Ok(Json(MessagesResponse {
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
-    }))
+    apply_reset(&state, &user.id, &user.id).await
 }
 
 /// Get full user settings (messages + aggregated notify flags)
@@ -180,26 +264,7 @@ async fn get_settings(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
 ) -> AppResult<Json<UserSettingsResponse>> {
-    let settings = SettingsService::get_settings(&state, &user.id).await?;
-    let (notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change) =
-        SettingsService::get_aggregated_notify_flags(&state, &user.id).await?;
-
-    Ok(Json(UserSettingsResponse {
-        id: settings.id,
-        user_id: settings.user_id,
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        notify_stream_online,
-        notify_stream_offline,
-        notify_title_change,
-        notify_category_change,
-        notify_reward_redemption: settings.notify_reward_redemption,
-        created_at: settings.created_at,
-        updated_at: settings.updated_at,
-    }))
+    settings_response(&state, &user.id).await
 }
 
 /// Update chat bot settings (notify_reward_redemption) - does NOT affect integrations
@@ -208,13 +273,7 @@ async fn update_settings(
     AuthUser(user): AuthUser,
     Json(request): Json<UpdateSettingsRequest>,
 ) -> AppResult<Json<UserSettingsResponse>> {
-    SettingsService::update_notify_reward_redemption(
-        &state,
-        &user.id,
-        request.notify_reward_redemption,
-    )
-    .await?;
-    get_settings(State(state), AuthUser(user)).await
+    apply_settings_update(&state, &user.id, &user.id, request).await
 }
 
 /// Get full settings for another user (requires share)
@@ -223,91 +282,19 @@ async fn get_settings_for_user(
     AuthUser(user): AuthUser,
     Path(owner_id): Path<String>,
 ) -> AppResult<Json<UserSettingsResponse>> {
-    if owner_id == user.id {
-        return get_settings(State(state), AuthUser(user)).await;
-    }
-
-    if !crate::services::integrations::IntegrationService::check_access(
-        &state, &owner_id, &user.id, false,
-    )
-    .await?
-    {
-        tracing::warn!(
-            "Access denied: user {} attempted to view settings of owner {} without share",
-            user.id,
-            owner_id
-        );
-        return Err(AppError::Forbidden);
-    }
-
-    let settings = SettingsService::get_settings(&state, &owner_id).await?;
-    let (notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change) =
-        SettingsService::get_aggregated_notify_flags(&state, &owner_id).await?;
-
-    Ok(Json(UserSettingsResponse {
-        id: settings.id,
-        user_id: settings.user_id,
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        notify_stream_online,
-        notify_stream_offline,
-        notify_title_change,
-        notify_category_change,
-        notify_reward_redemption: settings.notify_reward_redemption,
-        created_at: settings.created_at,
-        updated_at: settings.updated_at,
-    }))
+    require_permission(&state, &owner_id, &user.id, SharePermission::View).await?;
+    settings_response(&state, &owner_id).await
 }
 
-/// Update chat bot settings for another user (requires manage rights) - does NOT affect integrations
+/// Update chat bot settings for another user (requires the template editor role) - does NOT affect integrations
 async fn update_settings_for_user(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
     Path(owner_id): Path<String>,
     Json(request): Json<UpdateSettingsRequest>,
 ) -> AppResult<Json<UserSettingsResponse>> {
-    if owner_id == user.id {
-        return update_settings(State(state), AuthUser(user), Json(request)).await;
-    }
-
-    if !crate::services::integrations::IntegrationService::check_access(
-        &state, &owner_id, &user.id, true,
-    )
-    .await?
-    {
-        return Err(AppError::Forbidden);
-    }
-
-    SettingsService::update_notify_reward_redemption(
-        &state,
-        &owner_id,
-        request.notify_reward_redemption,
-    )
-    .await?;
-
-    let settings = SettingsService::get_settings(&state, &owner_id).await?;
-    let (notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change) =
-        SettingsService::get_aggregated_notify_flags(&state, &owner_id).await?;
-
-    Ok(Json(UserSettingsResponse {
-        id: settings.id,
-        user_id: settings.user_id,
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        notify_stream_online,
-        notify_stream_offline,
-        notify_title_change,
-        notify_category_change,
-        notify_reward_redemption: settings.notify_reward_redemption,
-        created_at: settings.created_at,
-        updated_at: settings.updated_at,
-    }))
+    require_permission(&state, &owner_id, &user.id, SharePermission::EditTemplates).await?;
+    apply_settings_update(&state, &user.id, &owner_id, request).await
 }
 
 /// List users the current user has shared their settings with
@@ -315,52 +302,7 @@ async fn list_shared(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
 ) -> AppResult<Json<Vec<SharedUserResponse>>> {
-    let rows = SettingsShareRepository::list_with_grantee_info(&state.db, &user.id).await?;
-    let resp: Vec<SharedUserResponse> = rows
-        .into_iter()
-        .map(
-            |(share, grantee_login, grantee_display)| SharedUserResponse {
-                grantee_user_id: share.grantee_user_id,
-                grantee_login,
-                grantee_display_name: grantee_display,
-                can_manage: share.can_manage,
-                created_at: share.created_at,
-                updated_at: share.updated_at,
-            },
-        )
-        .collect();
-    Ok(Json(resp))
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CreateShareRequest {
-    pub twitch_login: String,
-    pub can_manage: Option<bool>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UpdateShareRequest {
-    pub can_manage: bool,
-}
-
-#[derive(Debug, Serialize)]
-pub struct SharedUserResponse {
-    pub grantee_user_id: String,
-    pub grantee_login: String,
-    pub grantee_display_name: String,
-    pub can_manage: bool,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
-}
-
-#[derive(Debug, Serialize)]
-pub struct IncomingShareResponse {
-    pub owner_user_id: String,
-    pub owner_twitch_login: String,
-    pub owner_display_name: String,
-    pub can_manage: bool,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    list_shares_of(&state, &user.id).await
 }
 
 /// Create a new share (grant access to another user)
@@ -369,38 +311,7 @@ async fn create_share(
     AuthUser(user): AuthUser,
     Json(request): Json<CreateShareRequest>,
 ) -> AppResult<Json<SharedUserResponse>> {
-    // Find user by twitch login
-    let grantee = UserRepository::find_by_login(&state.db, &request.twitch_login)
-        .await?
-        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
-
-    if grantee.id == user.id {
-        return Err(AppError::Validation(
-            "Cannot share settings with yourself".to_string(),
-        ));
-    }
-
-    // Ensure we don't duplicate an existing share
-    if SettingsShareRepository::find_by_owner_and_grantee(&state.db, &user.id, &grantee.id)
-        .await?
-        .is_some()
-    {
-        return Err(AppError::Conflict("Share already exists".to_string()));
-    }
-
-    let can_manage = request.can_manage.unwrap_or(false);
-
-    let share =
-        SettingsShareRepository::create(&state.db, &user.id, &grantee.id, can_manage).await?;
-
-    Ok(Json(SharedUserResponse {
-        grantee_user_id: share.grantee_user_id,
-        grantee_login: request.twitch_login,
-        grantee_display_name: grantee.twitch_display_name,
-        can_manage: share.can_manage,
-        created_at: share.created_at,
-        updated_at: share.updated_at,
-    }))
+    apply_share_create(&state, &user.id, &user.id, request).await
 }
 
 /// List incoming shares (owners who shared with the current user)
@@ -417,7 +328,8 @@ async fn list_incoming_shared(
                 owner_user_id: share.owner_user_id,
                 owner_twitch_login: owner_login,
                 owner_display_name: owner_display,
-                can_manage: share.can_manage,
+                role: share.role,
+                can_manage: share.role == ShareRole::Manager,
                 created_at: share.created_at,
                 updated_at: share.updated_at,
             },
@@ -433,37 +345,118 @@ async fn revoke_share(
     AuthUser(user): AuthUser,
     Path(grantee_id): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
-    SettingsShareRepository::delete(&state.db, &user.id, &grantee_id).await?;
-    Ok(Json(serde_json::json!({ "ok": true })))
+    apply_share_revoke(&state, &user.id, &user.id, &grantee_id).await
 }
 
-/// Update an existing share (toggle `can_manage`)
+/// Update an existing share (change its role)
 async fn update_share(
     State(state): State<Arc<AppState>>,
     AuthUser(user): AuthUser,
     Path(grantee_id): Path<String>,
     Json(request): Json<UpdateShareRequest>,
 ) -> AppResult<Json<SharedUserResponse>> {
-    let updated = SettingsShareRepository::update_can_manage(
-        &state.db,
-        &user.id,
-        &grantee_id,
-        request.can_manage,
-    )
-    .await?;
-    // Fetch grantee info
-    let grantee = UserRepository::find_by_id(&state.db, &grantee_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    apply_share_update(&state, &user.id, &user.id, &grantee_id, request).await
+}
 
-    Ok(Json(SharedUserResponse {
-        grantee_user_id: updated.grantee_user_id,
-        grantee_login: grantee.twitch_login,
-        grantee_display_name: grantee.twitch_display_name,
-        can_manage: updated.can_manage,
-        created_at: updated.created_at,
-        updated_at: updated.updated_at,
-    }))
+/// List another user's shares (requires the manager role)
+async fn list_shared_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+) -> AppResult<Json<Vec<SharedUserResponse>>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    list_shares_of(&state, &owner_id).await
+}
+
+/// Share another user's settings on their behalf (requires the manager role)
+async fn create_share_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+    Json(request): Json<CreateShareRequest>,
+) -> AppResult<Json<SharedUserResponse>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    apply_share_create(&state, &user.id, &owner_id, request).await
+}
+
+/// Revoke one of another user's shares (requires the manager role)
+async fn revoke_share_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((owner_id, grantee_id)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    apply_share_revoke(&state, &user.id, &owner_id, &grantee_id).await
+}
+
+/// Change the role of one of another user's shares (requires the manager role)
+async fn update_share_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((owner_id, grantee_id)): Path<(String, String)>,
+    Json(request): Json<UpdateShareRequest>,
+) -> AppResult<Json<SharedUserResponse>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    apply_share_update(&state, &user.id, &owner_id, &grantee_id, request).await
+}
+
+/// List pending invites to the current user's settings
+async fn list_invites(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+) -> AppResult<Json<Vec<InviteResponse>>> {
+    list_invites_of(&state, &user.id).await
+}
+
+/// Create an invite link that shares the current user's settings with whoever accepts it
+async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Json(request): Json<CreateInviteRequest>,
+) -> AppResult<Json<CreatedInviteResponse>> {
+    apply_invite_create(&state, &user.id, &user.id, request).await
+}
+
+/// Revoke a pending invite
+async fn revoke_invite(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(invite_id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    ShareInviteService::revoke_invite(&state, &user.id, &user.id, &invite_id).await?;
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// List pending invites to another user's settings (requires the manager role)
+async fn list_invites_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+) -> AppResult<Json<Vec<InviteResponse>>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    list_invites_of(&state, &owner_id).await
+}
+
+/// Create an invite to another user's settings on their behalf (requires the manager role)
+async fn create_invite_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+    Json(request): Json<CreateInviteRequest>,
+) -> AppResult<Json<CreatedInviteResponse>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    apply_invite_create(&state, &user.id, &owner_id, request).await
+}
+
+/// Revoke a pending invite to another user's settings (requires the manager role)
+async fn revoke_invite_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((owner_id, invite_id)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::ManageShares).await?;
+    ShareInviteService::revoke_invite(&state, &user.id, &owner_id, &invite_id).await?;
+    Ok(Json(serde_json::json!({ "ok": true })))
 }
 
 /// Get notification message templates for another user (if shared)
@@ -472,57 +465,134 @@ async fn get_messages_for_user(
     AuthUser(user): AuthUser,
     Path(owner_id): Path<String>,
 ) -> AppResult<Json<MessagesResponse>> {
-    if owner_id == user.id {
-        return get_messages(State(state), AuthUser(user)).await;
-    }
+    require_permission(&state, &owner_id, &user.id, SharePermission::View).await?;
+    let settings = SettingsService::get_settings(&state, &owner_id).await?;
+    Ok(messages_response(settings))
+}
 
-    if !crate::services::integrations::IntegrationService::check_access(
-        &state, &owner_id, &user.id, false,
-    )
-    .await?
-    {
+/// Update notification message templates for another user (requires the template editor role)
+async fn update_messages_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+    Json(request): Json<UpdateMessagesRequest>,
+) -> AppResult<Json<MessagesResponse>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::EditTemplates).await?;
+    apply_messages_update(&state, &user.id, &owner_id, request).await
+}
+
+/// Reset settings to defaults for another user (requires the template editor role)
+async fn reset_to_defaults_for_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(owner_id): Path<String>,
+) -> AppResult<Json<MessagesResponse>> {
+    require_permission(&state, &owner_id, &user.id, SharePermission::EditTemplates).await?;
+    apply_reset(&state, &user.id, &owner_id).await
+}
+
+// ============================================================================
+// Shared Implementation
+// ============================================================================
+//
+// Owners and grantees go through the same functions below: `actor_id` is the
+// authenticated user, `owner_id` the account whose settings change. Access is
+// checked by the handlers; every change is written to the audit log.
+
+/// Reject the request unless `user_id` holds `permission` on `owner_id`'s settings
+async fn require_permission(
+    state: &Arc<AppState>,
+    owner_id: &str,
+    user_id: &str,
+    permission: SharePermission,
+) -> AppResult<()> {
+    if !IntegrationService::check_access(state, owner_id, user_id, permission).await? {
         tracing::warn!(
-            "Access denied: user {} attempted to view messages of owner {} without share",
-            user.id,
+            "Access denied: user {} lacks {:?} permission on settings of owner {}",
+            user_id,
+            permission,
             owner_id
         );
         return Err(AppError::Forbidden);
     }
+    Ok(())
+}
 
-    let settings = SettingsService::get_settings(&state, &owner_id).await?;
+async fn settings_response(
+    state: &Arc<AppState>,
+    owner_id: &str,
+) -> AppResult<Json<UserSettingsResponse>> {
+    let settings = SettingsService::get_settings(state, owner_id).await?;
+    let (notify_stream_online, notify_stream_offline, notify_title_change, notify_category_change) =
+        SettingsService::get_aggregated_notify_flags(state, owner_id).await?;
 
-    Ok(Json(MessagesResponse {
+    Ok(Json(UserSettingsResponse {
+        id: settings.id,
+        user_id: settings.user_id,
         stream_online_message: settings.stream_online_message,
         stream_offline_message: settings.stream_offline_message,
         stream_title_change_message: settings.stream_title_change_message,
         stream_category_change_message: settings.stream_category_change_message,
         reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
+        notify_stream_online,
+        notify_stream_offline,
+        notify_title_change,
+        notify_category_change,
+        notify_reward_redemption: settings.notify_reward_redemption,
+        created_at: settings.created_at,
+        updated_at: settings.updated_at,
     }))
 }
 
-/// Update notification message templates for another user (if shared with manage rights)
-async fn update_messages_for_user(
-    State(state): State<Arc<AppState>>,
-    AuthUser(user): AuthUser,
-    Path(owner_id): Path<String>,
-    Json(request): Json<UpdateMessagesRequest>,
-) -> AppResult<Json<MessagesResponse>> {
-    if owner_id == user.id {
-        return update_messages(State(state), AuthUser(user), Json(request)).await;
-    }
+fn messages_response(settings: NotificationSettings) -> Json<MessagesResponse> {
+    Json(MessagesResponse {
+        stream_online_message: settings.stream_online_message,
+        stream_offline_message: settings.stream_offline_message,
+        stream_title_change_message: settings.stream_title_change_message,
+        stream_category_change_message: settings.stream_category_change_message,
+        reward_redemption_message: settings.reward_redemption_message,
+        placeholders: get_placeholders_info(),
+    })
+}
 
-    if !crate::services::integrations::IntegrationService::check_access(
-        &state, &owner_id, &user.id, true,
+async fn apply_settings_update(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    request: UpdateSettingsRequest,
+) -> AppResult<Json<UserSettingsResponse>> {
+    let before = SettingsService::get_settings(state, owner_id).await?;
+    let after = SettingsService::update_notify_reward_redemption(
+        state,
+        owner_id,
+        request.notify_reward_redemption,
     )
-    .await?
-    {
-        return Err(AppError::Forbidden);
-    }
+    .await?;
+
+    AuditService::record(
+        state,
+        actor_id,
+        owner_id,
+        AUDIT_ENTITY_SETTINGS,
+        None,
+        AUDIT_ACTION_UPDATE,
+        AuditService::diff(&before, &after),
+    )
+    .await;
 
-    let settings = SettingsService::update_messages(
-        &state,
-        &owner_id,
+    settings_response(state, owner_id).await
+}
+
+async fn apply_messages_update(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    request: UpdateMessagesRequest,
+) -> AppResult<Json<MessagesResponse>> {
+    let before = SettingsService::get_settings(state, owner_id).await?;
+    let after = SettingsService::update_messages(
+        state,
+        owner_id,
         request.stream_online_message,
         request.stream_offline_message,
         request.stream_title_change_message,
@@ -531,46 +601,219 @@ async fn update_messages_for_user(
     )
     .await?;
 
-    Ok(Json(MessagesResponse {
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
-    }))
+    AuditService::record(
+        state,
+        actor_id,
+        owner_id,
+        AUDIT_ENTITY_MESSAGES,
+        None,
+        AUDIT_ACTION_UPDATE,
+        AuditService::diff(&before, &after),
+    )
+    .await;
+
+    Ok(messages_response(after))
 }
 
-/// Reset settings to defaults for another user (if shared with manage rights)
-async fn reset_to_defaults_for_user(
-    State(state): State<Arc<AppState>>,
-    AuthUser(user): AuthUser,
-    Path(owner_id): Path<String>,
+async fn apply_reset(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
 ) -> AppResult<Json<MessagesResponse>> {
-    if owner_id == user.id {
-        return reset_to_defaults(State(state), AuthUser(user)).await;
+    let before = SettingsService::get_settings(state, owner_id).await?;
+    let after = SettingsService::reset_to_defaults(state, owner_id).await?;
+
+    AuditService::record(
+        state,
+        actor_id,
+        owner_id,
+        AUDIT_ENTITY_MESSAGES,
+        None,
+        AUDIT_ACTION_RESET,
+        AuditService::diff(&before, &after),
+    )
+    .await;
+
+    Ok(messages_response(after))
+}
+
+async fn list_shares_of(
+    state: &Arc<AppState>,
+    owner_id: &str,
+) -> AppResult<Json<Vec<SharedUserResponse>>> {
+    let rows = SettingsShareRepository::list_with_grantee_info(&state.db, owner_id).await?;
+    let resp: Vec<SharedUserResponse> = rows
+        .into_iter()
+        .map(|(share, grantee_login, grantee_display)| {
+            SharedUserResponse::new(share, grantee_login, grantee_display)
+        })
+        .collect();
+    Ok(Json(resp))
+}
+
+async fn apply_share_create(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    request: CreateShareRequest,
+) -> AppResult<Json<SharedUserResponse>> {
+    // Find user by twitch login
+    let grantee = UserRepository::find_by_login(&state.db, &request.twitch_login)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if grantee.id == owner_id {
+        return Err(AppError::Validation(
+            "Cannot share settings with yourself".to_string(),
+        ));
     }
 
-    if !crate::services::integrations::IntegrationService::check_access(
-        &state, &owner_id, &user.id, true,
-    )
-    .await?
+    // Ensure we don't duplicate an existing share
+    if SettingsShareRepository::find_by_owner_and_grantee(&state.db, owner_id, &grantee.id)
+        .await?
+        .is_some()
     {
-        return Err(AppError::Forbidden);
+        return Err(AppError::Conflict("Share already exists".to_string()));
     }
 
-    let settings = SettingsService::reset_to_defaults(&state, &owner_id).await?;
+    let role = requested_role(request.role, request.can_manage).unwrap_or(ShareRole::Viewer);
 
-    Ok(Json(MessagesResponse {
-        stream_online_message: settings.stream_online_message,
-        stream_offline_message: settings.stream_offline_message,
-        stream_title_change_message: settings.stream_title_change_message,
-        stream_category_change_message: settings.stream_category_change_message,
-        reward_redemption_message: settings.reward_redemption_message,
-        placeholders: get_placeholders_info(),
+    let share = SettingsShareRepository::create(&state.db, owner_id, &grantee.id, role).await?;
+
+    AuditService::record(
+        state,
+        actor_id,
+        owner_id,
+        AUDIT_ENTITY_SHARE,
+        Some(&share.grantee_user_id),
+        AUDIT_ACTION_CREATE,
+        serde_json::json!({ "grantee_login": grantee.twitch_login, "role": role }),
+    )
+    .await;
+
+    Ok(Json(SharedUserResponse::new(
+        share,
+        grantee.twitch_login,
+        grantee.twitch_display_name,
+    )))
+}
+
+async fn apply_share_revoke(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    grantee_id: &str,
+) -> AppResult<Json<serde_json::Value>> {
+    let existing =
+        SettingsShareRepository::find_by_owner_and_grantee(&state.db, owner_id, grantee_id).await?;
+
+    if SettingsShareRepository::delete(&state.db, owner_id, grantee_id).await? {
+        ShareInviteRepository::delete_created_by(&state.db, owner_id, grantee_id).await?;
+        AuditService::record(
+            state,
+            actor_id,
+            owner_id,
+            AUDIT_ENTITY_SHARE,
+            Some(grantee_id),
+            AUDIT_ACTION_DELETE,
+            serde_json::json!({ "role": existing.map(|share| share.role) }),
+        )
+        .await;
+    }
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+async fn apply_share_update(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    grantee_id: &str,
+    request: UpdateShareRequest,
+) -> AppResult<Json<SharedUserResponse>> {
+    let role = requested_role(request.role, request.can_manage)
+        .ok_or_else(|| AppError::Validation(t("validation.share_role_required")))?;
+
+    let existing =
+        SettingsShareRepository::find_by_owner_and_grantee(&state.db, owner_id, grantee_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(t("not_found.share")))?;
+
+    let updated = SettingsShareRepository::update_role(&state.db, owner_id, grantee_id, role)
+        .await?
+        .ok_or_else(|| AppError::NotFound(t("not_found.share")))?;
+
+    if !updated.role.allows(SharePermission::ManageShares) {
+        ShareInviteRepository::delete_created_by(&state.db, owner_id, grantee_id).await?;
+    }
+
+    if existing.role != updated.role {
+        AuditService::record(
+            state,
+            actor_id,
+            owner_id,
+            AUDIT_ENTITY_SHARE,
+            Some(grantee_id),
+            AUDIT_ACTION_UPDATE,
+            AuditService::diff(&existing, &updated),
+        )
+        .await;
+    }
+
+    // Fetch grantee info
+    let grantee = UserRepository::find_by_id(&state.db, grantee_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    Ok(Json(SharedUserResponse::new(
+        updated,
+        grantee.twitch_login,
+        grantee.twitch_display_name,
+    )))
+}
+
+async fn list_invites_of(
+    state: &Arc<AppState>,
+    owner_id: &str,
+) -> AppResult<Json<Vec<InviteResponse>>> {
+    let invites = ShareInviteRepository::list_active_for_owner(&state.db, owner_id).await?;
+    Ok(Json(
+        invites.into_iter().map(InviteResponse::from).collect(),
+    ))
+}
+
+async fn apply_invite_create(
+    state: &Arc<AppState>,
+    actor_id: &str,
+    owner_id: &str,
+    request: CreateInviteRequest,
+) -> AppResult<Json<CreatedInviteResponse>> {
+    let ttl_hours = request.expires_in_hours.unwrap_or(DEFAULT_INVITE_TTL_HOURS);
+
+    let (invite, token) =
+        ShareInviteService::create_invite(state, owner_id, actor_id, request.role, ttl_hours)
+            .await?;
+
+    Ok(Json(CreatedInviteResponse {
+        invite: invite.into(),
+        url: ShareInviteService::invite_url(state, &token),
+        token,
     }))
 }
 
+/// Role from a share request; the legacy `can_manage` flag maps to manager / viewer
+fn requested_role(role: Option<ShareRole>, can_manage: Option<bool>) -> Option<ShareRole> {
+    role.or_else(|| {
+        can_manage.map(|manage| {
+            if manage {
+                ShareRole::Manager
+            } else {
+                ShareRole::Viewer
+            }
+        })
+    })
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{CreateWatchedChannel, WatchedChannel, WatchedChannelRepository};
+use crate::error::{AppError, AppResult};
+use crate::routes::auth::AuthUser;
+use crate::services::subscriptions::SubscriptionManager;
+use crate::AppState;
+
+/// Router for follow mode: channels the user watches without owning them
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_watched).post(add_watched))
+        .route("/:id", delete(remove_watched))
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct AddWatchedChannelRequest {
+    /// Twitch login of the channel to watch
+    pub twitch_login: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchedChannelResponse {
+    pub id: String,
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_display_name: String,
+    pub broadcaster_profile_image_url: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<WatchedChannel> for WatchedChannelResponse {
+    fn from(w: WatchedChannel) -> Self {
+        Self {
+            id: w.id,
+            broadcaster_id: w.broadcaster_id,
+            broadcaster_login: w.broadcaster_login,
+            broadcaster_display_name: w.broadcaster_display_name,
+            broadcaster_profile_image_url: w.broadcaster_profile_image_url,
+            created_at: w.created_at,
+        }
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List channels watched by the current user
+async fn list_watched(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+) -> AppResult<Json<Vec<WatchedChannelResponse>>> {
+    let channels = WatchedChannelRepository::find_by_user_id(&state.db, &user.id).await?;
+    Ok(Json(channels.into_iter().map(Into::into).collect()))
+}
+
+/// Start watching a channel by Twitch login
+async fn add_watched(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Json(request): Json<AddWatchedChannelRequest>,
+) -> AppResult<Json<WatchedChannelResponse>> {
+    let login = request.twitch_login.trim().to_lowercase();
+    if login.is_empty() || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(AppError::Validation("Invalid Twitch login".to_string()));
+    }
+
+    // Resolve the channel via Helix; the app token is enough for public user data
+    let app_token = state.twitch.get_valid_app_access_token().await?;
+    let twitch_user = state
+        .twitch
+        .get_users_by_logins(&app_token, &[login.as_str()])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::NotFound("Twitch channel not found".to_string()))?;
+
+    if twitch_user.id == user.twitch_id {
+        return Err(AppError::Validation(
+            "Your own channel is already tracked".to_string(),
+        ));
+    }
+
+    if WatchedChannelRepository::exists(&state.db, &user.id, &twitch_user.id).await? {
+        return Err(AppError::Conflict("Channel is already watched".to_string()));
+    }
+
+    let watched = WatchedChannelRepository::create(
+        &state.db,
+        &user.id,
+        CreateWatchedChannel {
+            broadcaster_id: twitch_user.id,
+            broadcaster_login: twitch_user.login,
+            broadcaster_display_name: twitch_user.display_name,
+            broadcaster_profile_image_url: twitch_user.profile_image_url,
+        },
+    )
+    .await?;
+
+    spawn_watched_sync(&state);
+
+    Ok(Json(watched.into()))
+}
+
+/// Stop watching a channel
+async fn remove_watched(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    let watched = WatchedChannelRepository::find_by_id(&state.db, &id)
+        .await?
+        .filter(|w| w.user_id == user.id)
+        .ok_or_else(|| AppError::NotFound("Watched channel not found".to_string()))?;
+
+    WatchedChannelRepository::delete(&state.db, &watched.id).await?;
+
+    spawn_watched_sync(&state);
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Synchronize watched channel EventSub subscriptions in the background
+fn spawn_watched_sync(state: &Arc<AppState>) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = SubscriptionManager::sync_watched_channels(&state).await {
+            tracing::warn!("Failed to sync watched channel subscriptions: {:?}", e);
+        }
+    });
+}
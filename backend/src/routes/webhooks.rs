@@ -5,10 +5,13 @@ use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
     routing::post,
-    Router,
+    Json, Router,
 };
+use serde_json::Value;
 
 use crate::error::AppError;
+use crate::metrics;
+use crate::services::discord_interactions::{DiscordInteractionService, Interaction};
 use crate::services::webhooks::{EventSubPayload, WebhookService};
 use crate::AppState;
 
@@ -17,7 +20,22 @@ const MESSAGE_TYPE_NOTIFICATION: &str = "notification";
 const MESSAGE_TYPE_REVOCATION: &str = "revocation";
 
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new().route("/twitch", post(handle_twitch_webhook))
+    Router::new()
+        .route("/twitch", post(handle_twitch_webhook))
+        .route("/discord/interactions", post(handle_discord_interaction))
+}
+
+async fn handle_discord_interaction(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, AppError> {
+    DiscordInteractionService::verify_signature(&state, &headers, &body)?;
+
+    let interaction: Interaction = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid interaction: {}", e)))?;
+
+    DiscordInteractionService::handle(&state, interaction).map(Json)
 }
 
 async fn handle_twitch_webhook(
@@ -28,7 +46,13 @@ async fn handle_twitch_webhook(
     let (message_id, timestamp, signature, message_type) =
         WebhookService::extract_headers(&headers)?;
 
-    WebhookService::verify_signature(&state, &message_id, &timestamp, &body, &signature)?;
+    if let Err(e) =
+        WebhookService::verify_signature(&state, &message_id, &timestamp, &body, &signature)
+    {
+        // The payload is untrusted, so its subscription type isn't used as a label
+        metrics::record_webhook("unknown", "rejected");
+        return Err(e);
+    }
 
     let payload: EventSubPayload = serde_json::from_slice(&body)
         .map_err(|e| AppError::BadRequest(format!("Invalid payload: {}", e)))?;
@@ -50,6 +74,7 @@ async fn handle_twitch_webhook(
             Ok((StatusCode::OK, "OK".to_string()))
         }
         MESSAGE_TYPE_REVOCATION => {
+            metrics::record_webhook(&payload.subscription.subscription_type, "revoked");
             tracing::warn!(
                 "Subscription revoked: id={}, type={}, reason={}",
                 payload.subscription.id,
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+
+use crate::db::{
+    DiscordIntegration, DiscordIntegrationRepository, NotificationLog, NotificationLogRepository,
+    NotificationSettings, NotificationSettingsRepository, SettingsShare, SettingsShareRepository,
+    ShareRole, SyncedCalendarEvent, SyncedCalendarRepository, TelegramIntegration,
+    TelegramIntegrationRepository, User, UserRepository,
+};
+use crate::error::AppResult;
+use crate::services::audit::REDACTED;
+use crate::services::auth::AuthService;
+use crate::services::calendar::CalendarSyncManager;
+use crate::services::integrations::IntegrationService;
+use crate::services::subscriptions::SubscriptionManager;
+use crate::AppState;
+
+// ============================================================================
+// Export Types
+// ============================================================================
+
+/// Everything stored about a user, as returned by `GET /api/account/export`
+#[derive(Debug, Serialize)]
+pub struct AccountExport {
+    pub exported_at: NaiveDateTime,
+    pub profile: AccountProfile,
+    pub settings: Option<NotificationSettings>,
+    pub telegram_integrations: Vec<TelegramIntegration>,
+    pub discord_integrations: Vec<DiscordIntegration>,
+    pub shares: AccountShares,
+    pub notification_history: Vec<NotificationLog>,
+    pub calendar_events: Vec<SyncedCalendarEvent>,
+}
+
+/// The user's profile without OAuth tokens
+#[derive(Debug, Serialize)]
+pub struct AccountProfile {
+    pub id: String,
+    pub twitch_id: String,
+    pub twitch_login: String,
+    pub twitch_display_name: String,
+    pub twitch_email: String,
+    pub twitch_profile_image_url: String,
+    pub telegram_user_id: Option<String>,
+    pub telegram_username: Option<String>,
+    pub discord_user_id: Option<String>,
+    pub discord_username: Option<String>,
+    pub lang: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<User> for AccountProfile {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            twitch_id: user.twitch_id,
+            twitch_login: user.twitch_login,
+            twitch_display_name: user.twitch_display_name,
+            twitch_email: user.twitch_email,
+            twitch_profile_image_url: user.twitch_profile_image_url,
+            telegram_user_id: user.telegram_user_id,
+            telegram_username: user.telegram_username,
+            discord_user_id: user.discord_user_id,
+            discord_username: user.discord_username,
+            lang: user.lang,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountShares {
+    /// Users the account shared its settings with
+    pub granted: Vec<ExportedShare>,
+    /// Accounts that shared their settings with the user
+    pub received: Vec<ExportedShare>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedShare {
+    /// The other side of the share
+    pub user_id: String,
+    pub twitch_login: String,
+    pub twitch_display_name: String,
+    pub role: ShareRole,
+    pub created_at: NaiveDateTime,
+}
+
+impl ExportedShare {
+    fn new(
+        share: SettingsShare,
+        user_id: String,
+        twitch_login: String,
+        twitch_display_name: String,
+    ) -> Self {
+        Self {
+            user_id,
+            twitch_login,
+            twitch_display_name,
+            role: share.role,
+            created_at: share.created_at,
+        }
+    }
+}
+
+// ============================================================================
+// Account Service
+// ============================================================================
+
+pub struct AccountService;
+
+impl AccountService {
+    /// Collect the user's data into a single archive
+    pub async fn export(state: &Arc<AppState>, user: User) -> AppResult<AccountExport> {
+        let user_id = user.id.clone();
+
+        let settings = NotificationSettingsRepository::find_by_user_id(&state.db, &user_id).await?;
+        let telegram_integrations =
+            TelegramIntegrationRepository::find_by_user_id(&state.db, &user_id).await?;
+        // Webhook URLs are credentials: the export only shows whether one is set
+        let discord_integrations =
+            DiscordIntegrationRepository::find_by_user_id(&state.db, &user_id)
+                .await?
+                .into_iter()
+                .map(|mut integration| {
+                    integration.discord_webhook_url = integration
+                        .discord_webhook_url
+                        .map(|_| REDACTED.to_string());
+                    integration
+                })
+                .collect();
+
+        let granted = SettingsShareRepository::list_with_grantee_info(&state.db, &user_id)
+            .await?
+            .into_iter()
+            .map(|(share, login, display_name)| {
+                let grantee = share.grantee_user_id.clone();
+                ExportedShare::new(share, grantee, login, display_name)
+            })
+            .collect();
+        let received = SettingsShareRepository::list_with_owner_info(&state.db, &user_id)
+            .await?
+            .into_iter()
+            .map(|(share, login, display_name)| {
+                let owner = share.owner_user_id.clone();
+                ExportedShare::new(share, owner, login, display_name)
+            })
+            .collect();
+
+        let notification_history =
+            NotificationLogRepository::find_all_by_user_id(&state.db, &user_id).await?;
+        let calendar_events =
+            SyncedCalendarRepository::find_by_user_id(&state.db, &user_id).await?;
+
+        Ok(AccountExport {
+            exported_at: Utc::now().naive_utc(),
+            profile: user.into(),
+            settings,
+            telegram_integrations,
+            discord_integrations,
+            shares: AccountShares { granted, received },
+            notification_history,
+            calendar_events,
+        })
+    }
+
+    /// Delete the user's account.
+    ///
+    /// External resources are cleaned up first (best effort: failures are logged so a
+    /// broken Twitch or Discord connection can't keep a user from leaving), then the user
+    /// row is deleted and everything referencing it cascades.
+    pub async fn delete_account(state: &Arc<AppState>, user: &User) -> AppResult<()> {
+        if let Err(e) = CalendarSyncManager::delete_events_for_user(state, &user.id).await {
+            tracing::warn!(
+                "Failed to delete Discord scheduled events of user {}: {:?}",
+                user.id,
+                e
+            );
+        }
+
+        // Removes the webhooks the bot created for the integrations
+        match DiscordIntegrationRepository::find_by_user_id(&state.db, &user.id).await {
+            Ok(integrations) => {
+                for integration in integrations {
+                    if let Err(e) =
+                        IntegrationService::delete_discord_integration(state, &integration.id).await
+                    {
+                        tracing::warn!(
+                            "Failed to delete Discord integration {} of user {}: {:?}",
+                            integration.id,
+                            user.id,
+                            e
+                        );
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to load Discord integrations of user {}: {:?}",
+                user.id,
+                e
+            ),
+        }
+
+        if let Err(e) = SubscriptionManager::remove_for_user(state, &user.id).await {
+            tracing::warn!(
+                "Failed to remove EventSub subscriptions of user {}: {:?}",
+                user.id,
+                e
+            );
+        }
+
+        for token in [&user.twitch_access_token, &user.twitch_refresh_token] {
+            if let Err(e) = state.twitch.revoke_token(token).await {
+                tracing::warn!("Failed to revoke Twitch token of user {}: {:?}", user.id, e);
+            }
+        }
+
+        UserRepository::delete(&state.db, &user.id).await?;
+
+        if let Some(telegram_id) = &user.telegram_user_id {
+            AuthService::remove_telegram_photos(telegram_id).await;
+        }
+
+        // Watchers of this streamer were served by the user's own subscriptions, and the
+        // user's watched broadcasters may now have no watchers left
+        {
+            let state = state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = SubscriptionManager::sync_watched_channels(&state).await {
+                    tracing::warn!("Failed to sync watched channel subscriptions: {:?}", e);
+                }
+            });
+        }
+
+        tracing::info!(
+            "Deleted account of user {} ({})",
+            user.id,
+            user.twitch_login
+        );
+
+        Ok(())
+    }
+}
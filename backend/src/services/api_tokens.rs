@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::db::{ApiToken, ApiTokenRepository, ApiTokenScope, User, UserRepository};
+use crate::error::{AppError, AppResult};
+use crate::i18n::t;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_CREATE, AUDIT_ACTION_DELETE, AUDIT_ENTITY_API_TOKEN,
+};
+use crate::services::auth::AuthService;
+use crate::AppState;
+
+/// Prefix that tells API tokens apart from session JWTs in the `Authorization` header
+pub const API_TOKEN_PREFIX: &str = "tn_";
+/// Length of the random part of a token
+const API_TOKEN_LENGTH: usize = 40;
+/// Characters of the token kept in clear to identify it in the token list
+const API_TOKEN_DISPLAY_PREFIX_LENGTH: usize = 8;
+/// Longest allowed token name
+const MAX_API_TOKEN_NAME_LENGTH: usize = 100;
+/// Longest allowed token lifetime
+pub const MAX_API_TOKEN_TTL_DAYS: i64 = 365;
+/// Tokens a single user may hold
+pub const MAX_API_TOKENS_PER_USER: i64 = 25;
+
+pub struct ApiTokenService;
+
+impl ApiTokenService {
+    /// Whether a bearer token is an API token rather than a session JWT
+    pub fn is_api_token(token: &str) -> bool {
+        token.starts_with(API_TOKEN_PREFIX)
+    }
+
+    /// Create a token for `user_id`. Returns the token record and the token itself;
+    /// only the token's hash is stored, so it can't be shown again.
+    pub async fn create_token(
+        state: &Arc<AppState>,
+        user_id: &str,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> AppResult<(ApiToken, String)> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_API_TOKEN_NAME_LENGTH {
+            return Err(AppError::Validation(t("validation.api_token_name_invalid")));
+        }
+
+        let scopes = Self::parse_scopes(scopes)?;
+
+        if let Some(days) = expires_in_days {
+            if !(1..=MAX_API_TOKEN_TTL_DAYS).contains(&days) {
+                return Err(AppError::Validation(t("validation.api_token_ttl_invalid")));
+            }
+        }
+
+        if ApiTokenRepository::count_for_user(&state.db, user_id).await? >= MAX_API_TOKENS_PER_USER
+        {
+            return Err(AppError::Validation(t("validation.api_token_limit")));
+        }
+
+        let token = format!(
+            "{}{}",
+            API_TOKEN_PREFIX,
+            AuthService::generate_random_string(API_TOKEN_LENGTH)
+        );
+        let display_prefix: String = token
+            .chars()
+            .take(API_TOKEN_DISPLAY_PREFIX_LENGTH)
+            .collect();
+        let expires_at =
+            expires_in_days.map(|days| (Utc::now() + Duration::days(days)).naive_utc());
+
+        let api_token = ApiTokenRepository::create(
+            &state.db,
+            &Uuid::new_v4().to_string(),
+            user_id,
+            name,
+            &AuthService::hash_token(&token),
+            &display_prefix,
+            &scopes,
+            expires_at,
+        )
+        .await?;
+
+        AuditService::record(
+            state,
+            user_id,
+            user_id,
+            AUDIT_ENTITY_API_TOKEN,
+            Some(&api_token.id),
+            AUDIT_ACTION_CREATE,
+            serde_json::json!({
+                "name": api_token.name,
+                "scopes": api_token.scopes,
+                "expires_at": api_token.expires_at,
+            }),
+        )
+        .await;
+
+        Ok((api_token, token))
+    }
+
+    pub async fn revoke_token(state: &Arc<AppState>, user_id: &str, id: &str) -> AppResult<()> {
+        let api_token = ApiTokenRepository::delete_for_user(&state.db, id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(t("not_found.api_token")))?;
+
+        AuditService::record(
+            state,
+            user_id,
+            user_id,
+            AUDIT_ENTITY_API_TOKEN,
+            Some(&api_token.id),
+            AUDIT_ACTION_DELETE,
+            serde_json::json!({ "name": api_token.name }),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Authenticate a request made with an API token. The token must be active and
+    /// carry the scope the endpoint requires.
+    pub async fn authenticate(
+        state: &Arc<AppState>,
+        token: &str,
+        method: &http::Method,
+        path: &str,
+    ) -> AppResult<User> {
+        let api_token = ApiTokenRepository::find_active_by_token_hash(
+            &state.db,
+            &AuthService::hash_token(token),
+        )
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        let Some(required) = ApiTokenScope::required_for(method, path) else {
+            tracing::debug!(
+                "API token {} used for {} {}, which is not available to API tokens",
+                api_token.id,
+                method,
+                path
+            );
+            return Err(AppError::Forbidden);
+        };
+        if !api_token.scopes.contains(&required) {
+            tracing::debug!(
+                "API token {} lacks scope {} for {} {}",
+                api_token.id,
+                required.as_str(),
+                method,
+                path
+            );
+            return Err(AppError::Forbidden);
+        }
+
+        let user = UserRepository::find_by_id(&state.db, &api_token.user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if let Err(e) = ApiTokenRepository::touch(&state.db, &api_token.id).await {
+            tracing::warn!(
+                "Failed to update last use of API token {}: {:?}",
+                api_token.id,
+                e
+            );
+        }
+
+        Ok(user)
+    }
+
+    /// Validate requested scope names; at least one is required
+    fn parse_scopes(scopes: &[String]) -> AppResult<Vec<ApiTokenScope>> {
+        let mut parsed: Vec<ApiTokenScope> = Vec::new();
+        for scope in scopes {
+            let scope = ApiTokenScope::from_str(scope.trim())
+                .ok_or_else(|| AppError::Validation(t("validation.api_token_scopes_invalid")))?;
+            if !parsed.contains(&scope) {
+                parsed.push(scope);
+            }
+        }
+
+        if parsed.is_empty() {
+            return Err(AppError::Validation(t(
+                "validation.api_token_scopes_invalid",
+            )));
+        }
+        Ok(parsed)
+    }
+}
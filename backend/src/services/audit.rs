@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::db::AuditEventRepository;
+use crate::AppState;
+
+/// Audited entities
+pub const AUDIT_ENTITY_SETTINGS: &str = "settings";
+pub const AUDIT_ENTITY_MESSAGES: &str = "messages";
+pub const AUDIT_ENTITY_SHARE: &str = "share";
+pub const AUDIT_ENTITY_SHARE_INVITE: &str = "share_invite";
+pub const AUDIT_ENTITY_TELEGRAM_INTEGRATION: &str = "telegram_integration";
+pub const AUDIT_ENTITY_DISCORD_INTEGRATION: &str = "discord_integration";
+pub const AUDIT_ENTITY_TELEGRAM_LINK_CODE: &str = "telegram_link_code";
+pub const AUDIT_ENTITY_TELEGRAM_ACCOUNT: &str = "telegram_account";
+pub const AUDIT_ENTITY_DISCORD_ACCOUNT: &str = "discord_account";
+pub const AUDIT_ENTITY_API_TOKEN: &str = "api_token";
+pub const AUDIT_ENTITY_ACCOUNT: &str = "account";
+
+/// Actors of changes not made by a signed-in user: the bots' chat commands and
+/// automatic changes such as disabling an unreachable integration
+pub const AUDIT_ACTOR_SYSTEM: &str = "system";
+pub const AUDIT_ACTOR_TELEGRAM_BOT: &str = "telegram_bot";
+pub const AUDIT_ACTOR_DISCORD_BOT: &str = "discord_bot";
+
+/// Audited actions
+pub const AUDIT_ACTION_CREATE: &str = "create";
+pub const AUDIT_ACTION_UPDATE: &str = "update";
+pub const AUDIT_ACTION_DELETE: &str = "delete";
+pub const AUDIT_ACTION_RESET: &str = "reset";
+pub const AUDIT_ACTION_LINK: &str = "link";
+pub const AUDIT_ACTION_UNLINK: &str = "unlink";
+pub const AUDIT_ACTION_SUSPEND: &str = "suspend";
+pub const AUDIT_ACTION_UNSUSPEND: &str = "unsuspend";
+
+/// Fields that change on every write and carry no information in a diff
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+/// Secrets: the log records that they changed, not their values
+const REDACTED_FIELDS: &[&str] = &["discord_webhook_url"];
+pub(crate) const REDACTED: &str = "<redacted>";
+
+pub struct AuditService;
+
+impl AuditService {
+    /// Record a change made by `actor_id` to resources of `owner_id`.
+    ///
+    /// The change itself has already been applied, so a failure to write the event is
+    /// logged rather than returned to the client.
+    pub async fn record(
+        state: &Arc<AppState>,
+        actor_id: &str,
+        owner_id: &str,
+        entity: &str,
+        entity_id: Option<&str>,
+        action: &str,
+        details: Value,
+    ) {
+        let details = (!details.is_null()).then(|| details.to_string());
+
+        if let Err(e) = AuditEventRepository::create(
+            &state.db,
+            actor_id,
+            owner_id,
+            entity,
+            entity_id,
+            action,
+            details.as_deref(),
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record audit event {} {} by {} for owner {}: {:?}",
+                action,
+                entity,
+                actor_id,
+                owner_id,
+                e
+            );
+        }
+    }
+
+    /// Top-level fields that differ between two versions of an entity,
+    /// as `{"field": {"from": old, "to": new}}`
+    pub fn diff<T: Serialize>(before: &T, after: &T) -> Value {
+        let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+            (serde_json::to_value(before), serde_json::to_value(after))
+        else {
+            return Value::Null;
+        };
+
+        let changes: Map<String, Value> = after
+            .iter()
+            .filter(|(key, _)| !IGNORED_FIELDS.contains(&key.as_str()))
+            .filter_map(|(key, new)| {
+                let old = before.get(key).unwrap_or(&Value::Null);
+                (old != new).then(|| {
+                    let change = if REDACTED_FIELDS.contains(&key.as_str()) {
+                        json!({ "from": redact(old), "to": redact(new) })
+                    } else {
+                        json!({ "from": old, "to": new })
+                    };
+                    (key.clone(), change)
+                })
+            })
+            .collect();
+
+        Value::Object(changes)
+    }
+
+    /// An entity as recorded for create and delete events (secrets redacted)
+    pub fn snapshot<T: Serialize>(value: &T) -> Value {
+        let Ok(Value::Object(mut fields)) = serde_json::to_value(value) else {
+            return Value::Null;
+        };
+
+        for key in REDACTED_FIELDS {
+            if let Some(field) = fields.get_mut(*key) {
+                *field = redact(field);
+            }
+        }
+        Value::Object(fields)
+    }
+}
+
+fn redact(value: &Value) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        _ => Value::String(REDACTED.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Template {
+        online: &'static str,
+        offline: &'static str,
+        discord_webhook_url: Option<&'static str>,
+        updated_at: u32,
+    }
+
+    #[test]
+    fn diff_lists_changed_fields_only() {
+        let before = Template {
+            online: "live",
+            offline: "bye",
+            discord_webhook_url: None,
+            updated_at: 1,
+        };
+        let after = Template {
+            online: "live now",
+            offline: "bye",
+            discord_webhook_url: Some("https://discord.com/api/webhooks/1/secret"),
+            updated_at: 2,
+        };
+
+        assert_eq!(
+            AuditService::diff(&before, &after),
+            json!({
+                "online": { "from": "live", "to": "live now" },
+                "discord_webhook_url": { "from": null, "to": "<redacted>" },
+            })
+        );
+        assert_eq!(
+            AuditService::snapshot(&after)["discord_webhook_url"],
+            json!("<redacted>")
+        );
+    }
+}
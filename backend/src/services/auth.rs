@@ -5,9 +5,10 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation}
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::db::UserRepository;
+use crate::db::{Session, SessionRepository, UserRepository};
 use crate::error::{AppError, AppResult};
 use crate::services::discord::{exchange_code_for_token, get_discord_user};
+use crate::services::share_invites::ShareInviteService;
 use crate::services::subscriptions::SubscriptionManager;
 use crate::services::telegram::verify_telegram_login_payload;
 use crate::services::twitch::TwitchService;
@@ -18,6 +19,9 @@ pub struct OAuthState {
     pub csrf_token: String,
     pub redirect_to: Option<String>,
     pub lang: Option<String>,
+    /// Share invite token to accept once the user is logged in
+    #[serde(default)]
+    pub invite: Option<String>,
     pub iat: usize,
     pub exp: usize,
 }
@@ -34,10 +38,19 @@ pub struct DiscordOAuthState {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
+    /// Session the token belongs to (`sessions.id`)
+    pub sid: String,
     pub exp: usize,
     pub iat: usize,
 }
 
+/// Client details stored with a new session
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
 pub struct AuthService;
 
 impl AuthService {
@@ -46,6 +59,7 @@ impl AuthService {
         state: &Arc<AppState>,
         redirect_to: Option<String>,
         lang: Option<String>,
+        invite: Option<String>,
     ) -> AppResult<String> {
         let csrf_token = Self::generate_random_string(32);
         let now = Utc::now();
@@ -56,6 +70,7 @@ impl AuthService {
             csrf_token,
             redirect_to,
             lang,
+            invite,
             iat,
             exp,
         };
@@ -128,14 +143,18 @@ impl AuthService {
         Ok(token_data.claims)
     }
 
-    /// Create a signed JWT for a user id
-    pub fn create_jwt(state: &Arc<AppState>, user_id: &str) -> AppResult<String> {
-        let now = Utc::now();
-        let exp = now + Duration::hours(state.config.jwt.expiration_hours);
+    /// Create a signed JWT for a user id, bound to session `session_id`
+    pub fn create_jwt(
+        state: &Arc<AppState>,
+        user_id: &str,
+        session_id: &str,
+        expires_at: chrono::DateTime<Utc>,
+    ) -> AppResult<String> {
         let claims = Claims {
             sub: user_id.to_string(),
-            iat: now.timestamp() as usize,
-            exp: exp.timestamp() as usize,
+            sid: session_id.to_string(),
+            iat: Utc::now().timestamp() as usize,
+            exp: expires_at.timestamp() as usize,
         };
 
         let token = encode(
@@ -156,16 +175,62 @@ impl AuthService {
         Ok(token_data.claims)
     }
 
-    /// Get user from JWT token
-    pub async fn get_user_from_token(
+    /// Hash of a token as stored in `sessions.token_hash`
+    pub fn hash_token(token: &str) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Start a session for a user and issue its JWT.
+    /// Returns the token and its expiry.
+    pub async fn create_session(
+        state: &Arc<AppState>,
+        user_id: &str,
+        client: &ClientInfo,
+    ) -> AppResult<(String, chrono::DateTime<Utc>)> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let expires_at = Utc::now() + Duration::hours(state.config.jwt.expiration_hours);
+        let token = Self::create_jwt(state, user_id, &session_id, expires_at)?;
+
+        SessionRepository::create(
+            &state.db,
+            &session_id,
+            user_id,
+            &Self::hash_token(&token),
+            expires_at.naive_utc(),
+            client.user_agent.as_deref(),
+            client.ip_address.as_deref(),
+        )
+        .await?;
+
+        Ok((token, expires_at))
+    }
+
+    /// Resolve a JWT to its user and session. Fails if the session was revoked or expired.
+    pub async fn authenticate(
         state: &Arc<AppState>,
         token: &str,
-    ) -> AppResult<crate::db::User> {
+    ) -> AppResult<(crate::db::User, Session)> {
         let claims = Self::decode_jwt(state, token)?;
+
+        let session =
+            SessionRepository::find_active_by_token_hash(&state.db, &Self::hash_token(token))
+                .await?
+                .filter(|s| s.id == claims.sid && s.user_id == claims.sub)
+                .ok_or(AppError::Unauthorized)?;
+
+        if let Err(e) = SessionRepository::touch(&state.db, &session.id).await {
+            tracing::warn!(
+                "Failed to update last use of session {}: {:?}",
+                session.id,
+                e
+            );
+        }
+
         let user = UserRepository::find_by_id(&state.db, &claims.sub)
             .await?
             .ok_or(AppError::Unauthorized)?;
-        Ok(user)
+        Ok((user, session))
     }
 
     /// Handle Twitch OAuth callback
@@ -173,6 +238,7 @@ impl AuthService {
         state: &Arc<AppState>,
         code: String,
         oauth_state: OAuthState,
+        client: ClientInfo,
     ) -> AppResult<(String, String)> {
         // Exchange code for tokens
         let token_response = state.twitch.exchange_code(&code).await?;
@@ -184,7 +250,7 @@ impl AuthService {
         let token_expires_at = TwitchService::calculate_token_expiry(token_response.expires_in);
 
         // Create or update user
-        let user = UserRepository::upsert_by_twitch_id(
+        let mut user = UserRepository::upsert_by_twitch_id(
             &state.db,
             &twitch_user.id,
             &twitch_user.login,
@@ -198,6 +264,18 @@ impl AuthService {
         )
         .await?;
 
+        // Keep the role in line with the configured administrators
+        let listed = state.config.admin.twitch_ids.contains(&user.twitch_id);
+        if user.is_admin != listed {
+            UserRepository::set_admin(&state.db, &user.id, listed).await?;
+            user.is_admin = listed;
+            tracing::info!(
+                "Set the administrator role of user {} to {}",
+                user.id,
+                listed
+            );
+        }
+
         // Spawn background task to synchronize EventSub subscriptions
         {
             let state = state.clone();
@@ -214,15 +292,34 @@ impl AuthService {
             });
         }
 
-        // Create JWT token
-        let token = Self::create_jwt(state, &user.id)?;
+        // Accept a share invite the login was started with. A bad invite doesn't fail the
+        // login; the frontend is told the outcome instead.
+        let invite_status = match oauth_state.invite.as_deref() {
+            Some(invite) => Some(
+                match ShareInviteService::accept_invite(state, invite, &user).await {
+                    Ok(Some(_)) => "accepted",
+                    Ok(None) => "invalid",
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to accept share invite for user {}: {:?}",
+                            user.id,
+                            e
+                        );
+                        "failed"
+                    }
+                },
+            ),
+            None => None,
+        };
+
+        // Start a session and issue its JWT
+        let (token, expires_at) = Self::create_session(state, &user.id, &client).await?;
 
         // Build redirect URL with token
         let frontend_base = state.config.server.frontend_url.trim_end_matches('/');
         let callback_url = format!("{}/auth/callback", frontend_base);
         let token_enc: String = url::form_urlencoded::byte_serialize(token.as_bytes()).collect();
-        let expires_at =
-            (Utc::now() + Duration::hours(state.config.jwt.expiration_hours)).timestamp();
+        let expires_at = expires_at.timestamp();
 
         let raw_redirect = oauth_state.redirect_to.as_deref().unwrap_or("/dashboard");
         let safe_redirect = if Self::is_safe_redirect(raw_redirect, frontend_base) {
@@ -232,13 +329,16 @@ impl AuthService {
             "/dashboard".to_string()
         };
 
-        let redirect_with_fragment = format!(
+        let mut redirect_with_fragment = format!(
             "{}#access_token={}&token_type=Bearer&expires_at={}&redirect_to={}",
             callback_url,
             token_enc,
             expires_at,
             urlencoding::encode(&safe_redirect)
         );
+        if let Some(status) = invite_status {
+            redirect_with_fragment.push_str(&format!("&invite={}", status));
+        }
 
         Ok((redirect_with_fragment, user.id))
     }
@@ -554,25 +654,30 @@ impl AuthService {
 
         // Remove cached photos
         if let Some(telegram_id) = user.telegram_user_id {
-            let dir = std::path::Path::new("data/telegram_photos");
-            let exts = ["jpg", "jpeg", "png", "webp"];
-            for ext in &exts {
-                let p = dir.join(format!("{}.{}", telegram_id, ext));
-                if tokio::fs::metadata(&p).await.is_ok() {
-                    if let Err(e) = tokio::fs::remove_file(&p).await {
-                        tracing::warn!(
-                            "Failed to remove cached Telegram photo {}: {:?}",
-                            p.display(),
-                            e
-                        );
-                    }
-                }
-            }
+            Self::remove_telegram_photos(&telegram_id).await;
         }
 
         Ok(())
     }
 
+    /// Remove the cached profile photos of a Telegram user
+    pub async fn remove_telegram_photos(telegram_id: &str) {
+        let dir = std::path::Path::new("data/telegram_photos");
+        let exts = ["jpg", "jpeg", "png", "webp"];
+        for ext in &exts {
+            let p = dir.join(format!("{}.{}", telegram_id, ext));
+            if tokio::fs::metadata(&p).await.is_ok() {
+                if let Err(e) = tokio::fs::remove_file(&p).await {
+                    tracing::warn!(
+                        "Failed to remove cached Telegram photo {}: {:?}",
+                        p.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
     /// Unlink Discord from user
     pub async fn unlink_discord(state: &Arc<AppState>, user_id: String) -> AppResult<()> {
         let user = UserRepository::find_by_id(&state.db, &user_id)
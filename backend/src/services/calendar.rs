@@ -7,7 +7,7 @@ use tracing::{info, warn};
 use crate::db::models::{CreateSyncedCalendarEvent, DiscordIntegration};
 use crate::db::{DiscordIntegrationRepository, SyncedCalendarRepository, UserRepository};
 use crate::error::AppResult;
-use crate::services::discord::ScheduledEvent;
+use crate::services::discord::{DiscordService, ScheduledEvent};
 use crate::services::twitch::ScheduleSegment;
 use crate::AppState;
 
@@ -52,6 +52,28 @@ impl CalendarSyncManager {
         Ok(())
     }
 
+    /// Delete the Discord scheduled events created for a user's Twitch schedule.
+    /// The synced rows themselves are left to the caller (they cascade with the user).
+    pub async fn delete_events_for_user(state: &Arc<AppState>, user_id: &str) -> AppResult<()> {
+        let integrations =
+            DiscordIntegrationRepository::find_by_user_id(&state.db, user_id).await?;
+        let events = SyncedCalendarRepository::find_by_user_id(&state.db, user_id).await?;
+
+        for event in events {
+            let (Some(integration_id), Some(event_id)) =
+                (&event.discord_integration_id, &event.discord_event_id)
+            else {
+                continue;
+            };
+
+            if let Some(integration) = integrations.iter().find(|i| &i.id == integration_id) {
+                Self::delete_discord_event(state, &integration.discord_guild_id, event_id).await;
+            }
+        }
+
+        Ok(())
+    }
+
     // Helper to delete a Discord scheduled event and log failures
     async fn delete_discord_event(state: &Arc<AppState>, guild_id: &str, event_id: &str) {
         let discord_guard = state.discord.read().await;
@@ -261,10 +283,7 @@ impl CalendarSyncManager {
                 Ok(_) => Some(existing_id),
                 Err(e) => {
                     // If event was deleted (404), create a new one
-                    let error_msg = format!("{:?}", e);
-                    if error_msg.contains("404")
-                        || error_msg.contains("Unknown Guild Scheduled Event")
-                    {
+                    if DiscordService::is_unknown_scheduled_event(&e) {
                         warn!(
                             "Discord event {} was deleted for recurring group {}, creating new event",
                             existing_id, title
@@ -453,10 +472,7 @@ impl CalendarSyncManager {
                 }
                 Err(e) => {
                     // If event was deleted (404), create a new one
-                    let error_msg = format!("{:?}", e);
-                    if error_msg.contains("404")
-                        || error_msg.contains("Unknown Guild Scheduled Event")
-                    {
+                    if DiscordService::is_unknown_scheduled_event(&e) {
                         warn!(
                             "Discord event {} was deleted, creating new event for segment {}",
                             existing_discord_id, segment.id
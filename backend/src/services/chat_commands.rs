@@ -0,0 +1,92 @@
+//! Replies shared by the chat bot commands (Telegram `/status`, Discord `/stream status`, ...).
+
+use std::sync::Arc;
+
+use crate::db::User;
+use crate::error::AppResult;
+use crate::i18n;
+use crate::AppState;
+
+/// Maximum number of schedule segments listed per channel
+const SCHEDULE_SEGMENTS_LIMIT: usize = 5;
+
+/// One line describing whether `owner` is live right now
+pub async fn status_line(
+    state: &Arc<AppState>,
+    app_token: &str,
+    owner: &User,
+    lang: Option<&str>,
+) -> AppResult<String> {
+    let streamer = owner.twitch_display_name.as_str();
+
+    let line = match state.twitch.get_stream(app_token, &owner.twitch_id).await? {
+        Some(stream) => {
+            let url = format!("https://twitch.tv/{}", owner.twitch_login);
+            i18n::tr(
+                lang,
+                "chat_commands.status_live",
+                Some(&[
+                    ("streamer", streamer),
+                    ("title", &stream.title),
+                    ("game", &stream.game_name),
+                    ("url", &url),
+                ]),
+            )
+        }
+        None => i18n::tr(
+            lang,
+            "chat_commands.status_offline",
+            Some(&[("streamer", streamer)]),
+        ),
+    };
+
+    Ok(line)
+}
+
+/// Upcoming (non-canceled) schedule segments of `owner`
+pub async fn schedule_block(
+    state: &Arc<AppState>,
+    app_token: &str,
+    owner: &User,
+    lang: Option<&str>,
+) -> AppResult<String> {
+    let streamer = owner.twitch_display_name.as_str();
+
+    let segments: Vec<String> = state
+        .twitch
+        .get_schedule(app_token, &owner.twitch_id)
+        .await?
+        .map(|data| data.segments)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.canceled_until.is_none())
+        .take(SCHEDULE_SEGMENTS_LIMIT)
+        .map(|s| {
+            let start = chrono::DateTime::parse_from_rfc3339(&s.start_time)
+                .map(|dt| dt.format("%d.%m %H:%M UTC").to_string())
+                .unwrap_or(s.start_time);
+            match s.category {
+                Some(category) => format!("• {} — {} ({})", start, s.title, category.name),
+                None => format!("• {} — {}", start, s.title),
+            }
+        })
+        .collect();
+
+    if segments.is_empty() {
+        return Ok(i18n::tr(
+            lang,
+            "chat_commands.schedule_empty",
+            Some(&[("streamer", streamer)]),
+        ));
+    }
+
+    Ok(format!(
+        "{}\n{}",
+        i18n::tr(
+            lang,
+            "chat_commands.schedule_header",
+            Some(&[("streamer", streamer)])
+        ),
+        segments.join("\n")
+    ))
+}
@@ -1,14 +1,32 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, DiscordError};
+use crate::services::discord_rate_limit::{DiscordRateLimiter, RouteKey};
 
 #[derive(Clone)]
 pub struct DiscordService {
     client: reqwest::Client,
     bot_token: String,
+    /// Shared by all clones so every sender respects the same limits
+    rate_limiter: Arc<DiscordRateLimiter>,
 }
 
+/// Channel type of announcement (news) channels
+pub const CHANNEL_TYPE_ANNOUNCEMENT: u8 = 5;
+
+/// JSON error code of "Unknown Webhook"
+pub const DISCORD_CODE_UNKNOWN_WEBHOOK: u64 = 10015;
+pub const DISCORD_CODE_UNKNOWN_SCHEDULED_EVENT: u64 = 10070;
+
+pub const PERMISSION_ADMINISTRATOR: u64 = 1 << 3;
+pub const PERMISSION_VIEW_CHANNEL: u64 = 1 << 10;
+pub const PERMISSION_SEND_MESSAGES: u64 = 1 << 11;
+pub const PERMISSION_EMBED_LINKS: u64 = 1 << 14;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscordEmbed {
     pub title: Option<String>,
@@ -60,6 +78,8 @@ pub struct WebhookMessage {
     pub username: Option<String>,
     pub avatar_url: Option<String>,
     pub embeds: Option<Vec<DiscordEmbed>>,
+    /// Defaults to [`AllowedMentions::none`] when sent
+    pub allowed_mentions: Option<AllowedMentions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +87,74 @@ pub struct DiscordMessage {
     pub content: Option<String>,
     pub embeds: Option<Vec<DiscordEmbed>>,
     pub tts: Option<bool>,
+    /// Defaults to [`AllowedMentions::none`] when sent
+    pub allowed_mentions: Option<AllowedMentions>,
+}
+
+/// Channel webhook
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordWebhook {
+    pub id: String,
+    /// Only present for incoming webhooks created by the bot or listed with manage rights
+    pub token: Option<String>,
+}
+
+impl DiscordWebhook {
+    /// Execute URL of the webhook
+    pub fn url(&self) -> Option<String> {
+        self.token
+            .as_ref()
+            .map(|token| format!("https://discord.com/api/webhooks/{}/{}", self.id, token))
+    }
+}
+
+/// Message returned by a send (bot or webhook with `wait=true`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordSentMessage {
+    pub id: String,
+    pub channel_id: String,
+}
+
+/// Which mentions in a message are allowed to ping
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AllowedMentions {
+    pub parse: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+impl AllowedMentions {
+    /// Nothing pings, whatever the content contains
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Prepend the configured `mention` ("here" or a role id) to `content` and
+    /// allow only that mention to ping. `@everyone`/`@here` typed in the content
+    /// itself are neutralized so a template can never ping on its own.
+    pub fn apply(content: &str, mention: Option<&str>) -> (String, Self) {
+        let content = content
+            .replace("@everyone", "@\u{200B}everyone")
+            .replace("@here", "@\u{200B}here");
+
+        match mention {
+            Some(crate::db::DISCORD_MENTION_HERE) => (
+                format!("@here {}", content),
+                Self {
+                    parse: vec!["everyone".to_string()],
+                    roles: Vec::new(),
+                },
+            ),
+            Some(role_id) => (
+                format!("<@&{}> {}", role_id, content),
+                Self {
+                    parse: Vec::new(),
+                    roles: vec![role_id.to_string()],
+                },
+            ),
+            None => (content, Self::none()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,11 +171,28 @@ pub struct DiscordChannel {
     #[serde(rename = "type")]
     pub channel_type: u8,
     pub guild_id: Option<String>,
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionOverwrite {
+    /// Role or member id
+    pub id: String,
+    /// 0 = role, 1 = member
+    #[serde(rename = "type")]
+    pub overwrite_type: u8,
+    #[serde(deserialize_with = "deserialize_permissions")]
+    pub allow: u64,
+    #[serde(deserialize_with = "deserialize_permissions")]
+    pub deny: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscordRole {
     pub id: String,
+    #[serde(default)]
+    pub name: String,
     #[serde(deserialize_with = "deserialize_permissions")]
     pub permissions: u64,
 }
@@ -123,14 +228,24 @@ pub struct EntityMetadata {
     pub location: Option<String>,
 }
 
+/// Longest rate limit wait a request will sit out before failing with 429
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+
+/// How often a request is retried after Discord answered 429
+const MAX_RATE_LIMIT_RETRIES: u32 = 2;
+
 impl DiscordService {
     pub async fn new(bot_token: String) -> AppResult<Self> {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
-            .map_err(|e| AppError::Discord(format!("Failed to create HTTP client: {}", e)))?;
+            .map_err(|e| AppError::discord(format!("Failed to create HTTP client: {}", e)))?;
 
-        Ok(Self { client, bot_token })
+        Ok(Self {
+            client,
+            bot_token,
+            rate_limiter: Arc::new(DiscordRateLimiter::new()),
+        })
     }
 
     fn api_url(&self, endpoint: &str) -> String {
@@ -141,58 +256,101 @@ impl DiscordService {
         format!("Bot {}", self.bot_token)
     }
 
-    /// Parse retry_after from a Discord rate limit error response
-    fn parse_retry_after(error_text: &str) -> Option<f64> {
-        if let Ok(json) = serde_json::from_str::<Value>(error_text) {
-            if let Some(retry_after) = json.get("retry_after") {
-                return retry_after.as_f64();
+    /// Send a request through the shared rate limiter.
+    ///
+    /// Waits for the route's bucket and the global limit before sending, records the
+    /// limits reported in the response headers and retries after a 429 when the wait
+    /// is short. Non-success responses become `AppError::Discord` with their status.
+    async fn execute(&self, request: reqwest::RequestBuilder) -> AppResult<reqwest::Response> {
+        let request = request.build()?;
+        let key = RouteKey::new(request.method(), request.url());
+
+        let mut attempt = 0;
+        loop {
+            if let Err(wait) = self.rate_limiter.acquire(&key, MAX_RATE_LIMIT_WAIT).await {
+                return Err(DiscordError::rate_limited(&key, wait).into());
             }
-        }
-        None
-    }
 
-    /// Handle rate limiting by waiting for the retry_after duration
-    async fn handle_rate_limit(error_text: &str) -> AppResult<()> {
-        if let Some(retry_after) = Self::parse_retry_after(error_text) {
-            let wait_seconds = (retry_after.ceil() as u64) + 1; // Add 1 second buffer
-            tracing::warn!(
-                "Discord rate limit hit, waiting {} seconds before retry",
-                wait_seconds
+            let retry = request
+                .try_clone()
+                .ok_or_else(|| AppError::discord("Discord request cannot be cloned"))?;
+            let response = self.client.execute(retry).await?;
+            self.rate_limiter.update(&key, response.headers());
+
+            let status = response.status();
+            crate::metrics::record_api_request(
+                "discord",
+                status == reqwest::StatusCode::TOO_MANY_REQUESTS,
             );
-            tokio::time::sleep(tokio::time::Duration::from_secs(wait_seconds)).await;
-            Ok(())
-        } else {
-            Err(AppError::Discord(format!(
-                "Rate limited but could not parse retry_after: {}",
-                error_text
-            )))
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let global = response.headers().contains_key("x-ratelimit-global");
+            let body = response.text().await.unwrap_or_default();
+
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let limit = serde_json::from_str::<RateLimitResponse>(&body).unwrap_or_default();
+                let retry_after = Duration::from_secs_f64(limit.retry_after.max(0.0));
+
+                if global || limit.global {
+                    self.rate_limiter.block_global(retry_after);
+                } else {
+                    self.rate_limiter.block_route(&key, retry_after);
+                }
+                tracing::warn!(
+                    "Discord rate limit hit on {} ({}), retry after {:?}{}",
+                    key.route,
+                    key.major,
+                    retry_after,
+                    if global || limit.global {
+                        " (global)"
+                    } else {
+                        ""
+                    }
+                );
+
+                if attempt < MAX_RATE_LIMIT_RETRIES && retry_after <= MAX_RATE_LIMIT_WAIT {
+                    attempt += 1;
+                    continue;
+                }
+            }
+
+            return Err(DiscordError::api(status.as_u16(), &body).into());
         }
     }
 
+    /// Send a request and parse the JSON response
+    async fn execute_json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        what: &str,
+    ) -> AppResult<T> {
+        self.execute(request)
+            .await?
+            .json()
+            .await
+            .map_err(|e| AppError::discord(format!("Failed to parse {} response: {}", what, e)))
+    }
+
     /// Send a message to a channel
-    pub async fn send_message(&self, channel_id: &str, message: DiscordMessage) -> AppResult<()> {
+    pub async fn send_message(
+        &self,
+        channel_id: &str,
+        mut message: DiscordMessage,
+    ) -> AppResult<DiscordSentMessage> {
+        message
+            .allowed_mentions
+            .get_or_insert_with(AllowedMentions::none);
         let url = self.api_url(&format!("/channels/{}/messages", channel_id));
 
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .json(&message)
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to send message: {}", e)))?;
+            .json(&message);
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
-
-        Ok(())
+        self.execute_json(request, "message").await
     }
 
     /// Send a message via webhook
@@ -200,132 +358,357 @@ impl DiscordService {
         &self,
         webhook_url: &str,
         message: WebhookMessage,
+    ) -> AppResult<DiscordSentMessage> {
+        self.send_webhook_message_in_thread(webhook_url, None, message)
+            .await
+    }
+
+    /// Send a message via webhook, optionally into a thread of the webhook's channel
+    pub async fn send_webhook_message_in_thread(
+        &self,
+        webhook_url: &str,
+        thread_id: Option<&str>,
+        mut message: WebhookMessage,
+    ) -> AppResult<DiscordSentMessage> {
+        message
+            .allowed_mentions
+            .get_or_insert_with(AllowedMentions::none);
+
+        let mut query = vec![("wait", "true")];
+        if let Some(thread_id) = thread_id {
+            query.push(("thread_id", thread_id));
+        }
+
+        // wait=true makes Discord return the created message
+        let request = self.client.post(webhook_url).query(&query).json(&message);
+
+        self.execute_json(request, "webhook").await
+    }
+
+    /// Publish (crosspost) a message of an announcement channel to following servers.
+    ///
+    /// Publishing has a tight per-channel rate limit (reset windows of up to an hour).
+    /// Once it is exhausted the limiter fails fast instead of waiting, since a late
+    /// crosspost is worth less than a prompt notification.
+    pub async fn crosspost_message(&self, channel_id: &str, message_id: &str) -> AppResult<()> {
+        let url = self.api_url(&format!(
+            "/channels/{}/messages/{}/crosspost",
+            channel_id, message_id
+        ));
+
+        let request = self
+            .client
+            .post(&url)
+            .header("Authorization", self.auth_header());
+
+        self.execute(request).await?;
+        Ok(())
+    }
+
+    /// Crosspost `message_id` if `channel_id` is an announcement channel
+    pub async fn publish_if_announcement(
+        &self,
+        channel_id: &str,
+        message_id: &str,
     ) -> AppResult<()> {
+        let channel = self.get_channel(channel_id).await?;
+        if channel.channel_type != CHANNEL_TYPE_ANNOUNCEMENT {
+            return Ok(());
+        }
+
+        self.crosspost_message(channel_id, message_id).await
+    }
+
+    /// Create a channel webhook named `name` with the image at `avatar_url` as avatar.
+    /// Requires the bot to have Manage Webhooks in the channel.
+    pub async fn create_webhook(
+        &self,
+        channel_id: &str,
+        name: &str,
+        avatar_url: Option<&str>,
+    ) -> AppResult<DiscordWebhook> {
+        let url = self.api_url(&format!("/channels/{}/webhooks", channel_id));
+
+        // Webhook names are limited to 80 characters and may not contain "discord"
+        let mut name: String = name.chars().take(80).collect();
+        if name.trim().is_empty() || name.to_lowercase().contains("discord") {
+            name = crate::i18n::t("app.name");
+        }
+
+        // The avatar is optional: a failed download only costs the picture
+        let avatar = match avatar_url {
+            Some(avatar_url) => self
+                .fetch_image_data_uri(avatar_url)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to fetch webhook avatar {}: {}", avatar_url, e);
+                    None
+                }),
+            None => None,
+        };
+
+        let request = self
+            .client
+            .post(&url)
+            .header("Authorization", self.auth_header())
+            .json(&serde_json::json!({ "name": name, "avatar": avatar }));
+
+        self.execute_json(request, "webhook").await
+    }
+
+    /// Delete a webhook through its execute URL. An already deleted webhook is not an error.
+    pub async fn delete_webhook(&self, webhook_url: &str) -> AppResult<()> {
+        match self.execute(self.client.delete(webhook_url)).await {
+            Ok(_) => Ok(()),
+            Err(e) if Self::is_unknown_webhook(&e) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether a send failed because the webhook no longer exists (404 Unknown Webhook)
+    pub fn is_unknown_webhook(error: &AppError) -> bool {
+        match error {
+            AppError::Discord(e) => {
+                e.code == Some(DISCORD_CODE_UNKNOWN_WEBHOOK)
+                    || (e.code.is_none() && e.status == Some(404))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a scheduled event request failed because the event no longer exists
+    /// (deleted in Discord, 404 Unknown Guild Scheduled Event)
+    pub fn is_unknown_scheduled_event(error: &AppError) -> bool {
+        match error {
+            AppError::Discord(e) => {
+                e.code == Some(DISCORD_CODE_UNKNOWN_SCHEDULED_EVENT)
+                    || (e.code.is_none() && e.status == Some(404))
+            }
+            _ => false,
+        }
+    }
+
+    /// Download an image as a `data:` URI as expected by Discord image fields
+    async fn fetch_image_data_uri(&self, url: &str) -> AppResult<Option<String>> {
+        use base64::Engine;
+
         let response = self
             .client
-            .post(webhook_url)
-            .header("Content-Type", "application/json")
-            .json(&message)
+            .get(url)
             .send()
             .await
-            .map_err(|e| AppError::Discord(format!("Failed to send webhook message: {}", e)))?;
+            .map_err(|e| AppError::discord(format!("Failed to fetch image: {}", e)))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord webhook error ({}): {}",
-                status, error_text
-            )));
+            return Ok(None);
         }
 
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("image/png")
+            .to_string();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::discord(format!("Failed to read image: {}", e)))?;
+
+        Ok(Some(format!(
+            "data:{};base64,{}",
+            content_type,
+            base64::engine::general_purpose::STANDARD.encode(&bytes)
+        )))
+    }
+
+    /// Start a public thread from a message; returns the thread (channel) id
+    pub async fn start_thread_from_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        name: &str,
+    ) -> AppResult<String> {
+        let url = self.api_url(&format!(
+            "/channels/{}/messages/{}/threads",
+            channel_id, message_id
+        ));
+
+        // Thread names are limited to 100 characters
+        let name: String = name.chars().take(100).collect();
+
+        let request = self
+            .client
+            .post(&url)
+            .header("Authorization", self.auth_header())
+            .json(&serde_json::json!({
+                "name": name,
+                "auto_archive_duration": 1440
+            }));
+
+        let thread: DiscordChannel = self.execute_json(request, "thread").await?;
+        Ok(thread.id)
+    }
+
+    /// Archive a thread
+    pub async fn archive_thread(&self, thread_id: &str) -> AppResult<()> {
+        let url = self.api_url(&format!("/channels/{}", thread_id));
+
+        let request = self
+            .client
+            .patch(&url)
+            .header("Authorization", self.auth_header())
+            .json(&serde_json::json!({ "archived": true }));
+
+        self.execute(request).await?;
         Ok(())
     }
 
+    /// Send a direct message to a user (opens the DM channel first).
+    /// Fails when the user shares no server with the bot or has DMs disabled.
+    pub async fn send_direct_message(
+        &self,
+        user_id: &str,
+        content: &str,
+    ) -> AppResult<DiscordSentMessage> {
+        let url = self.api_url("/users/@me/channels");
+
+        let request = self
+            .client
+            .post(&url)
+            .header("Authorization", self.auth_header())
+            .json(&serde_json::json!({ "recipient_id": user_id }));
+        let channel: DiscordChannel = self.execute_json(request, "DM channel").await?;
+
+        let message = DiscordMessage {
+            content: Some(content.to_string()),
+            embeds: None,
+            tts: None,
+            allowed_mentions: None,
+        };
+        self.send_message(&channel.id, message).await
+    }
+
+    /// Send a plain-text notification to an integration's destination and return the
+    /// created message. Applies the configured mention, posts into `ctx.discord_thread_id`
+    /// when set and publishes the message when auto-publish is enabled.
+    pub async fn send_text_notification(
+        &self,
+        ctx: &crate::services::notifications::IntegrationContext,
+        message: &str,
+    ) -> AppResult<DiscordSentMessage> {
+        let (message, allowed_mentions) =
+            AllowedMentions::apply(message, ctx.discord_mention.as_deref());
+        let thread_id = ctx.discord_thread_id.as_deref();
+
+        let sent = if let Some(webhook_url) = &ctx.webhook_url {
+            let msg = WebhookMessage {
+                content: Some(message),
+                username: None,
+                avatar_url: None,
+                embeds: None,
+                allowed_mentions: Some(allowed_mentions),
+            };
+            self.send_webhook_message_in_thread(webhook_url, thread_id, msg)
+                .await?
+        } else {
+            let msg = DiscordMessage {
+                content: Some(message),
+                embeds: None,
+                tts: None,
+                allowed_mentions: Some(allowed_mentions),
+            };
+            // Threads are channels: post there directly
+            self.send_message(thread_id.unwrap_or(&ctx.destination_id), msg)
+                .await?
+        };
+
+        // Publishing is best-effort: the notification itself was delivered.
+        // Thread messages cannot be published.
+        if ctx.discord_auto_publish && thread_id.is_none() {
+            let published = match ctx.discord_channel_type {
+                Some(CHANNEL_TYPE_ANNOUNCEMENT) => {
+                    self.crosspost_message(&sent.channel_id, &sent.id).await
+                }
+                Some(_) => Ok(()),
+                // Integrations created before the channel type was stored
+                None => {
+                    self.publish_if_announcement(&sent.channel_id, &sent.id)
+                        .await
+                }
+            };
+            if let Err(e) = published {
+                tracing::warn!(
+                    "Failed to publish Discord message {} in channel {}: {}",
+                    sent.id,
+                    sent.channel_id,
+                    e
+                );
+            }
+        }
+
+        Ok(sent)
+    }
+
     /// Get guilds the bot is a member of
     pub async fn get_guilds(&self) -> AppResult<Vec<DiscordGuild>> {
         let url = self.api_url("/users/@me/guilds");
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get guilds: {}", e)))?;
+            .header("Authorization", self.auth_header());
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse guilds response: {}", e)))
+        self.execute_json(request, "guilds").await
     }
 
     /// Get channels in a guild
     pub async fn get_guild_channels(&self, guild_id: &str) -> AppResult<Vec<DiscordChannel>> {
         let url = self.api_url(&format!("/guilds/{}/channels", guild_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get channels: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
+            .header("Authorization", self.auth_header());
 
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse channels response: {}", e)))
+        self.execute_json(request, "channels").await
     }
 
     /// Get a single Discord channel by ID
     pub async fn get_channel(&self, channel_id: &str) -> AppResult<DiscordChannel> {
         let url = self.api_url(&format!("/channels/{}", channel_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get channel: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
+            .header("Authorization", self.auth_header());
 
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse channel response: {}", e)))
+        self.execute_json(request, "channel").await
     }
 
-    /// Check whether a specific user is a member of a guild
-    pub async fn is_user_in_guild(&self, guild_id: &str, user_id: &str) -> AppResult<bool> {
-        let url = self.api_url(&format!("/guilds/{}/members/{}", guild_id, user_id));
+    /// Check that a webhook still resolves (GET on its execute URL needs no bot token)
+    pub async fn get_webhook(&self, webhook_url: &str) -> AppResult<()> {
+        self.execute(self.client.get(webhook_url)).await?;
+        Ok(())
+    }
 
-        let response = self
+    /// The bot's own user
+    pub async fn get_current_user(&self) -> AppResult<DiscordUser> {
+        let request = self
             .client
-            .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to check guild membership: {}", e)))?;
+            .get(self.api_url("/users/@me"))
+            .header("Authorization", self.auth_header());
+        self.execute_json(request, "current user").await
+    }
 
-        if response.status().is_success() {
-            Ok(true)
-        } else if response.status() == reqwest::StatusCode::NOT_FOUND {
-            Ok(false)
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::warn!("Discord membership check failed: body={}", error_text);
-            Err(AppError::Discord(format!(
-                "Discord API error: {}",
-                error_text
-            )))
+    /// Check whether a specific user is a member of a guild
+    pub async fn is_user_in_guild(&self, guild_id: &str, user_id: &str) -> AppResult<bool> {
+        match self.get_guild_member(guild_id, user_id).await {
+            Ok(_) => Ok(true),
+            Err(AppError::NotFound(_)) => Ok(false),
+            Err(e) => {
+                tracing::warn!("Discord membership check failed: {}", e);
+                Err(e)
+            }
         }
     }
 
@@ -337,28 +720,16 @@ impl DiscordService {
     pub async fn get_guild(&self, guild_id: &str) -> AppResult<DiscordGuildInfo> {
         let url = self.api_url(&format!("/guilds/{}", guild_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get guild info: {}", e)))?;
+            .header("Authorization", self.auth_header());
 
-        if response.status().is_success() {
-            response
-                .json()
-                .await
-                .map_err(|e| AppError::Discord(format!("Failed to parse guild response: {}", e)))
-        } else if response.status() == reqwest::StatusCode::NOT_FOUND {
-            Err(AppError::NotFound("Guild not found".to_string()))
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )))
+        match self.execute_json(request, "guild").await {
+            Err(AppError::Discord(e)) if e.status == Some(404) => {
+                Err(AppError::NotFound("Guild not found".to_string()))
+            }
+            result => result,
         }
     }
 
@@ -366,39 +737,20 @@ impl DiscordService {
     pub async fn get_guild_roles(&self, guild_id: &str) -> AppResult<Vec<DiscordRole>> {
         let url = self.api_url(&format!("/guilds/{}/roles", guild_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get guild roles: {}", e)))?;
+            .header("Authorization", self.auth_header());
 
-        if response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            match serde_json::from_str::<Vec<DiscordRole>>(&body) {
-                Ok(r) => Ok(r),
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to parse roles response for guild {}: {} -- body: {}",
-                        guild_id,
-                        e,
-                        body
-                    );
-                    Err(AppError::Discord(format!(
-                        "Failed to parse roles response: {}",
-                        e
-                    )))
-                }
-            }
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::warn!("Discord get_guild_roles failed: body={}", error_text);
-            Err(AppError::Discord(format!(
-                "Discord API error: {}",
-                error_text
-            )))
+        let result = self.execute_json(request, "roles").await;
+        if let Err(e) = &result {
+            tracing::warn!(
+                "Discord get_guild_roles failed for guild {}: {}",
+                guild_id,
+                e
+            );
         }
+        result
     }
 
     /// Fetch a guild member (includes member roles)
@@ -409,35 +761,16 @@ impl DiscordService {
     ) -> AppResult<DiscordMember> {
         let url = self.api_url(&format!("/guilds/{}/members/{}", guild_id, user_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get guild member: {}", e)))?;
+            .header("Authorization", self.auth_header());
 
-        if response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            match serde_json::from_str::<DiscordMember>(&body) {
-                Ok(m) => Ok(m),
-                Err(e) => {
-                    tracing::warn!("Failed to parse guild member response for guild {} user {}: {} -- body: {}", guild_id, user_id, e, body);
-                    Err(AppError::Discord(format!(
-                        "Failed to parse member response: {}",
-                        e
-                    )))
-                }
+        match self.execute_json(request, "member").await {
+            Err(AppError::Discord(e)) if e.status == Some(404) => {
+                Err(AppError::NotFound("Member not found".to_string()))
             }
-        } else if response.status() == reqwest::StatusCode::NOT_FOUND {
-            Err(AppError::NotFound("Member not found".to_string()))
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            tracing::warn!("Discord get_guild_member failed: body={}", error_text);
-            Err(AppError::Discord(format!(
-                "Discord API error: {}",
-                error_text
-            )))
+            result => result,
         }
     }
 
@@ -498,69 +831,63 @@ impl DiscordService {
         Ok((perms & PERM_ADMINISTRATOR) != 0 || (perms & PERM_MANAGE_GUILD) != 0)
     }
 
+    /// Overwrite the application's slash commands in a guild (idempotent)
+    pub async fn register_guild_commands(
+        &self,
+        application_id: &str,
+        guild_id: &str,
+        commands: &Value,
+    ) -> AppResult<()> {
+        let url = self.api_url(&format!(
+            "/applications/{}/guilds/{}/commands",
+            application_id, guild_id
+        ));
+
+        let request = self
+            .client
+            .put(&url)
+            .header("Authorization", self.auth_header())
+            .json(commands);
+
+        self.execute(request).await?;
+        Ok(())
+    }
+
+    /// Replace the content of a (deferred) interaction response
+    pub async fn edit_interaction_response(
+        &self,
+        application_id: &str,
+        interaction_token: &str,
+        content: &str,
+    ) -> AppResult<()> {
+        let url = self.api_url(&format!(
+            "/webhooks/{}/{}/messages/@original",
+            application_id, interaction_token
+        ));
+
+        let request = self.client.patch(&url).json(&serde_json::json!({
+            "content": content,
+            "allowed_mentions": { "parse": [] }
+        }));
+
+        self.execute(request).await?;
+        Ok(())
+    }
+
     /// Create a scheduled event in a guild
-    /// Retries once on rate limit (429) errors
     pub async fn create_scheduled_event(&self, event: ScheduledEvent) -> AppResult<ScheduledEvent> {
         let url = self.api_url(&format!("/guilds/{}/scheduled-events", event.guild_id));
 
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .json(&event)
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to create scheduled event: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Handle rate limiting (429) with retry
-            if status.as_u16() == 429 {
-                Self::handle_rate_limit(&error_text).await?;
-                // Retry once after waiting
-                let retry_response = self
-                    .client
-                    .post(&url)
-                    .header("Authorization", self.auth_header())
-                    .header("Content-Type", "application/json")
-                    .json(&event)
-                    .send()
-                    .await
-                    .map_err(|e| {
-                        AppError::Discord(format!("Failed to retry create scheduled event: {}", e))
-                    })?;
-
-                if !retry_response.status().is_success() {
-                    let retry_status = retry_response.status();
-                    let retry_error_text = retry_response.text().await.unwrap_or_default();
-                    return Err(AppError::Discord(format!(
-                        "Discord API error ({}): {}",
-                        retry_status, retry_error_text
-                    )));
-                }
-
-                return retry_response.json().await.map_err(|e| {
-                    AppError::Discord(format!("Failed to parse event response: {}", e))
-                });
-            }
-
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
+            .json(&event);
 
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse event response: {}", e)))
+        self.execute_json(request, "event").await
     }
 
     /// Update a scheduled event
-    /// Retries once on rate limit (429) errors
     pub async fn update_scheduled_event(
         &self,
         guild_id: &str,
@@ -572,114 +899,28 @@ impl DiscordService {
             guild_id, event_id
         ));
 
-        let response = self
+        let request = self
             .client
             .patch(&url)
             .header("Authorization", self.auth_header())
-            .header("Content-Type", "application/json")
-            .json(&event)
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to update scheduled event: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Handle rate limiting (429) with retry
-            if status.as_u16() == 429 {
-                Self::handle_rate_limit(&error_text).await?;
-                // Retry once after waiting
-                let retry_response = self
-                    .client
-                    .patch(&url)
-                    .header("Authorization", self.auth_header())
-                    .header("Content-Type", "application/json")
-                    .json(&event)
-                    .send()
-                    .await
-                    .map_err(|e| {
-                        AppError::Discord(format!("Failed to retry update scheduled event: {}", e))
-                    })?;
-
-                if !retry_response.status().is_success() {
-                    let retry_status = retry_response.status();
-                    let retry_error_text = retry_response.text().await.unwrap_or_default();
-                    return Err(AppError::Discord(format!(
-                        "Discord API error ({}): {}",
-                        retry_status, retry_error_text
-                    )));
-                }
-
-                return retry_response.json().await.map_err(|e| {
-                    AppError::Discord(format!("Failed to parse event response: {}", e))
-                });
-            }
+            .json(&event);
 
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse event response: {}", e)))
+        self.execute_json(request, "event").await
     }
 
     /// Delete a scheduled event
-    /// Retries once on rate limit (429) errors
     pub async fn delete_scheduled_event(&self, guild_id: &str, event_id: &str) -> AppResult<()> {
         let url = self.api_url(&format!(
             "/guilds/{}/scheduled-events/{}",
             guild_id, event_id
         ));
 
-        let response = self
+        let request = self
             .client
             .delete(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to delete scheduled event: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Handle rate limiting (429) with retry
-            if status.as_u16() == 429 {
-                Self::handle_rate_limit(&error_text).await?;
-                // Retry once after waiting
-                let retry_response = self
-                    .client
-                    .delete(&url)
-                    .header("Authorization", self.auth_header())
-                    .send()
-                    .await
-                    .map_err(|e| {
-                        AppError::Discord(format!("Failed to retry delete scheduled event: {}", e))
-                    })?;
-
-                if !retry_response.status().is_success() {
-                    let retry_status = retry_response.status();
-                    let retry_error_text = retry_response.text().await.unwrap_or_default();
-                    return Err(AppError::Discord(format!(
-                        "Discord API error ({}): {}",
-                        retry_status, retry_error_text
-                    )));
-                }
-
-                return Ok(());
-            }
-
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
+            .header("Authorization", self.auth_header());
 
+        self.execute(request).await?;
         Ok(())
     }
 
@@ -687,28 +928,65 @@ impl DiscordService {
     pub async fn get_scheduled_events(&self, guild_id: &str) -> AppResult<Vec<ScheduledEvent>> {
         let url = self.api_url(&format!("/guilds/{}/scheduled-events", guild_id));
 
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Authorization", self.auth_header())
-            .send()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to get scheduled events: {}", e)))?;
+            .header("Authorization", self.auth_header());
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Discord(format!(
-                "Discord API error ({}): {}",
-                status, error_text
-            )));
-        }
+        self.execute_json(request, "events").await
+    }
+}
 
-        response
-            .json()
-            .await
-            .map_err(|e| AppError::Discord(format!("Failed to parse events response: {}", e)))
+/// Body of a Discord 429 response
+#[derive(Debug, Default, Deserialize)]
+struct RateLimitResponse {
+    #[serde(default)]
+    retry_after: f64,
+    #[serde(default)]
+    global: bool,
+}
+
+/// Resolve a member's permissions in a channel: @everyone and member roles, then the
+/// channel's @everyone, role and member overwrites, in the order Discord applies them.
+pub fn compute_channel_permissions(
+    guild_id: &str,
+    member_id: &str,
+    member_roles: &[String],
+    roles: &[DiscordRole],
+    overwrites: &[PermissionOverwrite],
+) -> u64 {
+    // The @everyone role shares its id with the guild
+    let mut permissions = roles
+        .iter()
+        .filter(|r| r.id == guild_id || member_roles.contains(&r.id))
+        .fold(0, |acc, r| acc | r.permissions);
+
+    if permissions & PERMISSION_ADMINISTRATOR != 0 {
+        return u64::MAX;
+    }
+
+    if let Some(everyone) = overwrites.iter().find(|o| o.id == guild_id) {
+        permissions = (permissions & !everyone.deny) | everyone.allow;
     }
+
+    let (mut allow, mut deny) = (0, 0);
+    for overwrite in overwrites
+        .iter()
+        .filter(|o| o.overwrite_type == 0 && member_roles.contains(&o.id))
+    {
+        allow |= overwrite.allow;
+        deny |= overwrite.deny;
+    }
+    permissions = (permissions & !deny) | allow;
+
+    if let Some(member) = overwrites
+        .iter()
+        .find(|o| o.overwrite_type == 1 && o.id == member_id)
+    {
+        permissions = (permissions & !member.deny) | member.allow;
+    }
+
+    permissions
 }
 
 fn deserialize_permissions<'de, D>(deserializer: D) -> Result<u64, D::Error>
@@ -832,23 +1110,20 @@ pub async fn exchange_code_for_token(
         ])
         .send()
         .await
-        .map_err(|e| AppError::Discord(format!("Failed to exchange code for token: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to exchange code for token: {}", e)))?;
 
     let status = response.status();
     let body = response
         .text()
         .await
-        .map_err(|e| AppError::Discord(format!("Failed to read token response body: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to read token response body: {}", e)))?;
 
     if !status.is_success() {
-        return Err(AppError::Discord(format!(
-            "Discord token exchange failed ({}): {}",
-            status, body
-        )));
+        return Err(DiscordError::api(status.as_u16(), &body).into());
     }
 
     let token_resp = serde_json::from_str::<DiscordTokenResponse>(&body)
-        .map_err(|e| AppError::Discord(format!("Failed to parse token response: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to parse token response: {}", e)))?;
     Ok(token_resp)
 }
 
@@ -868,23 +1143,20 @@ pub async fn get_discord_user(access_token: &str) -> AppResult<DiscordUser> {
         .bearer_auth(access_token)
         .send()
         .await
-        .map_err(|e| AppError::Discord(format!("Failed to fetch Discord user: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to fetch Discord user: {}", e)))?;
 
     let status = response.status();
     let body = response
         .text()
         .await
-        .map_err(|e| AppError::Discord(format!("Failed to read user response body: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to read user response body: {}", e)))?;
 
     if !status.is_success() {
-        return Err(AppError::Discord(format!(
-            "Discord API error ({}): {}",
-            status, body
-        )));
+        return Err(DiscordError::api(status.as_u16(), &body).into());
     }
 
     let user = serde_json::from_str::<DiscordUser>(&body)
-        .map_err(|e| AppError::Discord(format!("Failed to parse user response: {}", e)))?;
+        .map_err(|e| AppError::discord(format!("Failed to parse user response: {}", e)))?;
     Ok(user)
 }
 
@@ -954,25 +1226,83 @@ impl crate::services::notifications::Notifier for DiscordService {
         message: String,
     ) -> crate::error::AppResult<Option<i32>> {
         // Send plain text message only (no embeds), same style as Telegram.
-        if let Some(webhook_url) = &ctx.webhook_url {
-            let msg = WebhookMessage {
-                content: Some(message),
-                username: None,
-                avatar_url: None,
-                embeds: None,
-            };
-            self.send_webhook_message(webhook_url, msg)
-                .await
-                .map(|_| None)
-        } else {
-            let msg = DiscordMessage {
-                content: Some(message),
-                embeds: None,
-                tts: None,
-            };
-            self.send_message(&ctx.destination_id, msg)
-                .await
-                .map(|_| None)
-        }
+        self.send_text_notification(ctx, &message).await?;
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_mentions_none_neutralizes_everyone() {
+        let (content, allowed) = AllowedMentions::apply("@everyone live!", None);
+        assert!(!content.contains("@everyone"));
+        assert!(allowed.parse.is_empty());
+        assert!(allowed.roles.is_empty());
+    }
+
+    #[test]
+    fn test_allowed_mentions_role() {
+        let (content, allowed) = AllowedMentions::apply("live! @here", Some("123"));
+        assert!(content.starts_with("<@&123> "));
+        assert!(!content.contains("@here"));
+        assert!(allowed.parse.is_empty());
+        assert_eq!(allowed.roles, vec!["123".to_string()]);
+    }
+
+    #[test]
+    fn test_allowed_mentions_here() {
+        let (content, allowed) = AllowedMentions::apply("@everyone live!", Some("here"));
+        assert!(content.starts_with("@here "));
+        assert!(!content.contains("@everyone"));
+        assert_eq!(allowed.parse, vec!["everyone".to_string()]);
+    }
+
+    #[test]
+    fn test_channel_permissions_apply_overwrites_in_order() {
+        let role = |id: &str, permissions: u64| DiscordRole {
+            id: id.to_string(),
+            name: String::new(),
+            permissions,
+        };
+        let overwrite = |id: &str, overwrite_type: u8, allow: u64, deny: u64| PermissionOverwrite {
+            id: id.to_string(),
+            overwrite_type,
+            allow,
+            deny,
+        };
+        let send = PERMISSION_VIEW_CHANNEL | PERMISSION_SEND_MESSAGES | PERMISSION_EMBED_LINKS;
+        let roles = vec![role("g", send), role("bot", 0)];
+        let member_roles = vec!["bot".to_string()];
+
+        // @everyone may send, the channel denies it for @everyone
+        let overwrites = vec![overwrite("g", 0, 0, PERMISSION_SEND_MESSAGES)];
+        let perms = compute_channel_permissions("g", "me", &member_roles, &roles, &overwrites);
+        assert_eq!(perms & PERMISSION_SEND_MESSAGES, 0);
+
+        // ...but the bot's role is allowed again
+        let overwrites = vec![
+            overwrite("g", 0, 0, PERMISSION_SEND_MESSAGES),
+            overwrite("bot", 0, PERMISSION_SEND_MESSAGES, 0),
+        ];
+        let perms = compute_channel_permissions("g", "me", &member_roles, &roles, &overwrites);
+        assert_ne!(perms & PERMISSION_SEND_MESSAGES, 0);
+
+        // ...and a member overwrite wins over roles
+        let overwrites = vec![
+            overwrite("bot", 0, PERMISSION_EMBED_LINKS, 0),
+            overwrite("me", 1, 0, PERMISSION_EMBED_LINKS),
+        ];
+        let perms = compute_channel_permissions("g", "me", &member_roles, &roles, &overwrites);
+        assert_eq!(perms & PERMISSION_EMBED_LINKS, 0);
+
+        // Administrators bypass overwrites
+        let roles = vec![role("g", 0), role("bot", PERMISSION_ADMINISTRATOR)];
+        let overwrites = vec![overwrite("g", 0, 0, send)];
+        let perms = compute_channel_permissions("g", "me", &member_roles, &roles, &overwrites);
+        assert_eq!(perms & send, send);
     }
 }
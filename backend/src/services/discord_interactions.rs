@@ -0,0 +1,460 @@
+//! Discord slash commands received through the interactions endpoint.
+//!
+//! Commands are registered per guild (`/stream status`, `/stream schedule`,
+//! `/notify test`, `/notify toggle <event>`). Every interaction is acknowledged
+//! with a deferred response and answered once the command has been processed.
+
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::db::{
+    DiscordIntegration, DiscordIntegrationRepository, UpdateDiscordIntegration, UserRepository,
+};
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_UPDATE, AUDIT_ACTOR_DISCORD_BOT, AUDIT_ENTITY_DISCORD_INTEGRATION,
+};
+use crate::services::chat_commands;
+use crate::services::integrations::IntegrationService;
+use crate::AppState;
+
+const INTERACTION_PING: u8 = 1;
+const INTERACTION_APPLICATION_COMMAND: u8 = 2;
+
+const RESPONSE_PONG: u8 = 1;
+const RESPONSE_DEFERRED_CHANNEL_MESSAGE: u8 = 5;
+
+/// Message flag: only the invoking user sees the response
+const FLAG_EPHEMERAL: u64 = 1 << 6;
+
+/// Events that can be toggled with `/notify toggle`
+const TOGGLE_EVENTS: &[&str] = &[
+    "stream_online",
+    "stream_offline",
+    "title_change",
+    "category_change",
+    "reward_redemption",
+];
+
+#[derive(Debug, Deserialize)]
+pub struct Interaction {
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub token: String,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub member: Option<InteractionMember>,
+    pub data: Option<InteractionData>,
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionMember {
+    pub user: InteractionUser,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionUser {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionData {
+    pub name: String,
+    #[serde(default)]
+    pub options: Vec<InteractionOption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InteractionOption {
+    pub name: String,
+    pub value: Option<Value>,
+    #[serde(default)]
+    pub options: Vec<InteractionOption>,
+}
+
+pub struct DiscordInteractionService;
+
+impl DiscordInteractionService {
+    /// Verify the Ed25519 signature Discord attaches to every interaction request
+    pub fn verify_signature(
+        state: &Arc<AppState>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> AppResult<()> {
+        let public_key = state.config.discord.public_key.as_deref().ok_or_else(|| {
+            AppError::ServiceUnavailable("Discord interactions are not configured".to_string())
+        })?;
+        let public_key = hex::decode(public_key)
+            .map_err(|_| AppError::Config("Invalid DISCORD_PUBLIC_KEY".to_string()))?;
+
+        let signature = headers
+            .get("X-Signature-Ed25519")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| hex::decode(v).ok())
+            .ok_or(AppError::Unauthorized)?;
+        let timestamp = headers
+            .get("X-Signature-Timestamp")
+            .and_then(|v| v.to_str().ok())
+            .ok_or(AppError::Unauthorized)?;
+
+        let mut message = Vec::with_capacity(timestamp.len() + body.len());
+        message.extend_from_slice(timestamp.as_bytes());
+        message.extend_from_slice(body);
+
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&message, &signature)
+            .map_err(|_| AppError::Unauthorized)
+    }
+
+    /// Produce the immediate response to an interaction.
+    /// Commands are deferred and processed in the background.
+    pub fn handle(state: &Arc<AppState>, interaction: Interaction) -> AppResult<Value> {
+        match interaction.kind {
+            INTERACTION_PING => Ok(json!({ "type": RESPONSE_PONG })),
+            INTERACTION_APPLICATION_COMMAND => {
+                let ephemeral = interaction
+                    .data
+                    .as_ref()
+                    .is_some_and(|data| data.name == "notify");
+
+                let state = state.clone();
+                tokio::spawn(async move {
+                    Self::process_command(&state, interaction).await;
+                });
+
+                let flags = if ephemeral { FLAG_EPHEMERAL } else { 0 };
+                Ok(json!({
+                    "type": RESPONSE_DEFERRED_CHANNEL_MESSAGE,
+                    "data": { "flags": flags }
+                }))
+            }
+            other => Err(AppError::BadRequest(format!(
+                "Unsupported interaction type: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Register the slash commands in every guild that has an integration
+    pub async fn register_commands_for_all_guilds(state: &Arc<AppState>) {
+        let guild_ids = match DiscordIntegrationRepository::list_guild_ids(&state.db).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to list Discord guilds for command registration: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        for guild_id in guild_ids {
+            if let Err(e) = Self::register_commands_for_guild(state, &guild_id).await {
+                tracing::warn!(
+                    "Failed to register Discord commands in guild {}: {}",
+                    guild_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Register (overwrite) the slash commands in a single guild
+    pub async fn register_commands_for_guild(
+        state: &Arc<AppState>,
+        guild_id: &str,
+    ) -> AppResult<()> {
+        if state.config.discord.public_key.is_none() {
+            return Ok(());
+        }
+        let application_id = state
+            .config
+            .discord
+            .client_id
+            .as_deref()
+            .ok_or_else(|| AppError::Config("DISCORD_CLIENT_ID is not set".to_string()))?;
+
+        let discord = state.discord.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable("Discord service not available".to_string())
+        })?;
+
+        discord
+            .register_guild_commands(application_id, guild_id, &Self::commands())
+            .await?;
+
+        tracing::debug!("Registered Discord commands in guild {}", guild_id);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Command processing
+    // ========================================================================
+
+    async fn process_command(state: &Arc<AppState>, interaction: Interaction) {
+        let lang = interaction
+            .locale
+            .as_deref()
+            .map(i18n::normalize_language)
+            .filter(|l| i18n::is_supported_language(l));
+        let lang = lang.as_deref();
+
+        let reply = Self::run_command(state, &interaction, lang)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Discord command failed in channel {:?}: {:?}",
+                    interaction.channel_id,
+                    e
+                );
+                i18n::tr(lang, "chat_commands.error", None)
+            });
+
+        let Some(application_id) = state.config.discord.client_id.as_deref() else {
+            return;
+        };
+        let Some(discord) = state.discord.read().await.clone() else {
+            return;
+        };
+
+        if let Err(e) = discord
+            .edit_interaction_response(application_id, &interaction.token, &reply)
+            .await
+        {
+            tracing::warn!("Failed to answer Discord interaction: {}", e);
+        }
+    }
+
+    async fn run_command(
+        state: &Arc<AppState>,
+        interaction: &Interaction,
+        lang: Option<&str>,
+    ) -> AppResult<String> {
+        let Some(data) = interaction.data.as_ref() else {
+            return Ok(i18n::tr(lang, "discord_bot.unknown_command", None));
+        };
+        let Some(subcommand) = data.options.first() else {
+            return Ok(i18n::tr(lang, "discord_bot.unknown_command", None));
+        };
+
+        let (Some(guild_id), Some(channel_id)) = (
+            interaction.guild_id.as_deref(),
+            interaction.channel_id.as_deref(),
+        ) else {
+            return Ok(i18n::tr(lang, "discord_bot.unknown_command", None));
+        };
+
+        let integrations: Vec<DiscordIntegration> =
+            DiscordIntegrationRepository::find_by_channel_id(&state.db, channel_id)
+                .await?
+                .into_iter()
+                .filter(|i| i.discord_guild_id == guild_id)
+                .collect();
+        if integrations.is_empty() {
+            return Ok(i18n::tr(lang, "discord_bot.not_linked", None));
+        }
+
+        match (data.name.as_str(), subcommand.name.as_str()) {
+            ("stream", "status") => Self::stream_status(state, &integrations, lang).await,
+            ("stream", "schedule") => Self::stream_schedule(state, &integrations, lang).await,
+            ("notify", action @ ("test" | "toggle")) => {
+                let member_id = interaction.member.as_ref().map(|m| m.user.id.as_str());
+                if !Self::member_can_manage(state, guild_id, member_id).await? {
+                    return Ok(i18n::tr(lang, "discord_bot.must_manage", None));
+                }
+
+                if action == "test" {
+                    Self::notify_test(state, &integrations, lang).await
+                } else {
+                    let event = subcommand
+                        .options
+                        .iter()
+                        .find(|o| o.name == "event")
+                        .and_then(|o| o.value.as_ref())
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    Self::notify_toggle(state, &integrations, event, lang).await
+                }
+            }
+            _ => Ok(i18n::tr(lang, "discord_bot.unknown_command", None)),
+        }
+    }
+
+    /// `/stream status`: whether each linked channel is live
+    async fn stream_status(
+        state: &Arc<AppState>,
+        integrations: &[DiscordIntegration],
+        lang: Option<&str>,
+    ) -> AppResult<String> {
+        let app_token = state.twitch.get_valid_app_access_token().await?;
+        let mut lines = Vec::with_capacity(integrations.len());
+
+        for integration in integrations {
+            if let Some(owner) = UserRepository::find_by_id(&state.db, &integration.user_id).await?
+            {
+                lines.push(chat_commands::status_line(state, &app_token, &owner, lang).await?);
+            }
+        }
+
+        Ok(lines.join("\n\n"))
+    }
+
+    /// `/stream schedule`: upcoming segments of each linked channel
+    async fn stream_schedule(
+        state: &Arc<AppState>,
+        integrations: &[DiscordIntegration],
+        lang: Option<&str>,
+    ) -> AppResult<String> {
+        let app_token = state.twitch.get_valid_app_access_token().await?;
+        let mut blocks = Vec::with_capacity(integrations.len());
+
+        for integration in integrations {
+            if let Some(owner) = UserRepository::find_by_id(&state.db, &integration.user_id).await?
+            {
+                blocks.push(chat_commands::schedule_block(state, &app_token, &owner, lang).await?);
+            }
+        }
+
+        Ok(blocks.join("\n\n"))
+    }
+
+    /// `/notify test`: send a test notification through each linked integration
+    async fn notify_test(
+        state: &Arc<AppState>,
+        integrations: &[DiscordIntegration],
+        lang: Option<&str>,
+    ) -> AppResult<String> {
+        for integration in integrations {
+            IntegrationService::send_discord_test_notification(state, integration, lang).await?;
+        }
+
+        Ok(i18n::tr(lang, "discord_bot.test_sent", None))
+    }
+
+    /// `/notify toggle <event>`: flip one notification type on the linked integrations
+    async fn notify_toggle(
+        state: &Arc<AppState>,
+        integrations: &[DiscordIntegration],
+        event: &str,
+        lang: Option<&str>,
+    ) -> AppResult<String> {
+        if !TOGGLE_EVENTS.contains(&event) {
+            return Ok(i18n::tr(lang, "discord_bot.unknown_command", None));
+        }
+
+        // All linked integrations follow the first one so they stay in sync
+        let enable = !Self::event_enabled(&integrations[0], event);
+
+        for integration in integrations {
+            let mut update = UpdateDiscordIntegration::default();
+            match event {
+                "stream_online" => update.notify_stream_online = Some(enable),
+                "stream_offline" => update.notify_stream_offline = Some(enable),
+                "title_change" => update.notify_title_change = Some(enable),
+                "category_change" => update.notify_category_change = Some(enable),
+                _ => update.notify_reward_redemption = Some(enable),
+            }
+            let updated =
+                IntegrationService::update_discord_integration(state, &integration.id, update)
+                    .await?;
+
+            AuditService::record(
+                state,
+                AUDIT_ACTOR_DISCORD_BOT,
+                &integration.user_id,
+                AUDIT_ENTITY_DISCORD_INTEGRATION,
+                Some(&integration.id),
+                AUDIT_ACTION_UPDATE,
+                AuditService::diff(integration, &updated),
+            )
+            .await;
+        }
+
+        let key = if enable {
+            "discord_bot.toggled_on"
+        } else {
+            "discord_bot.toggled_off"
+        };
+        Ok(i18n::tr(lang, key, Some(&[("event", event)])))
+    }
+
+    // ========================================================================
+    // Helper functions
+    // ========================================================================
+
+    async fn member_can_manage(
+        state: &Arc<AppState>,
+        guild_id: &str,
+        member_id: Option<&str>,
+    ) -> AppResult<bool> {
+        let Some(member_id) = member_id else {
+            return Ok(false);
+        };
+        let discord = state.discord.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable("Discord service not available".to_string())
+        })?;
+
+        discord
+            .user_has_manage_permissions(guild_id, member_id)
+            .await
+    }
+
+    fn event_enabled(integration: &DiscordIntegration, event: &str) -> bool {
+        match event {
+            "stream_online" => integration.notify_stream_online,
+            "stream_offline" => integration.notify_stream_offline,
+            "title_change" => integration.notify_title_change,
+            "category_change" => integration.notify_category_change,
+            _ => integration.notify_reward_redemption,
+        }
+    }
+
+    /// Guild command definitions (bulk overwrite payload)
+    fn commands() -> Value {
+        let choices: Vec<Value> = TOGGLE_EVENTS
+            .iter()
+            .map(|e| json!({ "name": e, "value": e }))
+            .collect();
+
+        json!([
+            {
+                "name": "stream",
+                "description": "Stream information",
+                "dm_permission": false,
+                "options": [
+                    { "type": 1, "name": "status", "description": "Is the stream live right now" },
+                    { "type": 1, "name": "schedule", "description": "Upcoming streams" }
+                ]
+            },
+            {
+                "name": "notify",
+                "description": "Manage stream notifications in this channel",
+                "dm_permission": false,
+                // MANAGE_GUILD; re-checked server-side on every invocation
+                "default_member_permissions": "32",
+                "options": [
+                    { "type": 1, "name": "test", "description": "Send a test notification" },
+                    {
+                        "type": 1,
+                        "name": "toggle",
+                        "description": "Turn a notification type on or off",
+                        "options": [
+                            {
+                                "type": 3,
+                                "name": "event",
+                                "description": "Notification type",
+                                "required": true,
+                                "choices": choices
+                            }
+                        ]
+                    }
+                ]
+            }
+        ])
+    }
+}
@@ -0,0 +1,283 @@
+//! Shared rate limiter for the Discord REST API.
+//!
+//! Discord groups routes into buckets (reported in `X-RateLimit-Bucket`) that are
+//! split further by the route's major parameter (channel, guild or webhook). Each
+//! request reserves a slot in its bucket before it is sent and waits for the
+//! bucket (or the global limit) to reset when none is left, instead of running
+//! into 429s during a fan-out to many channels.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::header::HeaderMap;
+
+/// Identifies the rate limit a request counts against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteKey {
+    /// Method and path with ids replaced, e.g. `POST /channels/:id/messages`
+    pub route: String,
+    /// Major parameter, e.g. `channels/123`
+    pub major: String,
+}
+
+impl RouteKey {
+    pub fn new(method: &reqwest::Method, url: &reqwest::Url) -> Self {
+        let path = url.path();
+        let path = path
+            .strip_prefix("/api/v10")
+            .or_else(|| path.strip_prefix("/api"))
+            .unwrap_or(path);
+
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut route = Vec::with_capacity(segments.len());
+        let mut major = String::new();
+
+        let mut i = 0;
+        while i < segments.len() {
+            let segment = segments[i];
+            route.push(segment.to_string());
+
+            if let Some(&id) = segments.get(i + 1) {
+                match segment {
+                    "channels" | "guilds" if major.is_empty() => {
+                        major = format!("{}/{}", segment, id);
+                        route.push(":id".to_string());
+                        i += 2;
+                        continue;
+                    }
+                    "webhooks" if major.is_empty() => {
+                        // Webhooks are limited per id + token
+                        let token = segments.get(i + 2).copied().unwrap_or_default();
+                        major = format!("webhooks/{}/{}", id, token);
+                        route.push(":id".to_string());
+                        if !token.is_empty() {
+                            route.push(":token".to_string());
+                        }
+                        i += if token.is_empty() { 2 } else { 3 };
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            if segment.chars().all(|c| c.is_ascii_digit()) {
+                route.pop();
+                route.push(":id".to_string());
+            }
+            i += 1;
+        }
+
+        Self {
+            route: format!("{} /{}", method, route.join("/")),
+            major,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    remaining: u32,
+    reset_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    global_until: Option<Instant>,
+    /// Route -> bucket hash learned from `X-RateLimit-Bucket`
+    route_buckets: HashMap<String, String>,
+    /// `bucket:major` (or `route:major` until the bucket is known) -> state
+    buckets: HashMap<String, Bucket>,
+}
+
+impl State {
+    fn bucket_key(&self, key: &RouteKey) -> String {
+        match self.route_buckets.get(&key.route) {
+            Some(bucket) => format!("{}:{}", bucket, key.major),
+            None => format!("{}:{}", key.route, key.major),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DiscordRateLimiter {
+    state: Mutex<State>,
+}
+
+impl DiscordRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until a request on `key` may be sent and reserve a slot for it.
+    /// Returns the remaining wait as error when it would exceed `max_wait`.
+    pub async fn acquire(&self, key: &RouteKey, max_wait: Duration) -> Result<(), Duration> {
+        let mut waited = Duration::ZERO;
+
+        loop {
+            let Some(wait) = self.reserve(key) else {
+                return Ok(());
+            };
+            if waited + wait > max_wait {
+                return Err(wait);
+            }
+
+            tracing::debug!(
+                "Discord rate limit: waiting {:?} for {} ({})",
+                wait,
+                key.route,
+                key.major
+            );
+            tokio::time::sleep(wait).await;
+            waited += wait;
+        }
+    }
+
+    /// Record the limits reported by a response
+    pub fn update(&self, key: &RouteKey, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let remaining = header("x-ratelimit-remaining").and_then(|v| v.parse::<u32>().ok());
+        let reset_after = header("x-ratelimit-reset-after")
+            .and_then(|v| v.parse::<f64>().ok())
+            .or_else(|| {
+                // Fall back to the absolute reset time (epoch seconds)
+                let reset = header("x-ratelimit-reset")?.parse::<f64>().ok()?;
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()?
+                    .as_secs_f64();
+                Some((reset - now).max(0.0))
+            });
+
+        let mut state = self.lock();
+        if let Some(bucket) = header("x-ratelimit-bucket") {
+            state
+                .route_buckets
+                .insert(key.route.clone(), bucket.to_string());
+        }
+
+        if let (Some(remaining), Some(reset_after)) = (remaining, reset_after) {
+            let bucket_key = state.bucket_key(key);
+            state.buckets.insert(
+                bucket_key,
+                Bucket {
+                    remaining,
+                    reset_at: Instant::now() + Duration::from_secs_f64(reset_after),
+                },
+            );
+        }
+    }
+
+    /// Block the bucket of `key` for `retry_after` (after a 429)
+    pub fn block_route(&self, key: &RouteKey, retry_after: Duration) {
+        let mut state = self.lock();
+        let bucket_key = state.bucket_key(key);
+        state.buckets.insert(
+            bucket_key,
+            Bucket {
+                remaining: 0,
+                reset_at: Instant::now() + retry_after,
+            },
+        );
+    }
+
+    /// Block every request for `retry_after` (global 429)
+    pub fn block_global(&self, retry_after: Duration) {
+        self.lock().global_until = Some(Instant::now() + retry_after);
+    }
+
+    /// Take a slot, or return how long to wait for one
+    fn reserve(&self, key: &RouteKey) -> Option<Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+
+        if let Some(until) = state.global_until {
+            if until > now {
+                return Some(until - now);
+            }
+            state.global_until = None;
+        }
+
+        let bucket_key = state.bucket_key(key);
+        match state.buckets.get_mut(&bucket_key) {
+            Some(bucket) if bucket.reset_at <= now => {
+                // Window is over; the next response reports the new one
+                state.buckets.remove(&bucket_key);
+                None
+            }
+            Some(bucket) if bucket.remaining == 0 => Some(bucket.reset_at - now),
+            Some(bucket) => {
+                bucket.remaining -= 1;
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(method: reqwest::Method, url: &str) -> RouteKey {
+        RouteKey::new(&method, &reqwest::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_route_key_uses_major_parameter() {
+        let k = key(
+            reqwest::Method::POST,
+            "https://discord.com/api/v10/channels/123/messages/456/crosspost",
+        );
+        assert_eq!(k.route, "POST /channels/:id/messages/:id/crosspost");
+        assert_eq!(k.major, "channels/123");
+
+        let k = key(
+            reqwest::Method::POST,
+            "https://discord.com/api/webhooks/1/tok?wait=true",
+        );
+        assert_eq!(k.route, "POST /webhooks/:id/:token");
+        assert_eq!(k.major, "webhooks/1/tok");
+    }
+
+    #[test]
+    fn test_exhausted_bucket_waits_until_reset() {
+        let limiter = DiscordRateLimiter::new();
+        let k = key(
+            reqwest::Method::POST,
+            "https://discord.com/api/v10/channels/1/messages",
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-bucket", "abc".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "1".parse().unwrap());
+        headers.insert("x-ratelimit-reset-after", "5".parse().unwrap());
+        limiter.update(&k, &headers);
+
+        // One slot left, then the bucket is empty until the reset
+        assert!(limiter.reserve(&k).is_none());
+        let wait = limiter.reserve(&k).expect("bucket should be exhausted");
+        assert!(wait > Duration::from_secs(4));
+
+        // Another channel has its own bucket
+        let other = key(
+            reqwest::Method::POST,
+            "https://discord.com/api/v10/channels/2/messages",
+        );
+        assert!(limiter.reserve(&other).is_none());
+    }
+
+    #[test]
+    fn test_global_block_applies_to_all_routes() {
+        let limiter = DiscordRateLimiter::new();
+        limiter.block_global(Duration::from_secs(2));
+
+        let k = key(reqwest::Method::GET, "https://discord.com/api/v10/guilds/1");
+        assert!(limiter.reserve(&k).is_some());
+    }
+}
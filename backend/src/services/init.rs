@@ -1,5 +1,5 @@
 //! Initialization helpers for the application:
-//! - database connection + migrations
+//! - database connection + migrations (including re-encryption of stored tokens)
 //! - optional integrations (Telegram / Discord)
 //! - background worker spawn helpers
 //!
@@ -11,6 +11,10 @@ use anyhow::Result;
 use chrono::{Duration, Utc};
 
 use crate::config::Config;
+use crate::services::discord_interactions::DiscordInteractionService;
+
+/// Migrations embedded at build time (path relative to the crate root)
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
 
 /// Redact potentially sensitive information from a database URL before logging.
 ///
@@ -35,7 +39,9 @@ pub fn redact_db_url(db_url: &str) -> String {
 /// Initialize SQLite database connection and run migrations.
 ///
 /// Creates the parent directory for the database file (if applicable),
-/// opens a connection pool using `create_if_missing(true)` and runs migrations.
+/// opens a connection pool using `create_if_missing(true)`, runs migrations,
+/// re-encrypts stored tokens with the current encryption key and grants the
+/// configured administrators their role.
 pub async fn init_db(config: &Config) -> Result<sqlx::SqlitePool> {
     let db_url = &config.database.url;
     tracing::info!("Connecting to database: {}", redact_db_url(db_url));
@@ -84,8 +90,19 @@ pub async fn init_db(config: &Config) -> Result<sqlx::SqlitePool> {
     }
 
     tracing::info!("Running database migrations");
-    // Keep the same path as before (relative to crate root)
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    MIGRATOR.run(&pool).await?;
+
+    // Encrypt tokens stored before encryption was enabled or under a rotated-out key
+    let reencrypted = crate::db::UserRepository::reencrypt_tokens(&pool).await?;
+    if reencrypted > 0 {
+        tracing::info!("Re-encrypted stored tokens for {} users", reencrypted);
+    }
+
+    // ADMIN_TWITCH_IDS is authoritative: ids removed from it lose the role on restart
+    let changed = crate::db::UserRepository::sync_admins(&pool, &config.admin.twitch_ids).await?;
+    if changed > 0 {
+        tracing::info!("Updated the administrator role of {} users", changed);
+    }
 
     Ok(pool)
 }
@@ -98,7 +115,8 @@ pub async fn initialize_optional_integrations(state: &Arc<crate::AppState>) {
     // Telegram
     if let Some(ref token) = state.config.telegram.bot_token {
         tracing::info!("Initializing Telegram bot");
-        match crate::services::telegram::TelegramService::new(token.clone()).await {
+        match crate::services::telegram::TelegramService::new(token.clone(), state.db.clone()).await
+        {
             Ok(telegram) => {
                 *state.telegram.write().await = Some(telegram);
                 tracing::info!("Telegram bot initialized successfully");
@@ -116,6 +134,13 @@ pub async fn initialize_optional_integrations(state: &Arc<crate::AppState>) {
             Ok(discord) => {
                 *state.discord.write().await = Some(discord);
                 tracing::info!("Discord bot initialized successfully");
+
+                if state.config.discord.public_key.is_some() {
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        DiscordInteractionService::register_commands_for_all_guilds(&state).await;
+                    });
+                }
             }
             Err(e) => {
                 tracing::warn!("Failed to initialize Discord bot: {}", e);
@@ -124,11 +149,24 @@ pub async fn initialize_optional_integrations(state: &Arc<crate::AppState>) {
     }
 }
 
+/// Names of the periodic background workers, as reported in metrics and checked by
+/// the readiness endpoint
+pub const WORKER_EVENTSUB_SYNC: &str = "eventsub_sync";
+pub const WORKER_CALENDAR_SYNC: &str = "calendar_sync";
+pub const WORKER_TOKEN_REFRESH: &str = "token_refresh";
+pub const WORKER_NOTIFICATION_RETRY: &str = "notification_retry";
+pub const WORKER_INTEGRATION_HEALTH: &str = "integration_health";
+
 /// Spawn background workers:
-/// - periodic EventSub synchronization for all users
+/// - periodic EventSub synchronization for all users and watched channels
 /// - periodic calendar synchronization for integrations
 /// - periodic user token refresh (refreshes tokens that expire within 1 hour)
 /// - notification retry queue processor
+/// - daily health probe of enabled integrations
+/// - Telegram bot update dispatcher: commands and chat membership (when the bot is configured)
+///
+/// Each periodic worker records a heartbeat in the metrics at the start of every
+/// iteration (see the `WORKER_*` names above).
 ///
 /// These are spawned as `tokio::spawn` tasks. The function returns a vector of
 /// `JoinHandle<()>`s so callers can await task shutdown. Each worker listens
@@ -145,6 +183,7 @@ pub fn spawn_background_workers(
         let state = state.clone();
         handles.push(tokio::spawn(async move {
             loop {
+                crate::metrics::record_worker_heartbeat(WORKER_EVENTSUB_SYNC);
                 tracing::info!("Starting periodic EventSub synchronization for all users");
 
                 match crate::db::UserRepository::list_all(&state.db).await {
@@ -180,6 +219,15 @@ pub fn spawn_background_workers(
                     }
                 }
 
+                if let Err(e) =
+                    crate::services::subscriptions::SubscriptionManager::sync_watched_channels(
+                        &state,
+                    )
+                    .await
+                {
+                    tracing::warn!("Failed to sync watched channel subscriptions: {:?}", e);
+                }
+
                 // Sleep for 1 hour between sync cycles or exit early on shutdown.
                 tokio::select! {
                     _ = shutdown_rx.recv() => {
@@ -198,6 +246,7 @@ pub fn spawn_background_workers(
         let state = state.clone();
         handles.push(tokio::spawn(async move {
             loop {
+                crate::metrics::record_worker_heartbeat(WORKER_CALENDAR_SYNC);
                 tracing::info!("Starting periodic calendar synchronization for integrations");
 
                 if let Err(e) =
@@ -227,6 +276,7 @@ pub fn spawn_background_workers(
             const CHECK_INTERVAL_SECS: u64 = 1800; // Check every 30 minutes
 
             loop {
+                crate::metrics::record_worker_heartbeat(WORKER_TOKEN_REFRESH);
                 tracing::info!("Starting periodic user token refresh check");
 
                 match crate::db::UserRepository::list_all(&state.db).await {
@@ -334,6 +384,7 @@ pub fn spawn_background_workers(
         let state = state.clone();
         handles.push(tokio::spawn(async move {
             loop {
+                crate::metrics::record_worker_heartbeat(WORKER_NOTIFICATION_RETRY);
                 tracing::debug!("Polling notification retry queue for due tasks");
 
                 // Exit early if shutdown requested
@@ -413,5 +464,48 @@ pub fn spawn_background_workers(
         }));
     }
 
+    // Integration health probe worker
+    {
+        let mut shutdown_rx = shutdown.subscribe();
+        let state = state.clone();
+        handles.push(tokio::spawn(async move {
+            loop {
+                crate::metrics::record_worker_heartbeat(WORKER_INTEGRATION_HEALTH);
+                tracing::info!("Starting daily integration health probe");
+
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        tracing::info!("Integration health worker shutting down");
+                        break;
+                    }
+                    result = crate::services::integration_health::IntegrationHealthService::probe_all(&state) => {
+                        match result {
+                            Ok(count) => tracing::info!("Integration health probe updated {} integration(s)", count),
+                            Err(e) => tracing::warn!("Integration health probe failed: {:?}", e),
+                        }
+                    }
+                }
+
+                // Sleep for 1 day between probes or exit early on shutdown.
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        tracing::info!("Integration health worker shutting down");
+                        break;
+                    }
+                    _ = tokio::time::sleep(std::time::Duration::from_secs(24 * 60 * 60)) => {}
+                }
+            }
+        }));
+    }
+
+    // Telegram bot update dispatcher (long polling)
+    if state.config.telegram.commands_enabled {
+        let shutdown_rx = shutdown.subscribe();
+        let state = state.clone();
+        handles.push(tokio::spawn(async move {
+            crate::services::telegram_bot::run(state, shutdown_rx).await;
+        }));
+    }
+
     handles
 }
@@ -0,0 +1,412 @@
+//! Failure streaks of notification integrations.
+//!
+//! Permanent delivery failures (chat or channel gone, missing access) are counted per
+//! integration and any successful send resets the count. Once an integration fails
+//! `auto_disable_after_failures` times in a row it is disabled with the reason recorded,
+//! and its owner is told through a Telegram or Discord direct message.
+//!
+//! A daily probe additionally checks that every enabled integration can still be posted
+//! to and stores the outcome in its `health_status`, so problems show up before the next
+//! stream goes live.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::db::{
+    DiscordIntegration, DiscordIntegrationRepository, TelegramIntegration,
+    TelegramIntegrationRepository, User, UserRepository, DISABLED_REASON_PERMISSION_DENIED,
+    DISABLED_REASON_TARGET_GONE, HEALTH_STATUS_ERROR, HEALTH_STATUS_MISSING_PERMISSIONS,
+    HEALTH_STATUS_OK, HEALTH_STATUS_UNREACHABLE,
+};
+use crate::error::{AppError, AppResult, DeliveryErrorKind};
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_UPDATE, AUDIT_ACTOR_SYSTEM, AUDIT_ENTITY_DISCORD_INTEGRATION,
+    AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+};
+use crate::services::discord::{
+    compute_channel_permissions, DiscordChannel, DiscordRole, DiscordService,
+    PERMISSION_EMBED_LINKS, PERMISSION_SEND_MESSAGES, PERMISSION_VIEW_CHANNEL,
+};
+use crate::services::telegram::TelegramMessage;
+use crate::AppState;
+
+pub struct IntegrationHealthService;
+
+/// Bot user and per-guild data shared by the Discord probes of one run, so each
+/// guild costs one member and one roles lookup however many integrations it has
+#[derive(Default)]
+struct DiscordProbeCache {
+    bot_id: Option<String>,
+    /// Guild id -> (bot member roles, guild roles)
+    guilds: HashMap<String, (Vec<String>, Vec<DiscordRole>)>,
+}
+
+impl DiscordProbeCache {
+    /// Permissions the bot has in a guild channel, after role and channel overwrites
+    async fn channel_permissions(
+        &mut self,
+        discord: &DiscordService,
+        channel: &DiscordChannel,
+    ) -> AppResult<u64> {
+        let guild_id = channel
+            .guild_id
+            .as_deref()
+            .ok_or_else(|| AppError::discord("Channel does not belong to a guild"))?;
+
+        let bot_id = match &self.bot_id {
+            Some(id) => id.clone(),
+            None => {
+                let id = discord.get_current_user().await?.id;
+                self.bot_id = Some(id.clone());
+                id
+            }
+        };
+
+        if !self.guilds.contains_key(guild_id) {
+            let member = discord.get_guild_member(guild_id, &bot_id).await?;
+            let roles = discord.get_guild_roles(guild_id).await?;
+            self.guilds
+                .insert(guild_id.to_string(), (member.roles, roles));
+        }
+        let (member_roles, roles) = &self.guilds[guild_id];
+
+        Ok(compute_channel_permissions(
+            guild_id,
+            &bot_id,
+            member_roles,
+            roles,
+            &channel.permission_overwrites,
+        ))
+    }
+}
+
+impl IntegrationHealthService {
+    /// End the failure streak of an integration after a successful send
+    pub async fn record_success(
+        state: &Arc<AppState>,
+        destination_type: &str,
+        integration_id: &str,
+    ) -> AppResult<()> {
+        match destination_type {
+            "telegram" => {
+                TelegramIntegrationRepository::reset_failures(&state.db, integration_id).await
+            }
+            "discord" => {
+                DiscordIntegrationRepository::reset_failures(&state.db, integration_id).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Count a failed send. Only permanent failures extend the streak; the integration
+    /// is disabled when the streak reaches the configured threshold.
+    pub async fn record_failure(
+        state: &Arc<AppState>,
+        destination_type: &str,
+        integration_id: &str,
+        kind: DeliveryErrorKind,
+    ) -> AppResult<()> {
+        let reason = match kind {
+            DeliveryErrorKind::TargetGone => DISABLED_REASON_TARGET_GONE,
+            DeliveryErrorKind::PermissionDenied => DISABLED_REASON_PERMISSION_DENIED,
+            _ => return Ok(()),
+        };
+
+        let streak = match destination_type {
+            "telegram" => {
+                TelegramIntegrationRepository::record_failure(&state.db, integration_id).await?
+            }
+            "discord" => {
+                DiscordIntegrationRepository::record_failure(&state.db, integration_id).await?
+            }
+            _ => return Ok(()),
+        };
+
+        let threshold = state.config.notification_retry.auto_disable_after_failures;
+        if threshold == 0 || streak < i64::from(threshold) {
+            return Ok(());
+        }
+
+        Self::disable(state, destination_type, integration_id, reason, streak).await
+    }
+
+    /// Disable an integration and tell its owner why
+    async fn disable(
+        state: &Arc<AppState>,
+        destination_type: &str,
+        integration_id: &str,
+        reason: &str,
+        streak: i64,
+    ) -> AppResult<()> {
+        // Resolve owner and a readable destination name; skip already disabled integrations
+        // so late retries don't message the owner twice
+        let (owner_id, entity, previous_reason, destination_key, destination_name) =
+            match destination_type {
+                "telegram" => {
+                    let Some(integration) =
+                        TelegramIntegrationRepository::find_by_id(&state.db, integration_id)
+                            .await?
+                    else {
+                        return Ok(());
+                    };
+                    if !integration.is_enabled {
+                        return Ok(());
+                    }
+                    TelegramIntegrationRepository::disable(&state.db, integration_id, reason)
+                        .await?;
+
+                    let name = integration
+                        .telegram_chat_title
+                        .unwrap_or(integration.telegram_chat_id);
+                    (
+                        integration.user_id,
+                        AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+                        integration.disabled_reason,
+                        "integration_health.telegram_chat",
+                        name,
+                    )
+                }
+                "discord" => {
+                    let Some(integration) =
+                        DiscordIntegrationRepository::find_by_id(&state.db, integration_id).await?
+                    else {
+                        return Ok(());
+                    };
+                    if !integration.is_enabled {
+                        return Ok(());
+                    }
+                    DiscordIntegrationRepository::disable(&state.db, integration_id, reason)
+                        .await?;
+
+                    let name = integration
+                        .discord_channel_name
+                        .unwrap_or(integration.discord_channel_id);
+                    (
+                        integration.user_id,
+                        AUDIT_ENTITY_DISCORD_INTEGRATION,
+                        integration.disabled_reason,
+                        "integration_health.discord_channel",
+                        name,
+                    )
+                }
+                _ => return Ok(()),
+            };
+
+        tracing::warn!(
+            "Disabled {} integration {} after {} consecutive failures ({})",
+            destination_type,
+            integration_id,
+            streak,
+            reason
+        );
+
+        AuditService::record(
+            state,
+            AUDIT_ACTOR_SYSTEM,
+            &owner_id,
+            entity,
+            Some(integration_id),
+            AUDIT_ACTION_UPDATE,
+            json!({
+                "is_enabled": { "from": true, "to": false },
+                "disabled_reason": { "from": previous_reason, "to": reason },
+            }),
+        )
+        .await;
+
+        let Some(owner) = UserRepository::find_by_id(&state.db, &owner_id).await? else {
+            return Ok(());
+        };
+        let lang = owner.lang.as_deref();
+        let destination = crate::i18n::tr(
+            lang,
+            destination_key,
+            Some(&[("name", destination_name.as_str())]),
+        );
+        let reason_text =
+            crate::i18n::tr(lang, &format!("integration_health.reason.{}", reason), None);
+        let streak = streak.to_string();
+        let text = crate::i18n::tr(
+            lang,
+            "integration_health.disabled",
+            Some(&[
+                ("destination", destination.as_str()),
+                ("count", streak.as_str()),
+                ("reason", reason_text.as_str()),
+            ]),
+        );
+
+        Self::notify_owner(state, &owner, &text).await;
+        Ok(())
+    }
+
+    /// Send a direct message to a user through Telegram, falling back to Discord
+    async fn notify_owner(state: &Arc<AppState>, owner: &User, text: &str) {
+        if let Some(telegram_user_id) = &owner.telegram_user_id {
+            if let Some(telegram) = state.telegram.read().await.clone() {
+                let message = TelegramMessage {
+                    chat_id: telegram_user_id.clone(),
+                    text: text.to_string(),
+                    parse_mode: None,
+                    ..Default::default()
+                };
+                match telegram.send_message(message).await {
+                    Ok(_) => return,
+                    Err(e) => {
+                        tracing::warn!("Failed to notify user {} via Telegram DM: {}", owner.id, e)
+                    }
+                }
+            }
+        }
+
+        if let Some(discord_user_id) = &owner.discord_user_id {
+            if let Some(discord) = state.discord.read().await.clone() {
+                match discord.send_direct_message(discord_user_id, text).await {
+                    Ok(_) => return,
+                    Err(e) => {
+                        tracing::warn!("Failed to notify user {} via Discord DM: {}", owner.id, e)
+                    }
+                }
+            }
+        }
+
+        tracing::info!(
+            "User {} could not be notified about a disabled integration",
+            owner.id
+        );
+    }
+
+    /// Probe every enabled integration and store the results.
+    /// Returns the number of integrations whose status was updated.
+    pub async fn probe_all(state: &Arc<AppState>) -> AppResult<usize> {
+        let mut updated = 0;
+
+        if let Some(telegram) = state.telegram.read().await.clone() {
+            for integration in TelegramIntegrationRepository::find_all_enabled(&state.db).await? {
+                let status = match Self::probe_telegram(&telegram, &integration).await {
+                    Ok(status) => status,
+                    Err(e) => match Self::status_for_error(&e) {
+                        Some(status) => status,
+                        None => {
+                            tracing::debug!(
+                                "Skipping health status of Telegram integration {}: {}",
+                                integration.id,
+                                e
+                            );
+                            continue;
+                        }
+                    },
+                };
+                Self::store(state, "telegram", &integration.id, status).await?;
+                updated += 1;
+            }
+        }
+
+        if let Some(discord) = state.discord.read().await.clone() {
+            let mut cache = DiscordProbeCache::default();
+            for integration in DiscordIntegrationRepository::find_all_enabled(&state.db).await? {
+                let status = match Self::probe_discord(&discord, &mut cache, &integration).await {
+                    Ok(status) => status,
+                    Err(e) => match Self::status_for_error(&e) {
+                        Some(status) => status,
+                        None => {
+                            tracing::debug!(
+                                "Skipping health status of Discord integration {}: {}",
+                                integration.id,
+                                e
+                            );
+                            continue;
+                        }
+                    },
+                };
+                Self::store(state, "discord", &integration.id, status).await?;
+                updated += 1;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    /// The chat is reachable and the bot may still post there
+    async fn probe_telegram(
+        telegram: &crate::services::telegram::TelegramService,
+        integration: &TelegramIntegration,
+    ) -> AppResult<&'static str> {
+        Ok(
+            if telegram
+                .can_post_to_chat(&integration.telegram_chat_id)
+                .await?
+            {
+                HEALTH_STATUS_OK
+            } else {
+                HEALTH_STATUS_MISSING_PERMISSIONS
+            },
+        )
+    }
+
+    /// The webhook still resolves, or the channel exists and the bot may send embeds there
+    async fn probe_discord(
+        discord: &DiscordService,
+        cache: &mut DiscordProbeCache,
+        integration: &DiscordIntegration,
+    ) -> AppResult<&'static str> {
+        if let Some(webhook_url) = &integration.discord_webhook_url {
+            discord.get_webhook(webhook_url).await?;
+            return Ok(HEALTH_STATUS_OK);
+        }
+
+        let channel = discord.get_channel(&integration.discord_channel_id).await?;
+        let permissions = cache.channel_permissions(discord, &channel).await?;
+
+        const REQUIRED: u64 =
+            PERMISSION_VIEW_CHANNEL | PERMISSION_SEND_MESSAGES | PERMISSION_EMBED_LINKS;
+        Ok(if permissions & REQUIRED == REQUIRED {
+            HEALTH_STATUS_OK
+        } else {
+            HEALTH_STATUS_MISSING_PERMISSIONS
+        })
+    }
+
+    /// Health status for a failed probe; `None` for temporary errors (keep the last status)
+    fn status_for_error(error: &AppError) -> Option<&'static str> {
+        // Discord answers 404 for guild members when the bot left the server
+        if matches!(error, AppError::NotFound(_)) {
+            return Some(HEALTH_STATUS_UNREACHABLE);
+        }
+
+        match error.delivery_kind() {
+            DeliveryErrorKind::TargetGone => Some(HEALTH_STATUS_UNREACHABLE),
+            DeliveryErrorKind::PermissionDenied => Some(HEALTH_STATUS_MISSING_PERMISSIONS),
+            DeliveryErrorKind::Rejected => Some(HEALTH_STATUS_ERROR),
+            DeliveryErrorKind::RateLimited { .. } | DeliveryErrorKind::Transient => None,
+        }
+    }
+
+    async fn store(
+        state: &Arc<AppState>,
+        destination_type: &str,
+        integration_id: &str,
+        status: &str,
+    ) -> AppResult<()> {
+        if status != HEALTH_STATUS_OK {
+            tracing::warn!(
+                "Health probe of {} integration {}: {}",
+                destination_type,
+                integration_id,
+                status
+            );
+        }
+
+        match destination_type {
+            "telegram" => {
+                TelegramIntegrationRepository::set_health(&state.db, integration_id, status).await
+            }
+            "discord" => {
+                DiscordIntegrationRepository::set_health(&state.db, integration_id, status).await
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -1,22 +1,31 @@
 use std::sync::Arc;
 
 use crate::db::{
-    ChatType, CreateDiscordIntegration, CreateTelegramIntegration, DiscordIntegrationRepository,
-    SettingsShareRepository, TelegramIntegrationRepository, UpdateDiscordIntegration,
-    UpdateTelegramIntegration,
+    ChatType, CreateDiscordIntegration, CreateTelegramIntegration, DiscordIntegration,
+    DiscordIntegrationRepository, NotificationQueueRepository, SettingsShareRepository,
+    SharePermission, TelegramIntegrationRepository, TelegramLinkCode, TelegramLinkCodeRepository,
+    UpdateDiscordIntegration, UpdateTelegramIntegration, UserRepository, DISCORD_MENTION_HERE,
 };
 use crate::error::{AppError, AppResult};
+use crate::services::auth::AuthService;
+use crate::services::discord_interactions::DiscordInteractionService;
 use crate::AppState;
 
+/// Length of one-time codes used by the Telegram bot `/link` command
+const TELEGRAM_LINK_CODE_LENGTH: usize = 12;
+/// How long a Telegram link code stays valid
+const TELEGRAM_LINK_CODE_TTL_SECONDS: i64 = 15 * 60;
+
 pub struct IntegrationService;
 
 impl IntegrationService {
-    /// Check if user has access to owner's resources (read or manage)
+    /// Check if user holds `permission` on owner's resources (owners hold every permission,
+    /// grantees the ones granted by their share role)
     pub async fn check_access(
         state: &Arc<AppState>,
         owner_id: &str,
         user_id: &str,
-        require_manage: bool,
+        permission: SharePermission,
     ) -> AppResult<bool> {
         if owner_id == user_id {
             return Ok(true);
@@ -26,11 +35,7 @@ impl IntegrationService {
             SettingsShareRepository::find_by_owner_and_grantee(&state.db, owner_id, user_id)
                 .await?;
 
-        match share {
-            Some(s) if require_manage => Ok(s.can_manage),
-            Some(_) => Ok(true), // Read access
-            None => Ok(false),
-        }
+        Ok(share.is_some_and(|s| s.role.allows(permission)))
     }
 
     /// Validate Telegram chat ID format
@@ -38,25 +43,23 @@ impl IntegrationService {
         let chat_id = chat_id.trim();
 
         match chat_type {
-            Some(ChatType::Group) => {
+            Some(ChatType::Group)
                 if chat_id.len() < 2
                     || !chat_id.starts_with('-')
-                    || !chat_id[1..].chars().all(|c| c.is_ascii_digit())
-                {
-                    return Err(AppError::Validation(crate::i18n::t(
-                        "validation.chat_id.group_invalid",
-                    )));
-                }
+                    || !chat_id[1..].chars().all(|c| c.is_ascii_digit()) =>
+            {
+                return Err(AppError::Validation(crate::i18n::t(
+                    "validation.chat_id.group_invalid",
+                )));
             }
-            Some(ChatType::Supergroup) | Some(ChatType::Channel) => {
+            Some(ChatType::Supergroup) | Some(ChatType::Channel)
                 if chat_id.len() < 5
                     || !chat_id.starts_with("-100")
-                    || !chat_id[4..].chars().all(|c| c.is_ascii_digit())
-                {
-                    return Err(AppError::Validation(crate::i18n::t(
-                        "validation.chat_id.supergroup_invalid",
-                    )));
-                }
+                    || !chat_id[4..].chars().all(|c| c.is_ascii_digit()) =>
+            {
+                return Err(AppError::Validation(crate::i18n::t(
+                    "validation.chat_id.supergroup_invalid",
+                )));
             }
             _ => {}
         }
@@ -80,6 +83,63 @@ impl IntegrationService {
             .await
     }
 
+    /// Validate that a forum topic exists in the Telegram chat
+    pub async fn check_telegram_topic(
+        state: &Arc<AppState>,
+        chat_id: &str,
+        message_thread_id: i32,
+    ) -> AppResult<()> {
+        let tg_guard = state.telegram.read().await;
+        let telegram_service = tg_guard.as_ref().ok_or_else(|| {
+            AppError::Validation(crate::i18n::t("validation.telegram_bot_not_configured"))
+        })?;
+
+        telegram_service
+            .validate_forum_topic(chat_id, message_thread_id)
+            .await
+    }
+
+    /// Validate mention settings: each one must be empty, "here" or a role of the guild
+    pub async fn check_discord_mentions(
+        state: &Arc<AppState>,
+        guild_id: &str,
+        update: &UpdateDiscordIntegration,
+    ) -> AppResult<()> {
+        let role_ids: Vec<&str> = [
+            &update.mention_stream_online,
+            &update.mention_stream_offline,
+            &update.mention_title_change,
+            &update.mention_category_change,
+            &update.mention_reward_redemption,
+        ]
+        .into_iter()
+        .filter_map(|m| m.as_deref())
+        .filter(|m| !m.is_empty() && *m != DISCORD_MENTION_HERE)
+        .collect();
+
+        if role_ids.is_empty() {
+            return Ok(());
+        }
+
+        let discord = state.discord.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable(crate::i18n::t(
+                "service_unavailable.discord_service_unavailable",
+            ))
+        })?;
+        let roles = discord.get_guild_roles(guild_id).await?;
+
+        // The @everyone role shares the guild's id and is never a valid choice
+        for role_id in role_ids {
+            if role_id == guild_id || !roles.iter().any(|r| r.id == role_id) {
+                return Err(AppError::Validation(crate::i18n::t(
+                    "validation.discord_role_not_found",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if user has manage permissions in Discord guild
     pub async fn check_discord_manage_permissions(
         state: &Arc<AppState>,
@@ -153,9 +213,12 @@ impl IntegrationService {
         chat_id: String,
         chat_title: Option<String>,
         chat_type: Option<ChatType>,
+        message_thread_id: Option<i32>,
     ) -> AppResult<crate::db::TelegramIntegration> {
         // Check if integration already exists
-        let exists = TelegramIntegrationRepository::exists(&state.db, &chat_id, owner_id).await?;
+        let exists =
+            TelegramIntegrationRepository::exists(&state.db, &chat_id, message_thread_id, owner_id)
+                .await?;
         if exists {
             return Err(AppError::Conflict(
                 "Integration already exists for this chat".to_string(),
@@ -166,18 +229,101 @@ impl IntegrationService {
             telegram_chat_id: chat_id,
             telegram_chat_title: chat_title,
             telegram_chat_type: chat_type,
+            message_thread_id,
         };
 
         TelegramIntegrationRepository::create(&state.db, owner_id, integration).await
     }
 
+    /// Generate a one-time code that links a Telegram chat to `owner_id` via `/link <code>`
+    pub async fn create_telegram_link_code(
+        state: &Arc<AppState>,
+        owner_id: &str,
+        created_by: &str,
+    ) -> AppResult<TelegramLinkCode> {
+        // Opportunistic cleanup; codes are only valid for a few minutes anyway
+        if let Err(e) = TelegramLinkCodeRepository::delete_expired(&state.db).await {
+            tracing::warn!("Failed to delete expired Telegram link codes: {:?}", e);
+        }
+
+        let code = AuthService::generate_random_string(TELEGRAM_LINK_CODE_LENGTH);
+        TelegramLinkCodeRepository::create(
+            &state.db,
+            &code,
+            owner_id,
+            created_by,
+            TELEGRAM_LINK_CODE_TTL_SECONDS,
+        )
+        .await
+    }
+
     /// Update Telegram integration
     pub async fn update_telegram_integration(
         state: &Arc<AppState>,
         integration_id: &str,
         update: UpdateTelegramIntegration,
     ) -> AppResult<crate::db::TelegramIntegration> {
-        TelegramIntegrationRepository::update(&state.db, integration_id, update).await
+        let current = TelegramIntegrationRepository::find_by_id(&state.db, integration_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
+
+        let reenabling = update.is_enabled == Some(true) && !current.is_enabled;
+        if reenabling && current.disabled_reason.is_some() {
+            let lang = Self::owner_lang(state, &current.user_id).await?;
+            Self::send_telegram_test_notification(state, &current, lang.as_deref())
+                .await
+                .map_err(|e| Self::reenable_test_failed(lang.as_deref(), e))?;
+        }
+
+        let updated =
+            TelegramIntegrationRepository::update(&state.db, integration_id, update).await?;
+        if reenabling {
+            TelegramIntegrationRepository::reset_failures(&state.db, integration_id).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Send a test notification to a Telegram integration
+    pub async fn send_telegram_test_notification(
+        state: &Arc<AppState>,
+        integration: &crate::db::TelegramIntegration,
+        lang: Option<&str>,
+    ) -> AppResult<()> {
+        let telegram = state.telegram.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable("Telegram service not available".to_string())
+        })?;
+
+        let title = crate::i18n::tr(lang, "messages.test_notification_title", None);
+        let body = crate::i18n::tr(lang, "messages.test_notification_body", None);
+        let message = format!("<b>{}</b>\n\n{}", title, body);
+
+        telegram
+            .send_message(crate::services::telegram::TelegramMessage {
+                chat_id: integration.telegram_chat_id.clone(),
+                text: message,
+                message_thread_id: integration.message_thread_id,
+                ..Default::default()
+            })
+            .await
+            .map(|_| ())
+    }
+
+    /// Language of an integration owner's messages
+    async fn owner_lang(state: &Arc<AppState>, owner_id: &str) -> AppResult<Option<String>> {
+        Ok(UserRepository::find_by_id(&state.db, owner_id)
+            .await?
+            .and_then(|owner| owner.lang))
+    }
+
+    /// An automatically disabled integration is only re-enabled after a successful test send
+    fn reenable_test_failed(lang: Option<&str>, error: AppError) -> AppError {
+        let err = error.to_string();
+        AppError::Validation(crate::i18n::tr(
+            lang,
+            "validation.reenable_test_failed",
+            Some(&[("err", &err)]),
+        ))
     }
 
     /// Delete Telegram integration
@@ -194,7 +340,202 @@ impl IntegrationService {
         owner_id: &str,
         integration: CreateDiscordIntegration,
     ) -> AppResult<crate::db::DiscordIntegration> {
-        DiscordIntegrationRepository::create(&state.db, owner_id, integration).await
+        let mut created =
+            DiscordIntegrationRepository::create(&state.db, owner_id, integration).await?;
+        Self::record_discord_channel_type(state, &mut created).await;
+
+        // Without a user-supplied webhook, create one that posts as the streamer
+        if created.discord_webhook_url.is_none() {
+            match Self::provision_discord_webhook(state, &created).await {
+                Ok(url) => {
+                    created.discord_webhook_url = Some(url);
+                    created.discord_webhook_managed = true;
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to create webhook for Discord integration {}, falling back to bot sends: {}",
+                    created.id,
+                    e
+                ),
+            }
+        }
+
+        // Make slash commands available in the guild
+        let state = state.clone();
+        let guild_id = created.discord_guild_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                DiscordInteractionService::register_commands_for_guild(&state, &guild_id).await
+            {
+                tracing::warn!(
+                    "Failed to register Discord commands in guild {}: {}",
+                    guild_id,
+                    e
+                );
+            }
+        });
+
+        Ok(created)
+    }
+
+    /// Store the type of the integration's channel so sends can crosspost without
+    /// looking the channel up. Best-effort: without it, sends fall back to the lookup.
+    async fn record_discord_channel_type(
+        state: &Arc<AppState>,
+        integration: &mut DiscordIntegration,
+    ) {
+        let Some(discord) = state.discord.read().await.clone() else {
+            return;
+        };
+
+        let channel_type = match discord.get_channel(&integration.discord_channel_id).await {
+            Ok(channel) => channel.channel_type,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to fetch channel of Discord integration {}: {}",
+                    integration.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        match DiscordIntegrationRepository::set_channel_type(
+            &state.db,
+            &integration.id,
+            channel_type,
+        )
+        .await
+        {
+            Ok(()) => integration.discord_channel_type = Some(channel_type),
+            Err(e) => tracing::warn!(
+                "Failed to store channel type of Discord integration {}: {:?}",
+                integration.id,
+                e
+            ),
+        }
+    }
+
+    /// Create a channel webhook with the owner's Twitch name and avatar and attach it to
+    /// the integration. Returns the webhook URL.
+    pub async fn provision_discord_webhook(
+        state: &Arc<AppState>,
+        integration: &DiscordIntegration,
+    ) -> AppResult<String> {
+        let discord = state.discord.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable(crate::i18n::t(
+                "service_unavailable.discord_service_unavailable",
+            ))
+        })?;
+        let owner = UserRepository::find_by_id(&state.db, &integration.user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.user")))?;
+
+        let avatar = Some(owner.twitch_profile_image_url.as_str()).filter(|u| !u.is_empty());
+        let webhook = discord
+            .create_webhook(
+                &integration.discord_channel_id,
+                &owner.twitch_display_name,
+                avatar,
+            )
+            .await?;
+        let url = webhook
+            .url()
+            .ok_or_else(|| AppError::discord("Created webhook has no token"))?;
+
+        DiscordIntegrationRepository::set_webhook(&state.db, &integration.id, Some(&url), true)
+            .await?;
+
+        tracing::info!(
+            "Created webhook {} for Discord integration {}",
+            webhook.id,
+            integration.id
+        );
+        Ok(url)
+    }
+
+    /// Replace a managed webhook that Discord no longer knows (404 Unknown Webhook).
+    /// Queued retries are moved to the new webhook. Returns the new URL.
+    pub async fn recreate_discord_webhook(
+        state: &Arc<AppState>,
+        integration: &DiscordIntegration,
+    ) -> AppResult<String> {
+        let url = Self::provision_discord_webhook(state, integration).await?;
+
+        if let Some(old_url) = integration.discord_webhook_url.as_deref() {
+            NotificationQueueRepository::replace_webhook_url(&state.db, old_url, &url).await?;
+        }
+
+        Ok(url)
+    }
+
+    /// Delete the integration's webhook if the backend created it (best-effort)
+    async fn remove_managed_discord_webhook(
+        state: &Arc<AppState>,
+        integration: &DiscordIntegration,
+    ) {
+        if !integration.discord_webhook_managed {
+            return;
+        }
+        let (Some(url), Some(discord)) = (
+            integration.discord_webhook_url.as_deref(),
+            state.discord.read().await.clone(),
+        ) else {
+            return;
+        };
+
+        if let Err(e) = discord.delete_webhook(url).await {
+            tracing::warn!(
+                "Failed to delete webhook of Discord integration {}: {}",
+                integration.id,
+                e
+            );
+        }
+    }
+
+    /// Send a test notification to a Discord integration (via its webhook if configured)
+    pub async fn send_discord_test_notification(
+        state: &Arc<AppState>,
+        integration: &DiscordIntegration,
+        lang: Option<&str>,
+    ) -> AppResult<()> {
+        use crate::services::discord::{colors, DiscordEmbed, DiscordMessage, WebhookMessage};
+
+        let discord = state.discord.read().await.clone().ok_or_else(|| {
+            AppError::ServiceUnavailable("Discord service not available".to_string())
+        })?;
+
+        let title = crate::i18n::tr(lang, "messages.test_notification_title", None);
+        let body = crate::i18n::tr(lang, "messages.test_notification_body", None);
+        let embed = DiscordEmbed::new()
+            .title(&title)
+            .description(&body)
+            .color(colors::SUCCESS)
+            .timestamp(chrono::Utc::now().to_rfc3339());
+
+        if let Some(ref webhook_url) = integration.discord_webhook_url {
+            let message = WebhookMessage {
+                content: None,
+                username: Some(crate::i18n::t("app.name")),
+                avatar_url: None,
+                embeds: Some(vec![embed]),
+                allowed_mentions: None,
+            };
+            discord
+                .send_webhook_message(webhook_url, message)
+                .await
+                .map(|_| ())
+        } else {
+            let message = DiscordMessage {
+                content: None,
+                embeds: Some(vec![embed]),
+                tts: None,
+                allowed_mentions: None,
+            };
+            discord
+                .send_message(&integration.discord_channel_id, message)
+                .await
+                .map(|_| ())
+        }
     }
 
     /// Update Discord integration
@@ -203,7 +544,62 @@ impl IntegrationService {
         integration_id: &str,
         update: UpdateDiscordIntegration,
     ) -> AppResult<crate::db::DiscordIntegration> {
-        DiscordIntegrationRepository::update(&state.db, integration_id, update).await
+        let current = DiscordIntegrationRepository::find_by_id(&state.db, integration_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")))?;
+
+        let reenabling = update.is_enabled == Some(true) && !current.is_enabled;
+        if reenabling && current.disabled_reason.is_some() {
+            let lang = Self::owner_lang(state, &current.user_id).await?;
+            Self::send_discord_test_notification(state, &current, lang.as_deref())
+                .await
+                .map_err(|e| Self::reenable_test_failed(lang.as_deref(), e))?;
+        }
+
+        let user_webhook = update
+            .discord_webhook_url
+            .as_ref()
+            .is_some_and(|url| current.discord_webhook_url.as_ref() != Some(url));
+        let mut updated =
+            DiscordIntegrationRepository::update(&state.db, integration_id, update).await?;
+        if reenabling {
+            DiscordIntegrationRepository::reset_failures(&state.db, integration_id).await?;
+        }
+        // Refreshed on every edit: a text channel can be converted to an announcement one
+        Self::record_discord_channel_type(state, &mut updated).await;
+
+        // A managed webhook is bound to its channel: replace it when the channel changes,
+        // and drop it when the user supplies their own webhook
+        let channel_changed = updated.discord_channel_id != current.discord_channel_id;
+        if current.discord_webhook_managed && (channel_changed || user_webhook) {
+            Self::remove_managed_discord_webhook(state, &current).await;
+
+            if user_webhook {
+                DiscordIntegrationRepository::set_webhook(
+                    &state.db,
+                    integration_id,
+                    updated.discord_webhook_url.as_deref(),
+                    false,
+                )
+                .await?;
+            } else {
+                DiscordIntegrationRepository::set_webhook(&state.db, integration_id, None, false)
+                    .await?;
+                if let Err(e) = Self::provision_discord_webhook(state, &updated).await {
+                    tracing::warn!(
+                        "Failed to create webhook for Discord integration {}, falling back to bot sends: {}",
+                        integration_id,
+                        e
+                    );
+                }
+            }
+
+            return DiscordIntegrationRepository::find_by_id(&state.db, integration_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(crate::i18n::t("not_found.integration")));
+        }
+
+        Ok(updated)
     }
 
     /// Delete Discord integration
@@ -211,6 +607,12 @@ impl IntegrationService {
         state: &Arc<AppState>,
         integration_id: &str,
     ) -> AppResult<()> {
+        if let Some(integration) =
+            DiscordIntegrationRepository::find_by_id(&state.db, integration_id).await?
+        {
+            Self::remove_managed_discord_webhook(state, &integration).await;
+        }
+
         DiscordIntegrationRepository::delete(&state.db, integration_id).await
     }
 
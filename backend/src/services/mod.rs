@@ -1,11 +1,21 @@
+pub mod account;
+pub mod api_tokens;
+pub mod audit;
 pub mod auth;
 pub mod calendar;
+pub mod chat_commands;
 pub mod discord;
+pub mod discord_interactions;
+pub mod discord_rate_limit;
 pub mod init;
+pub mod integration_health;
 pub mod integrations;
 pub mod notifications;
+pub mod readiness;
 pub mod settings;
+pub mod share_invites;
 pub mod subscriptions;
 pub mod telegram;
+pub mod telegram_bot;
 pub mod twitch;
 pub mod webhooks;
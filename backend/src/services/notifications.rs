@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::config::NotificationRetryConfig;
 use crate::db::{
     CreateNotificationLog,
     CreateNotificationTask,
     DiscordIntegration,
     DiscordIntegrationRepository,
+    DiscordStreamThreadRepository,
     NotificationLogRepository,
     // Queue/retry types
     NotificationQueueRepository,
@@ -16,8 +19,11 @@ use crate::db::{
     TelegramIntegrationRepository,
     UserRepository,
 };
-use crate::error::AppResult;
-use crate::services::discord::DiscordService;
+use crate::error::{AppResult, DeliveryErrorKind};
+use crate::metrics;
+use crate::services::discord::{DiscordSentMessage, DiscordService};
+use crate::services::integration_health::IntegrationHealthService;
+use crate::services::integrations::IntegrationService;
 use crate::services::telegram::TelegramService;
 use crate::AppState;
 
@@ -101,11 +107,33 @@ pub enum NotificationContent<'a> {
     RewardRedemption(&'a RewardRedemptionData),
 }
 
+impl NotificationContent<'_> {
+    pub fn notification_type(&self) -> NotificationType {
+        match self {
+            NotificationContent::StreamOnline(_) => NotificationType::StreamOnline,
+            NotificationContent::StreamOffline(_) => NotificationType::StreamOffline,
+            NotificationContent::TitleChange(_) => NotificationType::TitleChange,
+            NotificationContent::CategoryChange(_) => NotificationType::CategoryChange,
+            NotificationContent::RewardRedemption(_) => NotificationType::RewardRedemption,
+        }
+    }
+}
+
 /// Abstraction that carries per-integration destination information
 #[derive(Debug, Clone)]
 pub struct IntegrationContext {
     pub destination_id: String,
     pub webhook_url: Option<String>,
+    /// Telegram forum topic (Telegram only)
+    pub message_thread_id: Option<i32>,
+    /// Mention prepended to the message: "here" or a role id (Discord only)
+    pub discord_mention: Option<String>,
+    /// Crosspost when posted to an announcement channel (Discord only)
+    pub discord_auto_publish: bool,
+    /// Type of the channel, if known; saves the lookup before crossposting (Discord only)
+    pub discord_channel_type: Option<u8>,
+    /// Post into this thread instead of the channel (Discord only)
+    pub discord_thread_id: Option<String>,
 }
 
 #[cfg(test)]
@@ -359,6 +387,35 @@ mod tests {
         let should_send_integration = integration_enabled;
         assert!(!should_send_integration);
     }
+
+    #[test]
+    fn test_retry_delay_follows_error_kind() {
+        let cfg = crate::config::Config::default().notification_retry;
+
+        // Exponential backoff for transient errors, capped at max_backoff
+        assert_eq!(
+            retry_delay_seconds(&cfg, 0, DeliveryErrorKind::Transient),
+            30
+        );
+        assert_eq!(
+            retry_delay_seconds(&cfg, 2, DeliveryErrorKind::Transient),
+            120
+        );
+        assert_eq!(
+            retry_delay_seconds(&cfg, 20, DeliveryErrorKind::Transient),
+            cfg.max_backoff_seconds
+        );
+
+        // Rate limits wait exactly as long as requested (rounded up)
+        let rate_limited = DeliveryErrorKind::RateLimited {
+            retry_after: Some(std::time::Duration::from_millis(4200)),
+        };
+        assert_eq!(retry_delay_seconds(&cfg, 3, rate_limited), 5);
+
+        // Unknown retry-after falls back to backoff
+        let rate_limited = DeliveryErrorKind::RateLimited { retry_after: None };
+        assert_eq!(retry_delay_seconds(&cfg, 1, rate_limited), 60);
+    }
 }
 
 /// Renders the notification message from settings template and content.
@@ -428,45 +485,30 @@ fn serialize_notification_content<'a>(content: NotificationContent<'a>) -> (Stri
     }
 }
 
-/// Heuristics to decide whether an error is likely transient and should be retried.
-/// This inspects common HTTP API messages and network error strings.
-fn is_retryable_error(err: Option<&str>, destination_type: &str) -> bool {
-    let e = match err {
-        Some(v) => v.to_lowercase(),
-        None => return false,
-    };
-
-    // Common transient indicators
-    if e.contains("too many requests")
-        || e.contains("429")
-        || e.contains("timeout")
-        || e.contains("timed out")
-        || e.contains("temporarily unavailable")
-        || e.contains("service unavailable")
-        || e.contains("bad gateway")
-        || e.contains("connection reset")
-        || e.contains("failed to send")
+/// Seconds until the next attempt of a task that failed `attempts` times before.
+/// Rate-limited sends wait as long as the API asked; other transient failures back
+/// off exponentially: min(max_backoff, initial_backoff * 2^attempts).
+fn retry_delay_seconds(
+    cfg: &NotificationRetryConfig,
+    attempts: u32,
+    kind: DeliveryErrorKind,
+) -> u64 {
+    if let DeliveryErrorKind::RateLimited {
+        retry_after: Some(retry_after),
+    } = kind
     {
-        return true;
+        // Round up so the retry doesn't land just before the limit resets
+        return retry_after.as_secs() + 1;
     }
 
-    // Try to parse numeric status codes in known message shapes like:
-    // "Discord API error (502): ..." or "Discord webhook error (503): ..."
-    if destination_type == "discord"
-        && (e.contains("discord api error (") || e.contains("discord webhook error ("))
-    {
-        if let Some(open) = e.find('(') {
-            if let Some(close_rel) = e[open + 1..].find(')') {
-                let code_str = &e[open + 1..open + 1 + close_rel];
-                if let Ok(code) = code_str.parse::<u16>() {
-                    return code == 429 || code >= 500;
-                }
-            }
+    let mut delay = cfg.initial_backoff_seconds;
+    for _ in 0..attempts {
+        delay = delay.saturating_mul(2);
+        if delay >= cfg.max_backoff_seconds {
+            break;
         }
     }
-
-    // Default conservative behavior: do not retry
-    false
+    delay.min(cfg.max_backoff_seconds)
 }
 
 #[async_trait]
@@ -489,6 +531,8 @@ pub struct NotificationResult {
     pub destination_id: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Classification of the failure; `None` on success
+    pub error_kind: Option<DeliveryErrorKind>,
 }
 
 /// Service for sending notifications to various channels
@@ -519,6 +563,27 @@ impl NotificationService {
         &self,
         user_id: &str,
         content: NotificationContent<'a>,
+    ) -> AppResult<Vec<NotificationResult>> {
+        self.dispatch(user_id, None, content).await
+    }
+
+    /// Send a notification about a channel the user watches (follow mode) to the user's
+    /// integrations. Works like `send_notification`, but `{url}` points at `channel_login`
+    /// instead of the user's own channel.
+    pub async fn send_watched_channel_notification<'a>(
+        &self,
+        user_id: &str,
+        channel_login: &str,
+        content: NotificationContent<'a>,
+    ) -> AppResult<Vec<NotificationResult>> {
+        self.dispatch(user_id, Some(channel_login), content).await
+    }
+
+    async fn dispatch<'a>(
+        &self,
+        user_id: &str,
+        channel_login: Option<&str>,
+        content: NotificationContent<'a>,
     ) -> AppResult<Vec<NotificationResult>> {
         // Get user settings - used ONLY for message templates, not for blocking notifications
         let settings = NotificationSettingsRepository::get_or_create(&self.pool, user_id).await?;
@@ -526,8 +591,20 @@ impl NotificationService {
             .await?
             .unwrap();
 
+        if user.suspended_at.is_some() {
+            tracing::info!(
+                "User {} is suspended; skipping {} notification",
+                user_id,
+                content.notification_type().as_str()
+            );
+            return Ok(Vec::new());
+        }
+
         // Build a stable stream URL that we can pass to notifiers
-        let stream_url = Some(format!("https://twitch.tv/{}", user.twitch_login));
+        let stream_url = Some(format!(
+            "https://twitch.tv/{}",
+            channel_login.unwrap_or(&user.twitch_login)
+        ));
 
         // Render the message so {game}, {url}, etc. are always substituted
         let message = render_notification_message(&settings, content, stream_url.as_deref());
@@ -535,13 +612,7 @@ impl NotificationService {
         let mut results: Vec<NotificationResult> = Vec::new();
 
         // Determine notification type once (used for logging / queueing)
-        let ntype = match content {
-            NotificationContent::StreamOnline(_) => NotificationType::StreamOnline,
-            NotificationContent::StreamOffline(_) => NotificationType::StreamOffline,
-            NotificationContent::TitleChange(_) => NotificationType::TitleChange,
-            NotificationContent::CategoryChange(_) => NotificationType::CategoryChange,
-            NotificationContent::RewardRedemption(_) => NotificationType::RewardRedemption,
-        };
+        let ntype = content.notification_type();
 
         // Telegram integrations
         let telegram_integrations =
@@ -626,8 +697,7 @@ impl NotificationService {
                     .await;
 
                 // Determine whether this error should be retried.
-                let should_retry =
-                    !res.success && is_retryable_error(res.error.as_deref(), "telegram");
+                let should_retry = res.error_kind.is_some_and(|kind| kind.is_retryable());
 
                 // Create a log entry. If we plan to retry, mark log as 'pending'.
                 let log = self
@@ -644,10 +714,28 @@ impl NotificationService {
                     let ctx = IntegrationContext {
                         destination_id: integration.telegram_chat_id.clone(),
                         webhook_url: None,
+                        message_thread_id: integration.message_thread_id,
+                        discord_mention: None,
+                        discord_auto_publish: false,
+                        discord_channel_type: None,
+                        discord_thread_id: None,
                     };
                     // Enqueue for retries
-                    self.enqueue_retry(&log, "telegram", &ctx, content, &message)
-                        .await?;
+                    self.enqueue_retry(
+                        &log,
+                        "telegram",
+                        &ctx,
+                        content,
+                        &message,
+                        stream_url.as_deref(),
+                        res.error_kind,
+                    )
+                    .await?;
+                }
+
+                if res.success || res.error_kind.is_some() {
+                    self.track_delivery("telegram", &integration.id, res.error_kind)
+                        .await;
                 }
 
                 results.push(res);
@@ -681,6 +769,9 @@ impl NotificationService {
             discord_integrations.len()
         );
 
+        // Stream the notification is about (owner or watched channel)
+        let broadcaster_login = channel_login.unwrap_or(&user.twitch_login);
+
         for integration in discord_integrations {
             let should_send = match content {
                 NotificationContent::StreamOnline(_) => {
@@ -743,18 +834,29 @@ impl NotificationService {
                     integration.id,
                     integration.discord_channel_id
                 );
+                let ctx = IntegrationContext {
+                    destination_id: integration.discord_channel_id.clone(),
+                    webhook_url: integration.discord_webhook_url.clone(),
+                    message_thread_id: None,
+                    discord_mention: integration.mention_for(ntype.as_str()).map(str::to_string),
+                    discord_auto_publish: integration.auto_publish,
+                    discord_channel_type: integration.discord_channel_type,
+                    discord_thread_id: self
+                        .stream_thread_for(&integration, broadcaster_login, content)
+                        .await,
+                };
+
                 let res = self
                     .send_discord_notification(
                         &integration,
-                        &settings,
+                        &ctx,
                         content,
-                        stream_url.as_deref(),
+                        broadcaster_login,
                         &message,
                     )
                     .await;
 
-                let should_retry =
-                    !res.success && is_retryable_error(res.error.as_deref(), "discord");
+                let should_retry = res.error_kind.is_some_and(|kind| kind.is_retryable());
 
                 let log = self
                     .log_notification(
@@ -767,12 +869,24 @@ impl NotificationService {
                     .await?;
 
                 if should_retry {
-                    let ctx = IntegrationContext {
-                        destination_id: integration.discord_channel_id.clone(),
-                        webhook_url: integration.discord_webhook_url.clone(),
-                    };
-                    self.enqueue_retry(&log, "discord", &ctx, content, &message)
-                        .await?;
+                    self.enqueue_retry(
+                        &log,
+                        "discord",
+                        &ctx,
+                        content,
+                        &message,
+                        stream_url.as_deref(),
+                        res.error_kind,
+                    )
+                    .await?;
+                }
+
+                // A deleted stream thread says nothing about the channel itself
+                let thread_gone = ctx.discord_thread_id.is_some()
+                    && res.error_kind == Some(DeliveryErrorKind::TargetGone);
+                if (res.success || res.error_kind.is_some()) && !thread_gone {
+                    self.track_delivery("discord", &integration.id, res.error_kind)
+                        .await;
                 }
 
                 results.push(res);
@@ -794,6 +908,12 @@ impl NotificationService {
                     reason
                 );
             }
+
+            // The broadcast is over: archive its thread even if offline notifications are off
+            if let NotificationContent::StreamOffline(_) = content {
+                self.close_stream_thread(&integration, broadcaster_login)
+                    .await;
+            }
         }
 
         if results.is_empty() {
@@ -839,6 +959,7 @@ impl NotificationService {
                     destination_id: chat_id,
                     success: false,
                     error: Some("Telegram service not initialized".to_string()),
+                    error_kind: None,
                 }
             }
         };
@@ -859,10 +980,16 @@ impl NotificationService {
         let ctx = IntegrationContext {
             destination_id: chat_id.clone(),
             webhook_url: None,
+            message_thread_id: integration.message_thread_id,
+            discord_mention: None,
+            discord_auto_publish: false,
+            discord_channel_type: None,
+            discord_thread_id: None,
         };
 
         // Convert borrowed params into owned types expected by the Notifier trait
         let owned_stream_url = stream_url.map(|s| s.to_string());
+        let started = Instant::now();
         let send_result = telegram
             .send_notification(
                 &ctx,
@@ -872,6 +999,7 @@ impl NotificationService {
                 message.to_string(),
             )
             .await;
+        metrics::record_send("telegram", send_result.is_ok(), started.elapsed());
 
         match send_result {
             Ok(opt_message_id) => {
@@ -895,6 +1023,7 @@ impl NotificationService {
                     destination_id: chat_id,
                     success: true,
                     error: None,
+                    error_kind: None,
                 }
             }
             Err(e) => NotificationResult {
@@ -902,21 +1031,22 @@ impl NotificationService {
                 destination_id: chat_id,
                 success: false,
                 error: Some(e.to_string()),
+                error_kind: Some(e.delivery_kind()),
             },
         }
     }
 
-    /// Send a notification via Discord for any notification content (uses embeds)
+    /// Send a notification via Discord. For stream.online, a thread is started from the
+    /// message when the integration has `stream_thread_enabled`.
     async fn send_discord_notification<'a>(
         &self,
         integration: &DiscordIntegration,
-        settings: &NotificationSettings,
+        ctx: &IntegrationContext,
         content: NotificationContent<'a>,
-        stream_url: Option<&'a str>,
+        broadcaster_login: &str,
         message: &str,
     ) -> NotificationResult {
         let channel_id = integration.discord_channel_id.clone();
-        let webhook = integration.discord_webhook_url.clone();
 
         // Clone the Option<DiscordService> out of the RwLock guard so we can own it
         let discord_opt = self.discord.read().await.clone();
@@ -928,43 +1058,246 @@ impl NotificationService {
                     destination_id: channel_id,
                     success: false,
                     error: Some("Discord service not initialized".to_string()),
+                    error_kind: None,
                 }
             }
         };
 
-        let ctx = IntegrationContext {
-            destination_id: channel_id.clone(),
-            webhook_url: webhook.clone(),
-        };
-
-        // Convert borrowed params into owned types expected by the Notifier trait
-        let owned_stream_url = stream_url.map(|s| s.to_string());
-        let send_result = discord
-            .send_notification(
-                &ctx,
-                content,
-                settings,
-                owned_stream_url,
-                message.to_string(),
-            )
+        let started = Instant::now();
+        let send_result = self
+            .send_discord_message(&discord, Some(integration), ctx, message)
             .await;
 
+        metrics::record_send("discord", send_result.is_ok(), started.elapsed());
+
         match send_result {
-            Ok(_) => NotificationResult {
-                destination_type: "discord".to_string(),
-                destination_id: channel_id,
-                success: true,
-                error: None,
-            },
+            Ok(sent) => {
+                if let NotificationContent::StreamOnline(data) = content {
+                    if integration.stream_thread_enabled {
+                        self.open_stream_thread(
+                            &discord,
+                            integration,
+                            broadcaster_login,
+                            data,
+                            &sent,
+                        )
+                        .await;
+                    }
+                }
+                NotificationResult {
+                    destination_type: "discord".to_string(),
+                    destination_id: channel_id,
+                    success: true,
+                    error: None,
+                    error_kind: None,
+                }
+            }
             Err(e) => NotificationResult {
                 destination_type: "discord".to_string(),
                 destination_id: channel_id,
                 success: false,
                 error: Some(e.to_string()),
+                error_kind: Some(e.delivery_kind()),
             },
         }
     }
 
+    /// Send a Discord message for an integration. A managed webhook deleted on the
+    /// Discord side is recreated and the send retried once.
+    async fn send_discord_message(
+        &self,
+        discord: &DiscordService,
+        integration: Option<&DiscordIntegration>,
+        ctx: &IntegrationContext,
+        message: &str,
+    ) -> AppResult<DiscordSentMessage> {
+        let result = discord.send_text_notification(ctx, message).await;
+
+        let (Err(e), Some(integration)) = (&result, integration) else {
+            return result;
+        };
+        if !integration.discord_webhook_managed || !DiscordService::is_unknown_webhook(e) {
+            return result;
+        }
+
+        tracing::warn!(
+            "Webhook of Discord integration {} is gone, recreating it",
+            integration.id
+        );
+        match IntegrationService::recreate_discord_webhook(&self.state, integration).await {
+            Ok(url) => {
+                let ctx = IntegrationContext {
+                    webhook_url: Some(url),
+                    ..ctx.clone()
+                };
+                discord.send_text_notification(&ctx, message).await
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to recreate webhook of Discord integration {}: {}",
+                    integration.id,
+                    e
+                );
+                result
+            }
+        }
+    }
+
+    /// Integration a queued Discord task was created for: same owner and channel,
+    /// preferring the one whose webhook the task posts through
+    async fn discord_integration_for_task(
+        &self,
+        task: &NotificationTask,
+    ) -> Option<DiscordIntegration> {
+        let found = match DiscordIntegrationRepository::find_by_channel_id(
+            &self.pool,
+            &task.destination_id,
+        )
+        .await
+        {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to resolve Discord integration of queued notification {}: {:?}",
+                    task.id,
+                    e
+                );
+                return None;
+            }
+        };
+
+        let mut owned: Vec<DiscordIntegration> = found
+            .into_iter()
+            .filter(|i| i.user_id == task.user_id)
+            .collect();
+        let index = owned
+            .iter()
+            .position(|i| i.discord_webhook_url == task.webhook_url)
+            .unwrap_or(0);
+        (!owned.is_empty()).then(|| owned.swap_remove(index))
+    }
+
+    /// Thread of the current broadcast for follow-up notifications (title, category, rewards)
+    async fn stream_thread_for<'a>(
+        &self,
+        integration: &DiscordIntegration,
+        broadcaster_login: &str,
+        content: NotificationContent<'a>,
+    ) -> Option<String> {
+        if !integration.stream_thread_enabled
+            || matches!(
+                content,
+                NotificationContent::StreamOnline(_) | NotificationContent::StreamOffline(_)
+            )
+        {
+            return None;
+        }
+
+        match DiscordStreamThreadRepository::find(&self.pool, &integration.id, broadcaster_login)
+            .await
+        {
+            Ok(found) => found.and_then(|t| t.thread_id),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load stream thread for Discord integration {}: {}",
+                    integration.id,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Start a thread named after the stream title from the stream.online message and
+    /// remember it for the rest of the broadcast
+    async fn open_stream_thread(
+        &self,
+        discord: &DiscordService,
+        integration: &DiscordIntegration,
+        broadcaster_login: &str,
+        data: &StreamOnlineData,
+        sent: &DiscordSentMessage,
+    ) {
+        let name = if data.title.trim().is_empty() {
+            data.streamer_name.as_str()
+        } else {
+            data.title.as_str()
+        };
+
+        let thread_id = match discord
+            .start_thread_from_message(&sent.channel_id, &sent.id, name)
+            .await
+        {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to start stream thread for Discord integration {}: {}",
+                    integration.id,
+                    e
+                );
+                None
+            }
+        };
+
+        if let Err(e) = DiscordStreamThreadRepository::upsert(
+            &self.pool,
+            &integration.id,
+            broadcaster_login,
+            &sent.channel_id,
+            &sent.id,
+            thread_id.as_deref(),
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to save stream thread for Discord integration {}: {}",
+                integration.id,
+                e
+            );
+        }
+    }
+
+    /// Archive the thread of the finished broadcast and forget it
+    async fn close_stream_thread(&self, integration: &DiscordIntegration, broadcaster_login: &str) {
+        let thread = match DiscordStreamThreadRepository::find(
+            &self.pool,
+            &integration.id,
+            broadcaster_login,
+        )
+        .await
+        {
+            Ok(Some(thread)) => thread,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load stream thread for Discord integration {}: {}",
+                    integration.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Some(thread_id) = thread.thread_id.as_deref() {
+            if let Some(discord) = self.discord.read().await.clone() {
+                if let Err(e) = discord.archive_thread(thread_id).await {
+                    tracing::warn!("Failed to archive Discord thread {}: {}", thread_id, e);
+                }
+            }
+        }
+
+        if let Err(e) =
+            DiscordStreamThreadRepository::delete(&self.pool, &integration.id, broadcaster_login)
+                .await
+        {
+            tracing::warn!(
+                "Failed to delete stream thread for Discord integration {}: {}",
+                integration.id,
+                e
+            );
+        }
+    }
+
     async fn log_notification(
         &self,
         user_id: &str,
@@ -995,7 +1328,92 @@ impl NotificationService {
         Ok(created)
     }
 
+    /// Update the failure streak of an integration after a send (`None` = delivered)
+    async fn track_delivery(
+        &self,
+        destination_type: &str,
+        integration_id: &str,
+        error_kind: Option<DeliveryErrorKind>,
+    ) {
+        let result = match error_kind {
+            None => {
+                IntegrationHealthService::record_success(
+                    &self.state,
+                    destination_type,
+                    integration_id,
+                )
+                .await
+            }
+            Some(kind) => {
+                IntegrationHealthService::record_failure(
+                    &self.state,
+                    destination_type,
+                    integration_id,
+                    kind,
+                )
+                .await
+            }
+        };
+
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to track delivery of {} integration {}: {:?}",
+                destination_type,
+                integration_id,
+                e
+            );
+        }
+    }
+
+    /// Update the failure streaks of the owner's integrations a queued task went to
+    async fn track_delivery_for_task(
+        &self,
+        task: &NotificationTask,
+        error_kind: Option<DeliveryErrorKind>,
+    ) {
+        let integration_ids = match task.destination_type.as_str() {
+            "telegram" => {
+                TelegramIntegrationRepository::find_by_chat_id(&self.pool, &task.destination_id)
+                    .await
+                    .map(|found| {
+                        found
+                            .into_iter()
+                            .filter(|i| i.user_id == task.user_id)
+                            .map(|i| i.id)
+                            .collect::<Vec<_>>()
+                    })
+            }
+            "discord" => {
+                DiscordIntegrationRepository::find_by_channel_id(&self.pool, &task.destination_id)
+                    .await
+                    .map(|found| {
+                        found
+                            .into_iter()
+                            .filter(|i| i.user_id == task.user_id)
+                            .map(|i| i.id)
+                            .collect::<Vec<_>>()
+                    })
+            }
+            _ => return,
+        };
+
+        match integration_ids {
+            Ok(ids) => {
+                for id in ids {
+                    self.track_delivery(&task.destination_type, &id, error_kind)
+                        .await;
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to resolve integrations of queued notification {}: {:?}",
+                task.id,
+                e
+            ),
+        }
+    }
+
     /// Enqueue a failed notification for background retry processing.
+    #[allow(clippy::too_many_arguments)]
     async fn enqueue_retry<'a>(
         &self,
         log: &crate::db::NotificationLog,
@@ -1003,13 +1421,15 @@ impl NotificationService {
         ctx: &IntegrationContext,
         content: NotificationContent<'a>,
         message: &str,
+        stream_url: Option<&str>,
+        error_kind: Option<DeliveryErrorKind>,
     ) -> AppResult<()> {
         // Serialize specific payload & choose initial schedule based on config
         let (notification_type, content_json) = serialize_notification_content(content);
 
         let cfg = &self.state.config.notification_retry;
-        let initial_secs = cfg.initial_backoff_seconds as i64;
-        let next_attempt_at = Utc::now().naive_utc() + chrono::Duration::seconds(initial_secs);
+        let delay = retry_delay_seconds(cfg, 0, error_kind.unwrap_or(DeliveryErrorKind::Transient));
+        let next_attempt_at = Utc::now().naive_utc() + chrono::Duration::seconds(delay as i64);
 
         // Determine expiration/TTL based on notification type to avoid retrying stale events
         let expires_in_secs = match notification_type.as_str() {
@@ -1031,6 +1451,11 @@ impl NotificationService {
             destination_type: destination_type.to_string(),
             destination_id: ctx.destination_id.clone(),
             webhook_url: ctx.webhook_url.clone(),
+            stream_url: stream_url.map(|s| s.to_string()),
+            message_thread_id: ctx.message_thread_id,
+            discord_mention: ctx.discord_mention.clone(),
+            discord_auto_publish: ctx.discord_auto_publish,
+            discord_thread_id: ctx.discord_thread_id.clone(),
             max_attempts: Some(cfg.max_attempts as i32),
             next_attempt_at: Some(next_attempt_at),
             expires_at: Some(expires_at),
@@ -1219,10 +1644,44 @@ impl NotificationService {
             }
         };
 
-        let stream_url = Some(format!("https://twitch.tv/{}", user.twitch_login));
+        // Retries of a suspended account are dropped, not delivered later
+        if user.suspended_at.is_some() {
+            tracing::info!(
+                "User {} of notification task {} is suspended; moving to DLQ",
+                task.user_id,
+                task.id
+            );
+            let _ = NotificationQueueRepository::mark_dead(
+                &self.pool,
+                &task.id,
+                Some("account suspended".to_string()),
+            )
+            .await;
+            if let Some(ref log_id) = task.notification_log_id {
+                let _ = NotificationLogRepository::update_status(
+                    &self.pool,
+                    log_id,
+                    "failed",
+                    Some("Account suspended"),
+                )
+                .await;
+            }
+            return Ok(());
+        }
+
+        let stream_url = task
+            .stream_url
+            .clone()
+            .or_else(|| Some(format!("https://twitch.tv/{}", user.twitch_login)));
         let ctx = IntegrationContext {
             destination_id: task.destination_id.clone(),
             webhook_url: task.webhook_url.clone(),
+            message_thread_id: task.message_thread_id,
+            discord_mention: task.discord_mention.clone(),
+            discord_auto_publish: task.discord_auto_publish,
+            // Known once the Discord integration is resolved below
+            discord_channel_type: None,
+            discord_thread_id: task.discord_thread_id.clone(),
         };
 
         // Re-render message from template so {game}, {url}, etc. are always substituted (avoids stale or partial placeholder in task.message).
@@ -1234,6 +1693,7 @@ impl NotificationService {
         )?;
 
         // Attempt sending via the appropriate service.
+        let started = Instant::now();
         let send_result: Result<Option<i32>, crate::error::AppError> = match task
             .destination_type
             .as_str()
@@ -1351,73 +1811,43 @@ impl NotificationService {
                     }
                 };
 
+                let integration = self.discord_integration_for_task(&task).await;
+                let ctx = IntegrationContext {
+                    discord_channel_type: integration.as_ref().and_then(|i| i.discord_channel_type),
+                    ..ctx
+                };
                 match task.notification_type.as_str() {
                     "stream_online" => {
                         let data: StreamOnlineData = serde_json::from_str(&task.content_json)
                             .map_err(|e| crate::error::AppError::Internal(anyhow::anyhow!(e)))?;
-                        discord
-                            .send_notification(
-                                &ctx,
-                                NotificationContent::StreamOnline(&data),
-                                &settings,
-                                stream_url,
-                                message.clone(),
-                            )
-                            .await
-                    }
-                    "stream_offline" => {
-                        let data: StreamOfflineData = serde_json::from_str(&task.content_json)
-                            .map_err(|e| crate::error::AppError::Internal(anyhow::anyhow!(e)))?;
-                        discord
-                            .send_notification(
-                                &ctx,
-                                NotificationContent::StreamOffline(&data),
-                                &settings,
-                                stream_url,
-                                message.clone(),
-                            )
-                            .await
-                    }
-                    "title_change" => {
-                        let data: TitleChangeData = serde_json::from_str(&task.content_json)
-                            .map_err(|e| crate::error::AppError::Internal(anyhow::anyhow!(e)))?;
-                        discord
-                            .send_notification(
-                                &ctx,
-                                NotificationContent::TitleChange(&data),
-                                &settings,
-                                stream_url,
-                                message.clone(),
-                            )
-                            .await
-                    }
-                    "category_change" => {
-                        let data: CategoryChangeData = serde_json::from_str(&task.content_json)
-                            .map_err(|e| crate::error::AppError::Internal(anyhow::anyhow!(e)))?;
-                        discord
-                            .send_notification(
-                                &ctx,
-                                NotificationContent::CategoryChange(&data),
-                                &settings,
-                                stream_url,
-                                message.clone(),
-                            )
-                            .await
+                        let sent = self
+                            .send_discord_message(&discord, integration.as_ref(), &ctx, &message)
+                            .await;
+
+                        // Start the stream thread as a direct send would; the stream URL
+                        // names the broadcaster the thread is keyed by
+                        if let (Ok(sent), Some(integration)) = (&sent, &integration) {
+                            if integration.stream_thread_enabled {
+                                let broadcaster_login = stream_url
+                                    .as_deref()
+                                    .and_then(|url| url.strip_prefix("https://twitch.tv/"))
+                                    .unwrap_or(&user.twitch_login);
+                                self.open_stream_thread(
+                                    &discord,
+                                    integration,
+                                    broadcaster_login,
+                                    &data,
+                                    sent,
+                                )
+                                .await;
+                            }
+                        }
+                        sent.map(|_| None)
                     }
-                    "reward_redemption" => {
-                        let data: RewardRedemptionData = serde_json::from_str(&task.content_json)
-                            .map_err(|e| {
-                            crate::error::AppError::Internal(anyhow::anyhow!(e))
-                        })?;
-                        discord
-                            .send_notification(
-                                &ctx,
-                                NotificationContent::RewardRedemption(&data),
-                                &settings,
-                                stream_url,
-                                message.clone(),
-                            )
+                    "stream_offline" | "title_change" | "category_change" | "reward_redemption" => {
+                        self.send_discord_message(&discord, integration.as_ref(), &ctx, &message)
                             .await
+                            .map(|_| None)
                     }
                     _ => Err(crate::error::AppError::BadRequest(
                         "Unknown notification type".to_string(),
@@ -1443,6 +1873,12 @@ impl NotificationService {
             }
         };
 
+        metrics::record_send(
+            &task.destination_type,
+            send_result.is_ok(),
+            started.elapsed(),
+        );
+
         // Handle send result
         match send_result {
             Ok(_) => {
@@ -1454,13 +1890,15 @@ impl NotificationService {
                             .await;
                 }
                 tracing::info!("Queued notification {} sent successfully", task.id);
+                self.track_delivery_for_task(&task, None).await;
                 Ok(())
             }
             Err(e) => {
                 let err_str = e.to_string();
+                let kind = e.delivery_kind();
 
                 // Permanent errors -> move to DLQ
-                if !is_retryable_error(Some(&err_str), &task.destination_type) {
+                if !kind.is_retryable() {
                     let _ = NotificationQueueRepository::mark_dead(
                         &self.pool,
                         &task.id,
@@ -1477,26 +1915,16 @@ impl NotificationService {
                         .await;
                     }
                     tracing::warn!("Queued notification {} moved to DLQ: {}", task.id, err_str);
+                    if !(kind == DeliveryErrorKind::TargetGone && task.discord_thread_id.is_some())
+                    {
+                        self.track_delivery_for_task(&task, Some(kind)).await;
+                    }
                     return Ok(());
                 }
 
-                // Transient error -> schedule retry with exponential backoff
+                // Transient error -> wait out the rate limit or back off exponentially
                 let cfg = &self.state.config.notification_retry;
-                let attempts = task.attempts as u32;
-
-                // Compute delay = min(max_backoff, initial_backoff * 2^attempts)
-                let mut delay: u128 = cfg.initial_backoff_seconds as u128;
-                for _ in 0..attempts {
-                    delay = delay.saturating_mul(2);
-                    if delay as u64 >= cfg.max_backoff_seconds {
-                        delay = cfg.max_backoff_seconds as u128;
-                        break;
-                    }
-                }
-                if delay as u64 > cfg.max_backoff_seconds {
-                    delay = cfg.max_backoff_seconds as u128;
-                }
-
+                let delay = retry_delay_seconds(cfg, task.attempts as u32, kind);
                 let next = now + chrono::Duration::seconds(delay as i64);
 
                 match NotificationQueueRepository::register_attempt_and_schedule(
@@ -0,0 +1,237 @@
+//! Readiness checks behind `GET /health/ready`.
+//!
+//! Unlike `/health`, which only shows the process is up, readiness covers the
+//! dependencies the service needs to do its job: the database and its schema, the
+//! configured bots, the Twitch app access token and the background workers.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::metrics;
+use crate::services::init::{
+    MIGRATOR, WORKER_CALENDAR_SYNC, WORKER_EVENTSUB_SYNC, WORKER_INTEGRATION_HEALTH,
+    WORKER_NOTIFICATION_RETRY, WORKER_TOKEN_REFRESH,
+};
+use crate::AppState;
+
+/// Longest wait for the database to answer
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Not configured, so not required
+    Disabled,
+    Failing,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentCheck {
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl ComponentCheck {
+    fn ok() -> Self {
+        Self {
+            status: CheckStatus::Ok,
+            details: None,
+        }
+    }
+
+    fn disabled() -> Self {
+        Self {
+            status: CheckStatus::Disabled,
+            details: None,
+        }
+    }
+
+    fn failing(details: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Failing,
+            details: Some(details.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub components: BTreeMap<&'static str, ComponentCheck>,
+    pub workers: BTreeMap<&'static str, ComponentCheck>,
+}
+
+impl ReadinessReport {
+    pub fn is_ready(&self) -> bool {
+        self.components
+            .values()
+            .chain(self.workers.values())
+            .all(|c| c.status != CheckStatus::Failing)
+    }
+}
+
+pub struct ReadinessService;
+
+impl ReadinessService {
+    pub async fn check(state: &Arc<AppState>) -> ReadinessReport {
+        let mut components = BTreeMap::new();
+
+        let database = Self::check_database(&state.db).await;
+        let migrations = if database.status == CheckStatus::Ok {
+            Self::check_migrations(&state.db).await
+        } else {
+            ComponentCheck::failing("database unavailable")
+        };
+        components.insert("database", database);
+        components.insert("migrations", migrations);
+
+        let telegram = if state.config.telegram.bot_token.is_none() {
+            ComponentCheck::disabled()
+        } else if state.telegram.read().await.is_some() {
+            ComponentCheck::ok()
+        } else {
+            ComponentCheck::failing("Telegram bot is configured but not initialized")
+        };
+        components.insert("telegram", telegram);
+
+        let discord = if state.config.discord.bot_token.is_none() {
+            ComponentCheck::disabled()
+        } else if state.discord.read().await.is_some() {
+            ComponentCheck::ok()
+        } else {
+            ComponentCheck::failing("Discord bot is configured but not initialized")
+        };
+        components.insert("discord", discord);
+
+        let app_token = match state.twitch.app_access_token_expires_at().await {
+            Some(expires_at) if expires_at > Utc::now() => ComponentCheck::ok(),
+            Some(expires_at) => ComponentCheck::failing(format!("expired at {}", expires_at)),
+            None => ComponentCheck::failing("no app access token"),
+        };
+        components.insert("twitch_app_token", app_token);
+
+        let now = Utc::now().timestamp();
+        let workers = Self::heartbeat_limits(state)
+            .into_iter()
+            .map(|(worker, limit)| {
+                let check = check_heartbeat(metrics::last_worker_heartbeat(worker), now, limit);
+                (worker, check)
+            })
+            .collect();
+
+        ReadinessReport {
+            components,
+            workers,
+        }
+    }
+
+    async fn check_database(pool: &SqlitePool) -> ComponentCheck {
+        match tokio::time::timeout(DATABASE_TIMEOUT, sqlx::query("SELECT 1").execute(pool)).await {
+            Ok(Ok(_)) => ComponentCheck::ok(),
+            Ok(Err(e)) => ComponentCheck::failing(e.to_string()),
+            Err(_) => {
+                ComponentCheck::failing(format!("no answer within {}s", DATABASE_TIMEOUT.as_secs()))
+            }
+        }
+    }
+
+    /// Every embedded migration must have been applied successfully
+    async fn check_migrations(pool: &SqlitePool) -> ComponentCheck {
+        let applied: Vec<i64> =
+            match sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+                .fetch_all(pool)
+                .await
+            {
+                Ok(versions) => versions,
+                Err(e) => return ComponentCheck::failing(e.to_string()),
+            };
+        let applied: HashSet<i64> = applied.into_iter().collect();
+
+        let pending: Vec<String> = MIGRATOR
+            .iter()
+            .filter(|m| !applied.contains(&m.version))
+            .map(|m| m.version.to_string())
+            .collect();
+
+        if pending.is_empty() {
+            ComponentCheck::ok()
+        } else {
+            ComponentCheck::failing(format!("pending migrations: {}", pending.join(", ")))
+        }
+    }
+
+    /// How long each worker may go without a heartbeat: its interval between
+    /// iterations plus room for a slow iteration
+    fn heartbeat_limits(state: &Arc<AppState>) -> [(&'static str, i64); 5] {
+        let retry_poll = state
+            .config
+            .notification_retry
+            .poll_interval_seconds
+            .max(60) as i64;
+
+        [
+            (WORKER_EVENTSUB_SYNC, 2 * 60 * 60),
+            (WORKER_CALENDAR_SYNC, 2 * 60 * 60),
+            (WORKER_TOKEN_REFRESH, 60 * 60),
+            (WORKER_NOTIFICATION_RETRY, 3 * retry_poll),
+            (WORKER_INTEGRATION_HEALTH, 26 * 60 * 60),
+        ]
+    }
+}
+
+fn check_heartbeat(last: Option<i64>, now: i64, limit_secs: i64) -> ComponentCheck {
+    match last {
+        Some(last) if now - last <= limit_secs => ComponentCheck::ok(),
+        Some(last) => ComponentCheck::failing(format!("last heartbeat {}s ago", now - last)),
+        None => ComponentCheck::failing("no heartbeat yet"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[test]
+    fn test_heartbeat_must_be_within_limit() {
+        assert_eq!(
+            check_heartbeat(Some(1_000), 1_060, 60).status,
+            CheckStatus::Ok
+        );
+        assert_eq!(
+            check_heartbeat(Some(1_000), 1_061, 60).status,
+            CheckStatus::Failing
+        );
+        assert_eq!(
+            check_heartbeat(None, 1_000, 60).status,
+            CheckStatus::Failing
+        );
+    }
+
+    #[tokio::test]
+    async fn migrations_check_requires_all_migrations() -> anyhow::Result<()> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        assert_eq!(
+            ReadinessService::check_migrations(&pool).await.status,
+            CheckStatus::Failing
+        );
+
+        MIGRATOR.run(&pool).await?;
+        assert_eq!(
+            ReadinessService::check_migrations(&pool).await.status,
+            CheckStatus::Ok
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::db::{
+    AcceptedShareInvite, SettingsShare, ShareInvite, ShareInviteRepository, ShareRole, User,
+};
+use crate::error::{AppError, AppResult};
+use crate::i18n::t;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_CREATE, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE,
+    AUDIT_ENTITY_SHARE, AUDIT_ENTITY_SHARE_INVITE,
+};
+use crate::services::auth::AuthService;
+use crate::AppState;
+
+/// Length of invite tokens
+const INVITE_TOKEN_LENGTH: usize = 32;
+/// Invite lifetime when the request does not set one
+pub const DEFAULT_INVITE_TTL_HOURS: i64 = 72;
+/// Longest allowed invite lifetime
+pub const MAX_INVITE_TTL_HOURS: i64 = 30 * 24;
+
+pub struct ShareInviteService;
+
+impl ShareInviteService {
+    /// Create an invite to `owner_id`'s settings with the given role.
+    /// Returns the invite and its token; only the token's hash is stored.
+    pub async fn create_invite(
+        state: &Arc<AppState>,
+        owner_id: &str,
+        created_by: &str,
+        role: ShareRole,
+        ttl_hours: i64,
+    ) -> AppResult<(ShareInvite, String)> {
+        if !(1..=MAX_INVITE_TTL_HOURS).contains(&ttl_hours) {
+            return Err(AppError::Validation(t("validation.invite_ttl_invalid")));
+        }
+
+        // Opportunistic cleanup of invites nobody accepted in time
+        if let Err(e) = ShareInviteRepository::delete_expired(&state.db).await {
+            tracing::warn!("Failed to delete expired share invites: {:?}", e);
+        }
+
+        let token = AuthService::generate_random_string(INVITE_TOKEN_LENGTH);
+        let expires_at = (Utc::now() + Duration::hours(ttl_hours)).naive_utc();
+        let invite = ShareInviteRepository::create(
+            &state.db,
+            &Uuid::new_v4().to_string(),
+            &AuthService::hash_token(&token),
+            owner_id,
+            created_by,
+            role,
+            expires_at,
+        )
+        .await?;
+
+        AuditService::record(
+            state,
+            created_by,
+            owner_id,
+            AUDIT_ENTITY_SHARE_INVITE,
+            Some(&invite.id),
+            AUDIT_ACTION_CREATE,
+            serde_json::json!({ "role": role, "expires_at": invite.expires_at }),
+        )
+        .await;
+
+        Ok((invite, token))
+    }
+
+    /// Link that starts the Twitch login with the invite attached
+    pub fn invite_url(state: &Arc<AppState>, token: &str) -> String {
+        format!(
+            "{}/api/auth/login?invite={}",
+            state.config.server.webhook_url.trim_end_matches('/'),
+            urlencoding::encode(token)
+        )
+    }
+
+    pub async fn revoke_invite(
+        state: &Arc<AppState>,
+        actor_id: &str,
+        owner_id: &str,
+        invite_id: &str,
+    ) -> AppResult<()> {
+        let invite = ShareInviteRepository::delete_for_owner(&state.db, invite_id, owner_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(t("not_found.share_invite")))?;
+
+        AuditService::record(
+            state,
+            actor_id,
+            owner_id,
+            AUDIT_ENTITY_SHARE_INVITE,
+            Some(&invite.id),
+            AUDIT_ACTION_DELETE,
+            serde_json::json!({ "role": invite.role }),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Accept an invite for a user who just logged in. Creates the share, or raises the
+    /// role of an existing one. Returns `None` if the invite is unknown, expired, already
+    /// used, or the user's own.
+    pub async fn accept_invite(
+        state: &Arc<AppState>,
+        token: &str,
+        invitee: &User,
+    ) -> AppResult<Option<SettingsShare>> {
+        let Some(AcceptedShareInvite {
+            invite,
+            share,
+            previous_role,
+        }) = ShareInviteRepository::accept(&state.db, &AuthService::hash_token(token), &invitee.id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // The share is granted by whoever created the invite
+        let action = match previous_role {
+            None => Some(AUDIT_ACTION_CREATE),
+            Some(role) if role != share.role => Some(AUDIT_ACTION_UPDATE),
+            Some(_) => None,
+        };
+        if let Some(action) = action {
+            AuditService::record(
+                state,
+                &invite.created_by,
+                &invite.owner_user_id,
+                AUDIT_ENTITY_SHARE,
+                Some(&invitee.id),
+                action,
+                serde_json::json!({
+                    "grantee_login": invitee.twitch_login,
+                    "role": share.role,
+                    "previous_role": previous_role,
+                    "invite_id": invite.id,
+                }),
+            )
+            .await;
+        }
+
+        tracing::info!(
+            "User {} accepted share invite {} from owner {}",
+            invitee.id,
+            invite.id,
+            invite.owner_user_id
+        );
+
+        Ok(Some(share))
+    }
+}
@@ -5,13 +5,20 @@ use chrono::Utc;
 use serde_json::Value;
 use tracing::{info, warn};
 
-use crate::db::{CreateEventSubSubscription, EventSubSubscriptionRepository};
+use crate::db::{
+    CreateEventSubSubscription, EventSubSubscriptionRepository, UserRepository,
+    WatchedChannelRepository, WatchedChannelSubscriptionRepository,
+};
 use crate::error::AppResult;
 use crate::AppState;
 
 // Alias the Twitch service EventSub type to avoid name collisions with DB models
 use crate::services::twitch::EventSubSubscription as TwitchEventSub;
 
+/// Event types subscribed for watched channels (follow mode). None of them require
+/// broadcaster authorization, so the app access token is sufficient.
+const WATCHED_SUBSCRIPTION_TYPES: [&str; 3] = ["stream.online", "stream.offline", "channel.update"];
+
 pub struct SubscriptionManager;
 
 impl SubscriptionManager {
@@ -402,6 +409,220 @@ impl SubscriptionManager {
 
         Ok(())
     }
+
+    /// Delete all EventSub subscriptions of a user (from Twitch + DB), e.g. when the
+    /// account is deleted. Failures on Twitch are logged and the DB row is kept.
+    pub async fn remove_for_user(state: &Arc<AppState>, user_id: &str) -> AppResult<()> {
+        let subs = EventSubSubscriptionRepository::find_by_user_id(&state.db, user_id).await?;
+
+        for sub in subs {
+            if let Err(e) = state
+                .twitch
+                .delete_eventsub_subscription(&sub.twitch_subscription_id)
+                .await
+            {
+                warn!(
+                    "Failed to delete EventSub {} (twitch_id={}) for user {} on Twitch: {}",
+                    sub.subscription_type, sub.twitch_subscription_id, user_id, e
+                );
+                continue;
+            }
+
+            EventSubSubscriptionRepository::delete(&state.db, &sub.id).await?;
+        }
+
+        info!("Removed EventSub subscriptions for user {}", user_id);
+
+        Ok(())
+    }
+
+    /// Ensure app-token EventSub subscriptions exist for every watched channel.
+    ///
+    /// - Creates stream.online / stream.offline / channel.update for watched broadcasters.
+    /// - Removes subscriptions for broadcasters nobody watches anymore.
+    ///
+    /// Broadcasters that are registered users are skipped, and their subscriptions from before
+    /// they registered removed: their own subscriptions (managed by `sync_for_user`) already
+    /// deliver the events, and the webhook handler fans them out to watchers.
+    pub async fn sync_watched_channels(state: &Arc<AppState>) -> AppResult<()> {
+        let watched: HashSet<String> = WatchedChannelRepository::list_broadcaster_ids(&state.db)
+            .await?
+            .into_iter()
+            .collect();
+        let existing = WatchedChannelSubscriptionRepository::list_all(&state.db).await?;
+        let registered: HashSet<String> =
+            WatchedChannelSubscriptionRepository::list_registered_broadcaster_ids(&state.db)
+                .await?
+                .into_iter()
+                .collect();
+
+        info!(
+            "Syncing watched channel subscriptions: {} broadcaster(s), {} existing subscription(s)",
+            watched.len(),
+            existing.len()
+        );
+
+        // 1) Remove subscriptions for broadcasters that are no longer watched, or that have
+        //    registered since (their events would otherwise reach watchers twice)
+        for sub in &existing {
+            if watched.contains(&sub.broadcaster_id) && !registered.contains(&sub.broadcaster_id) {
+                continue;
+            }
+
+            // The subscription may also be recorded for a registered owner (discovered
+            // during their own sync); in that case keep it on Twitch.
+            let owned = EventSubSubscriptionRepository::find_by_twitch_subscription_id(
+                &state.db,
+                &sub.twitch_subscription_id,
+            )
+            .await?
+            .is_some();
+
+            if !owned {
+                if let Err(e) = state
+                    .twitch
+                    .delete_eventsub_subscription(&sub.twitch_subscription_id)
+                    .await
+                {
+                    warn!(
+                        "Failed to delete watched channel EventSub {} (twitch_id={}) on Twitch: {}",
+                        sub.subscription_type, sub.twitch_subscription_id, e
+                    );
+                    continue;
+                }
+            }
+
+            if let Err(e) = WatchedChannelSubscriptionRepository::delete(&state.db, &sub.id).await {
+                warn!(
+                    "Failed to remove watched channel subscription row {}: {}",
+                    sub.id, e
+                );
+            } else {
+                info!(
+                    "Removed watched channel EventSub {} for broadcaster {}",
+                    sub.subscription_type, sub.broadcaster_id
+                );
+            }
+        }
+
+        // 2) Create missing subscriptions for watched broadcasters
+        let now = Utc::now().naive_utc();
+        let verification_timeout = chrono::Duration::minutes(10);
+        let secret = &state.config.jwt.secret;
+        let mut listing: Option<Vec<TwitchEventSub>> = None;
+
+        for broadcaster_id in &watched {
+            if UserRepository::find_by_twitch_id(&state.db, broadcaster_id)
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            for sub_type in WATCHED_SUBSCRIPTION_TYPES {
+                if let Some(current) = existing.iter().find(|s| {
+                    &s.broadcaster_id == broadcaster_id && s.subscription_type == sub_type
+                }) {
+                    let pending_fresh = current.status == "webhook_callback_verification_pending"
+                        && now.signed_duration_since(current.created_at) <= verification_timeout;
+                    if current.status == "enabled" || pending_fresh {
+                        continue;
+                    }
+
+                    warn!(
+                        "Watched channel subscription {} (type={}) has status '{}'. Deleting and recreating.",
+                        current.twitch_subscription_id, current.subscription_type, current.status
+                    );
+                    if let Err(e) = state
+                        .twitch
+                        .delete_eventsub_subscription(&current.twitch_subscription_id)
+                        .await
+                    {
+                        warn!(
+                            "Failed to delete watched channel subscription {} on Twitch: {}",
+                            current.twitch_subscription_id, e
+                        );
+                    }
+                    WatchedChannelSubscriptionRepository::delete(&state.db, &current.id).await?;
+                }
+
+                let subscribe_result = match sub_type {
+                    "stream.online" => {
+                        state
+                            .twitch
+                            .subscribe_stream_online(broadcaster_id, secret)
+                            .await
+                    }
+                    "stream.offline" => {
+                        state
+                            .twitch
+                            .subscribe_stream_offline(broadcaster_id, secret)
+                            .await
+                    }
+                    _ => {
+                        state
+                            .twitch
+                            .subscribe_channel_update(broadcaster_id, secret)
+                            .await
+                    }
+                };
+
+                let twitch_sub = match subscribe_result {
+                    Ok(twitch_sub) => Some(twitch_sub),
+                    Err(e) => {
+                        // Fallback: the subscription may already exist on Twitch
+                        warn!(
+                            "Failed to create watched channel EventSub {} for broadcaster {}: {}. Attempting to discover existing subscription.",
+                            sub_type, broadcaster_id, e
+                        );
+                        if listing.is_none() {
+                            match state.twitch.list_eventsub_subscriptions().await {
+                                Ok(subs) => listing = Some(subs),
+                                Err(list_err) => warn!(
+                                    "Failed to list EventSub subscriptions for watched channels: {}",
+                                    list_err
+                                ),
+                            }
+                        }
+                        listing.as_ref().and_then(|subs| {
+                            subs.iter()
+                                .find(|s| {
+                                    s.subscription_type == sub_type
+                                        && condition_matches_broadcaster(
+                                            &s.condition,
+                                            broadcaster_id,
+                                        )
+                                })
+                                .cloned()
+                        })
+                    }
+                };
+
+                if let Some(twitch_sub) = twitch_sub {
+                    match WatchedChannelSubscriptionRepository::create(
+                        &state.db,
+                        broadcaster_id,
+                        &twitch_sub.id,
+                        &twitch_sub.subscription_type,
+                        &twitch_sub.status,
+                    )
+                    .await
+                    {
+                        Ok(_) => info!(
+                            "Recorded watched channel EventSub {} for broadcaster {} (twitch id={})",
+                            twitch_sub.subscription_type, broadcaster_id, twitch_sub.id
+                        ),
+                        Err(e) => warn!(
+                            "Failed to persist watched channel EventSub {}: {}",
+                            twitch_sub.id, e
+                        ),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Check if the EventSub condition object targets the given broadcaster id
@@ -1,15 +1,21 @@
 use serde::Serialize;
+use sqlx::SqlitePool;
 use teloxide::prelude::*;
-use teloxide::types::{ChatId, MessageId, ParseMode};
+use teloxide::types::{
+    ChatAction, ChatId, ChatKind, ChatPublic, MessageId, ParseMode, PublicChatKind,
+};
+use teloxide::RequestError;
 
-use crate::db::NotificationSettings;
-use crate::error::{AppError, AppResult};
+use crate::db::{NotificationSettings, TelegramIntegrationRepository};
+use crate::error::{AppError, AppResult, DeliveryErrorKind, TelegramError};
 use crate::services::notifications::{IntegrationContext, NotificationContent, Notifier};
 use async_trait::async_trait;
 
 #[derive(Clone)]
 pub struct TelegramService {
     bot: Bot,
+    /// Used to persist chat id changes (group -> supergroup migrations)
+    pool: SqlitePool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -19,6 +25,8 @@ pub struct TelegramMessage {
     pub parse_mode: Option<String>,
     pub disable_web_page_preview: bool,
     pub disable_notification: bool,
+    /// Forum topic to post into
+    pub message_thread_id: Option<i32>,
 }
 
 impl Default for TelegramMessage {
@@ -29,25 +37,26 @@ impl Default for TelegramMessage {
             parse_mode: Some("HTML".to_string()),
             disable_web_page_preview: false,
             disable_notification: false,
+            message_thread_id: None,
         }
     }
 }
 
 impl TelegramService {
-    pub async fn new(token: String) -> AppResult<Self> {
+    pub async fn new(token: String, pool: SqlitePool) -> AppResult<Self> {
         let bot = Bot::new(token);
 
         // Verify the bot token by getting bot info
         match bot.get_me().await {
             Ok(me) => {
                 tracing::info!("Telegram bot initialized: @{}", me.username());
-                Ok(Self { bot })
+                Ok(Self { bot, pool })
             }
             Err(e) => {
                 tracing::error!("Failed to initialize Telegram bot: {}", e);
-                Err(AppError::Telegram(format!(
-                    "Failed to initialize bot: {}",
-                    e
+                Err(AppError::Telegram(TelegramError::request(
+                    "Failed to initialize bot",
+                    &e,
                 )))
             }
         }
@@ -57,33 +66,80 @@ impl TelegramService {
         let chat_id: i64 = message
             .chat_id
             .parse()
-            .map_err(|_| AppError::Telegram("Invalid chat_id".to_string()))?;
+            .map_err(|_| AppError::telegram("Invalid chat_id"))?;
+
+        let result = match self.send_to_chat(chat_id, &message).await {
+            // The group was upgraded to a supergroup: move integrations to the new id and resend
+            Err(RequestError::MigrateToChatId(new_chat_id)) => {
+                tracing::info!(
+                    "Telegram chat {} migrated to supergroup {}",
+                    chat_id,
+                    new_chat_id
+                );
+                self.migrate_chat(&message.chat_id, new_chat_id).await;
+                self.send_to_chat(new_chat_id, &message).await
+            }
+            other => other,
+        };
+
+        result.map_err(|e| {
+            tracing::error!("Failed to send Telegram message: {}", e);
+            AppError::Telegram(TelegramError::request("Failed to send message", &e))
+        })
+    }
 
+    async fn send_to_chat(
+        &self,
+        chat_id: i64,
+        message: &TelegramMessage,
+    ) -> Result<i32, RequestError> {
         let mut request = self
             .bot
             .send_message(ChatId(chat_id), &message.text)
             .disable_web_page_preview(message.disable_web_page_preview)
             .disable_notification(message.disable_notification);
 
+        if let Some(thread_id) = message.message_thread_id {
+            request = request.message_thread_id(thread_id);
+        }
+
         if message.parse_mode.as_deref() == Some("HTML") {
             request = request.parse_mode(ParseMode::Html);
         } else if message.parse_mode.as_deref() == Some("Markdown") {
             request = request.parse_mode(ParseMode::MarkdownV2);
         }
 
-        match request.await {
-            Ok(sent_message) => {
-                tracing::debug!(
-                    "Telegram message sent to {}: message_id={}",
-                    message.chat_id,
-                    sent_message.id
-                );
-                Ok(sent_message.id.0)
-            }
-            Err(e) => {
-                tracing::error!("Failed to send Telegram message: {}", e);
-                Err(AppError::Telegram(format!("Failed to send message: {}", e)))
-            }
+        let sent_message = track_request(request.await)?;
+        tracing::debug!(
+            "Telegram message sent to {}: message_id={}",
+            chat_id,
+            sent_message.id
+        );
+        Ok(sent_message.id.0)
+    }
+
+    /// Persist a group -> supergroup migration so later sends use the new chat id.
+    /// Errors are logged; the caller still retries the send against the new id.
+    async fn migrate_chat(&self, old_chat_id: &str, new_chat_id: i64) {
+        match TelegramIntegrationRepository::migrate_chat(
+            &self.pool,
+            old_chat_id,
+            &new_chat_id.to_string(),
+        )
+        .await
+        {
+            Ok(updated) => tracing::info!(
+                "Migrated {} Telegram integration(s) from chat {} to {}",
+                updated,
+                old_chat_id,
+                new_chat_id
+            ),
+            Err(e) => tracing::error!(
+                "Failed to migrate Telegram integrations from chat {} to {}: {:?}",
+                old_chat_id,
+                new_chat_id,
+                e
+            ),
         }
     }
 
@@ -96,12 +152,12 @@ impl TelegramService {
     pub async fn delete_message(&self, chat_id: &str, message_id: i32) -> AppResult<()> {
         let chat_id: i64 = chat_id
             .parse()
-            .map_err(|_| AppError::Telegram("Invalid chat_id".to_string()))?;
-        match self
-            .bot
-            .delete_message(ChatId(chat_id), MessageId(message_id))
-            .await
-        {
+            .map_err(|_| AppError::telegram("Invalid chat_id"))?;
+        match track_request(
+            self.bot
+                .delete_message(ChatId(chat_id), MessageId(message_id))
+                .await,
+        ) {
             Ok(_) => {
                 tracing::debug!(
                     "Deleted Telegram message chat_id={} message_id={}",
@@ -117,9 +173,9 @@ impl TelegramService {
                     message_id,
                     e
                 );
-                Err(AppError::Telegram(format!(
-                    "Failed to delete message: {}",
-                    e
+                Err(AppError::Telegram(TelegramError::request(
+                    "Failed to delete message",
+                    &e,
                 )))
             }
         }
@@ -131,17 +187,17 @@ impl TelegramService {
     pub async fn is_user_admin(&self, chat_id: &str, user_id: &str) -> AppResult<bool> {
         let chat_id: i64 = chat_id
             .parse()
-            .map_err(|_| AppError::Telegram("Invalid chat_id".to_string()))?;
+            .map_err(|_| AppError::telegram("Invalid chat_id"))?;
         // Telegram user IDs are represented as unsigned in teloxide types; parse as u64 to match
         let user_id: u64 = user_id
             .parse()
-            .map_err(|_| AppError::Telegram("Invalid user_id".to_string()))?;
+            .map_err(|_| AppError::telegram("Invalid user_id"))?;
 
         // Attempt to fetch the list of chat administrators using the bot.
         // Note: this requires the bot to be a member of the chat; if it's not,
         // the call will fail and we return an error so the caller can surface
         // an actionable message (e.g. ask to add the bot to the chat).
-        match self.bot.get_chat_administrators(ChatId(chat_id)).await {
+        match track_request(self.bot.get_chat_administrators(ChatId(chat_id)).await) {
             Ok(admins) => {
                 for admin in admins.into_iter() {
                     // admin.user.id is a UserId wrapper; compare by .0
@@ -153,13 +209,107 @@ impl TelegramService {
             }
             Err(e) => {
                 tracing::warn!("Failed to fetch chat administrators for {}: {}", chat_id, e);
-                Err(AppError::Telegram(format!(
-                    "Failed to fetch chat administrators: {}",
-                    e
+                Err(AppError::Telegram(TelegramError::request(
+                    "Failed to fetch chat administrators",
+                    &e,
                 )))
             }
         }
     }
+
+    /// Check that the bot can still post to a chat. Channels require the bot to be an
+    /// administrator with the "Post messages" right; groups and supergroups require it
+    /// to be an administrator or owner. Returns Ok(false) when the rights are missing, or
+    /// an error when the chat cannot be reached at all (deleted, bot removed or blocked).
+    pub async fn can_post_to_chat(&self, chat_id: &str) -> AppResult<bool> {
+        let chat_id: i64 = chat_id
+            .parse()
+            .map_err(|_| AppError::telegram("Invalid chat_id"))?;
+
+        let chat = track_request(self.bot.get_chat(ChatId(chat_id)).await)
+            .map_err(|e| AppError::Telegram(TelegramError::request("Failed to fetch chat", &e)))?;
+        if chat.is_private() {
+            // Reachable private chats never restrict the bot
+            return Ok(true);
+        }
+
+        let me = track_request(self.bot.get_me().await).map_err(|e| {
+            AppError::Telegram(TelegramError::request("Failed to fetch bot info", &e))
+        })?;
+        let member = track_request(self.bot.get_chat_member(ChatId(chat_id), me.id).await)
+            .map_err(|e| {
+                AppError::Telegram(TelegramError::request("Failed to fetch bot membership", &e))
+            })?;
+
+        if !member.kind.is_present() {
+            return Err(AppError::Telegram(TelegramError {
+                kind: DeliveryErrorKind::TargetGone,
+                message: "Bot is no longer a member of the chat".to_string(),
+            }));
+        }
+
+        if chat.is_channel() {
+            return Ok(member.kind.can_post_messages());
+        }
+
+        Ok(member.kind.is_privileged())
+    }
+
+    /// Check that `message_thread_id` is an existing topic of a forum supergroup.
+    /// The Bot API has no "get topic" method, so a chat action is sent to the topic;
+    /// Telegram rejects it for unknown topics.
+    pub async fn validate_forum_topic(
+        &self,
+        chat_id: &str,
+        message_thread_id: i32,
+    ) -> AppResult<()> {
+        let chat_id: i64 = chat_id
+            .parse()
+            .map_err(|_| AppError::telegram("Invalid chat_id"))?;
+
+        let chat = track_request(self.bot.get_chat(ChatId(chat_id)).await)
+            .map_err(|e| AppError::Telegram(TelegramError::request("Failed to fetch chat", &e)))?;
+
+        let is_forum = matches!(
+            &chat.kind,
+            ChatKind::Public(ChatPublic {
+                kind: PublicChatKind::Supergroup(supergroup),
+                ..
+            }) if supergroup.is_forum
+        );
+        if !is_forum {
+            return Err(AppError::Validation(crate::i18n::t(
+                "validation.telegram_chat_not_forum",
+            )));
+        }
+
+        track_request(
+            self.bot
+                .send_chat_action(ChatId(chat_id), ChatAction::Typing)
+                .message_thread_id(message_thread_id)
+                .await,
+        )
+        .map_err(|e| {
+            tracing::debug!(
+                "Forum topic {} not usable in chat {}: {}",
+                message_thread_id,
+                chat_id,
+                e
+            );
+            AppError::Validation(crate::i18n::t("validation.telegram_topic_not_found"))
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Count a Bot API request in the metrics; `RetryAfter` is Telegram's 429
+fn track_request<T>(result: Result<T, RequestError>) -> Result<T, RequestError> {
+    crate::metrics::record_api_request(
+        "telegram",
+        matches!(result, Err(RequestError::RetryAfter(_))),
+    );
+    result
 }
 
 // Notifier implementation moved to bottom of file.
@@ -209,7 +359,7 @@ pub fn verify_telegram_login_payload(
     // HMAC-SHA256
     type HmacSha256 = Hmac<Sha256>;
     let mut mac = HmacSha256::new_from_slice(&secret_key)
-        .map_err(|e| AppError::Telegram(format!("Failed to initialize HMAC: {}", e)))?;
+        .map_err(|e| AppError::telegram(format!("Failed to initialize HMAC: {}", e)))?;
     mac.update(data_check_string.as_bytes());
     let computed = mac.finalize().into_bytes();
     let computed_hex = hex::encode(computed);
@@ -273,6 +423,7 @@ impl Notifier for TelegramService {
             .send_message(TelegramMessage {
                 chat_id: ctx.destination_id.clone(),
                 text: message,
+                message_thread_id: ctx.message_thread_id,
                 ..Default::default()
             })
             .await?;
@@ -0,0 +1,448 @@
+//! Telegram bot command interface.
+//!
+//! Runs a teloxide dispatcher (long polling) next to the HTTP server so chats can
+//! interact with the bot directly:
+//! - `/status`   — live status of the channels linked to this chat
+//! - `/schedule` — upcoming segments from the Twitch schedule
+//! - `/link`     — link this chat using a one-time code generated in the web UI
+//! - `/mute`, `/unmute` — pause/resume notifications in this chat (admins only)
+//!
+//! In channels the commands are sent as channel posts. Only channel administrators can
+//! post there, so a post counts as coming from an admin.
+//!
+//! It also tracks the bot's own membership (`my_chat_member` updates): chats the bot is
+//! added to are recorded for the UI chat picker, and integrations are disabled when the
+//! bot is removed from their chat.
+
+use std::sync::Arc;
+
+use teloxide::prelude::*;
+use teloxide::types::{Chat, ChatMemberStatus, MessageKind};
+use teloxide::utils::command::BotCommands;
+
+use crate::db::{
+    ChatType, TelegramIntegration, TelegramIntegrationRepository, TelegramLinkCodeRepository,
+    TelegramPendingChatRepository, UpdateTelegramIntegration, UpsertTelegramPendingChat, User,
+    UserRepository, DISABLED_REASON_BOT_REMOVED,
+};
+use crate::error::{AppError, AppResult};
+use crate::i18n;
+use crate::services::audit::{
+    AuditService, AUDIT_ACTION_UPDATE, AUDIT_ACTOR_TELEGRAM_BOT, AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+};
+use crate::services::chat_commands;
+use crate::services::integrations::IntegrationService;
+use crate::AppState;
+
+#[derive(BotCommands, Clone, Debug)]
+#[command(rename_rule = "lowercase", description = "Available commands:")]
+pub enum Command {
+    #[command(description = "show this help")]
+    Help,
+    #[command(description = "stream status of the linked channels")]
+    Status,
+    #[command(description = "upcoming scheduled streams")]
+    Schedule,
+    #[command(description = "link this chat: /link <code from the web UI>")]
+    Link(String),
+    #[command(description = "pause notifications in this chat")]
+    Mute,
+    #[command(description = "resume notifications in this chat")]
+    Unmute,
+}
+
+/// Run the update dispatcher until a shutdown signal is received.
+pub async fn run(state: Arc<AppState>, mut shutdown_rx: tokio::sync::broadcast::Receiver<()>) {
+    let bot = match state.telegram.read().await.as_ref() {
+        Some(telegram) => telegram.get_bot().clone(),
+        None => return,
+    };
+
+    if let Err(e) = bot.set_my_commands(Command::bot_commands()).await {
+        tracing::warn!("Failed to register Telegram bot commands: {}", e);
+    }
+
+    let handler = dptree::entry()
+        .branch(
+            Update::filter_message()
+                .filter_command::<Command>()
+                .endpoint(handle_command),
+        )
+        .branch(
+            Update::filter_channel_post()
+                .filter_command::<Command>()
+                .endpoint(handle_command),
+        )
+        .branch(Update::filter_my_chat_member().endpoint(handle_my_chat_member));
+
+    let mut dispatcher = Dispatcher::builder(bot, handler)
+        .dependencies(dptree::deps![state])
+        .default_handler(|_| async {})
+        .error_handler(LoggingErrorHandler::with_custom_text(
+            "Telegram bot command handler failed",
+        ))
+        .build();
+
+    let shutdown_token = dispatcher.shutdown_token();
+    tokio::spawn(async move {
+        let _ = shutdown_rx.recv().await;
+        match shutdown_token.shutdown() {
+            Ok(stopped) => stopped.await,
+            Err(e) => tracing::debug!("Telegram dispatcher was not running: {}", e),
+        }
+    });
+
+    tracing::info!("Telegram bot dispatcher started");
+    dispatcher.dispatch().await;
+    tracing::info!("Telegram bot dispatcher stopped");
+}
+
+async fn handle_my_chat_member(
+    update: ChatMemberUpdated,
+    state: Arc<AppState>,
+) -> ResponseResult<()> {
+    if let Err(e) = on_my_chat_member(&state, &update).await {
+        tracing::warn!(
+            "Failed to process my_chat_member update for chat {}: {:?}",
+            update.chat.id,
+            e
+        );
+    }
+    Ok(())
+}
+
+async fn handle_command(
+    bot: Bot,
+    msg: Message,
+    cmd: Command,
+    state: Arc<AppState>,
+) -> ResponseResult<()> {
+    let lang = reply_lang(&msg);
+    let lang = lang.as_deref();
+
+    let result = match cmd {
+        Command::Help => Ok(Command::descriptions().to_string()),
+        Command::Status => status(&state, &msg, lang).await,
+        Command::Schedule => schedule(&state, &msg, lang).await,
+        Command::Link(code) => link(&state, &msg, code.trim(), lang).await,
+        Command::Mute => set_enabled(&state, &msg, false, lang).await,
+        Command::Unmute => set_enabled(&state, &msg, true, lang).await,
+    };
+
+    let reply = result.unwrap_or_else(|e| {
+        tracing::warn!(
+            "Telegram bot command failed in chat {}: {:?}",
+            msg.chat.id,
+            e
+        );
+        i18n::tr(lang, "chat_commands.error", None)
+    });
+
+    bot.send_message(msg.chat.id, reply)
+        .reply_to_message_id(msg.id)
+        .disable_web_page_preview(true)
+        .await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// `/status`: report whether each linked channel is live
+async fn status(state: &Arc<AppState>, msg: &Message, lang: Option<&str>) -> AppResult<String> {
+    let linked = linked_owners(state, msg).await?;
+    if linked.is_empty() {
+        return Ok(i18n::tr(lang, "telegram_bot.not_linked", None));
+    }
+
+    let app_token = state.twitch.get_valid_app_access_token().await?;
+    let mut lines = Vec::with_capacity(linked.len());
+
+    for (integration, owner) in linked {
+        let mut line = chat_commands::status_line(state, &app_token, &owner, lang).await?;
+        if !integration.is_enabled {
+            line.push(' ');
+            line.push_str(&i18n::tr(lang, "telegram_bot.muted_suffix", None));
+        }
+        lines.push(line);
+    }
+
+    Ok(lines.join("\n\n"))
+}
+
+/// `/schedule`: list upcoming (non-canceled) segments of each linked channel
+async fn schedule(state: &Arc<AppState>, msg: &Message, lang: Option<&str>) -> AppResult<String> {
+    let linked = linked_owners(state, msg).await?;
+    if linked.is_empty() {
+        return Ok(i18n::tr(lang, "telegram_bot.not_linked", None));
+    }
+
+    let app_token = state.twitch.get_valid_app_access_token().await?;
+    let mut blocks = Vec::with_capacity(linked.len());
+
+    for (_, owner) in linked {
+        blocks.push(chat_commands::schedule_block(state, &app_token, &owner, lang).await?);
+    }
+
+    Ok(blocks.join("\n\n"))
+}
+
+/// `/link <code>`: create an integration for the code's owner in this chat
+async fn link(
+    state: &Arc<AppState>,
+    msg: &Message,
+    code: &str,
+    lang: Option<&str>,
+) -> AppResult<String> {
+    if code.is_empty() {
+        return Ok(i18n::tr(lang, "telegram_bot.link_usage", None));
+    }
+
+    // Check admin rights before consuming so non-admins cannot burn someone's code
+    if !sender_is_admin(state, msg).await? {
+        return Ok(i18n::tr(lang, "telegram_bot.must_be_admin", None));
+    }
+
+    // The code is only used up once the chat is linked, so a failed attempt can be retried
+    let Some(link_code) = TelegramLinkCodeRepository::find_valid(&state.db, code).await? else {
+        return Ok(i18n::tr(lang, "telegram_bot.link_invalid_code", None));
+    };
+
+    let owner = UserRepository::find_by_id(&state.db, &link_code.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    let streamer = owner.twitch_display_name.as_str();
+
+    let chat_id = msg.chat.id.to_string();
+    let chat_type = chat_type_of(&msg.chat);
+    let chat_title = msg
+        .chat
+        .title()
+        .or_else(|| msg.chat.username())
+        .map(str::to_string);
+    // Sent from inside a forum topic: link that topic
+    let message_thread_id = match &msg.kind {
+        MessageKind::Common(common) if common.is_topic_message => msg.thread_id,
+        _ => None,
+    };
+
+    match IntegrationService::create_telegram_integration(
+        state,
+        &owner.id,
+        chat_id,
+        chat_title,
+        Some(chat_type),
+        message_thread_id,
+    )
+    .await
+    {
+        Ok(integration) => {
+            // Another /link used the code in the meantime
+            if TelegramLinkCodeRepository::consume(&state.db, code)
+                .await?
+                .is_none()
+            {
+                IntegrationService::delete_telegram_integration(state, &integration.id).await?;
+                return Ok(i18n::tr(lang, "telegram_bot.link_invalid_code", None));
+            }
+
+            tracing::info!(
+                "Telegram chat {} linked to user {} via /link (code created by {})",
+                integration.telegram_chat_id,
+                owner.id,
+                link_code.created_by
+            );
+            Ok(i18n::tr(
+                lang,
+                "telegram_bot.linked",
+                Some(&[("streamer", streamer)]),
+            ))
+        }
+        Err(AppError::Conflict(_)) => Ok(i18n::tr(
+            lang,
+            "telegram_bot.already_linked",
+            Some(&[("streamer", streamer)]),
+        )),
+        // Surface validation errors (e.g. owner has no linked Telegram) as-is
+        Err(AppError::Validation(message)) => Ok(message),
+        Err(e) => Err(e),
+    }
+}
+
+/// `/mute` and `/unmute`: toggle every integration linked to this chat
+async fn set_enabled(
+    state: &Arc<AppState>,
+    msg: &Message,
+    enabled: bool,
+    lang: Option<&str>,
+) -> AppResult<String> {
+    let integrations =
+        TelegramIntegrationRepository::find_by_chat_id(&state.db, &msg.chat.id.to_string()).await?;
+    if integrations.is_empty() {
+        return Ok(i18n::tr(lang, "telegram_bot.not_linked", None));
+    }
+
+    if !sender_is_admin(state, msg).await? {
+        return Ok(i18n::tr(lang, "telegram_bot.must_be_admin", None));
+    }
+
+    for integration in integrations.into_iter().filter(|i| i.is_enabled != enabled) {
+        let updated = IntegrationService::update_telegram_integration(
+            state,
+            &integration.id,
+            UpdateTelegramIntegration {
+                is_enabled: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        AuditService::record(
+            state,
+            AUDIT_ACTOR_TELEGRAM_BOT,
+            &integration.user_id,
+            AUDIT_ENTITY_TELEGRAM_INTEGRATION,
+            Some(&integration.id),
+            AUDIT_ACTION_UPDATE,
+            AuditService::diff(&integration, &updated),
+        )
+        .await;
+    }
+
+    let key = if enabled {
+        "telegram_bot.unmuted"
+    } else {
+        "telegram_bot.muted"
+    };
+    Ok(i18n::tr(lang, key, None))
+}
+
+// ============================================================================
+// Membership updates
+// ============================================================================
+
+/// Record chats the bot joins and disable integrations of chats it leaves
+async fn on_my_chat_member(state: &Arc<AppState>, update: &ChatMemberUpdated) -> AppResult<()> {
+    let chat_id = update.chat.id.to_string();
+    let member = &update.new_chat_member;
+
+    if member.is_present() {
+        // Private chats are linked through the Telegram login, not the picker
+        if update.chat.is_private() {
+            return Ok(());
+        }
+
+        TelegramPendingChatRepository::upsert(
+            &state.db,
+            UpsertTelegramPendingChat {
+                chat_id: chat_id.clone(),
+                chat_title: update.chat.title().map(str::to_string),
+                chat_type: chat_type_of(&update.chat),
+                added_by_telegram_user_id: Some(update.from.id.to_string()),
+                bot_status: status_str(member.status()).to_string(),
+            },
+        )
+        .await?;
+
+        tracing::info!(
+            "Telegram bot added to chat {} ({}) by {}",
+            chat_id,
+            update.chat.title().unwrap_or(""),
+            update.from.id
+        );
+        return Ok(());
+    }
+
+    // Left or kicked (or blocked, for private chats)
+    TelegramPendingChatRepository::delete(&state.db, &chat_id).await?;
+
+    let disabled = TelegramIntegrationRepository::disable_for_chat(
+        &state.db,
+        &chat_id,
+        DISABLED_REASON_BOT_REMOVED,
+    )
+    .await?;
+    if disabled > 0 {
+        tracing::warn!(
+            "Telegram bot removed from chat {}; disabled {} integration(s)",
+            chat_id,
+            disabled
+        );
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Integrations linked to the message's chat together with their owners
+async fn linked_owners(
+    state: &Arc<AppState>,
+    msg: &Message,
+) -> AppResult<Vec<(TelegramIntegration, User)>> {
+    let integrations =
+        TelegramIntegrationRepository::find_by_chat_id(&state.db, &msg.chat.id.to_string()).await?;
+
+    let mut linked = Vec::with_capacity(integrations.len());
+    for integration in integrations {
+        if let Some(owner) = UserRepository::find_by_id(&state.db, &integration.user_id).await? {
+            linked.push((integration, owner));
+        }
+    }
+
+    Ok(linked)
+}
+
+/// Whether the message sender may change this chat's integrations
+async fn sender_is_admin(state: &Arc<AppState>, msg: &Message) -> AppResult<bool> {
+    // Anonymous group admins and channel posts are sent on behalf of the chat itself
+    if msg.sender_chat().map(|c| c.id) == Some(msg.chat.id) {
+        return Ok(true);
+    }
+
+    let Some(from) = msg.from() else {
+        return Ok(false);
+    };
+
+    if msg.chat.is_private() {
+        return Ok(from.id.0 as i64 == msg.chat.id.0);
+    }
+
+    IntegrationService::check_telegram_admin(state, &msg.chat.id.to_string(), &from.id.to_string())
+        .await
+}
+
+fn chat_type_of(chat: &Chat) -> ChatType {
+    if chat.is_private() {
+        ChatType::Private
+    } else if chat.is_supergroup() {
+        ChatType::Supergroup
+    } else if chat.is_channel() {
+        ChatType::Channel
+    } else {
+        ChatType::Group
+    }
+}
+
+fn status_str(status: ChatMemberStatus) -> &'static str {
+    match status {
+        ChatMemberStatus::Owner => "owner",
+        ChatMemberStatus::Administrator => "administrator",
+        ChatMemberStatus::Member => "member",
+        ChatMemberStatus::Restricted => "restricted",
+        ChatMemberStatus::Left => "left",
+        ChatMemberStatus::Banned => "banned",
+    }
+}
+
+/// Reply language derived from the sender's Telegram client language
+fn reply_lang(msg: &Message) -> Option<String> {
+    msg.from()
+        .and_then(|u| u.language_code.as_deref())
+        .map(i18n::normalize_language)
+        .filter(|l| i18n::is_supported_language(l))
+}
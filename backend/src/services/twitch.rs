@@ -342,6 +342,14 @@ impl TwitchService {
 
     /// Refresh user access token
     pub async fn refresh_token(&self, refresh_token: &str) -> AppResult<TokenResponse> {
+        let result = self.request_refreshed_token(refresh_token).await;
+        if result.is_err() {
+            crate::metrics::record_token_refresh_failure("user");
+        }
+        result
+    }
+
+    async fn request_refreshed_token(&self, refresh_token: &str) -> AppResult<TokenResponse> {
         let response = self
             .send_with_backoff(|| {
                 self.client
@@ -375,6 +383,14 @@ impl TwitchService {
     /// its expiry) into the internal lock. The method takes `&self` and uses
     /// interior mutability so it can be called concurrently.
     pub async fn refresh_app_access_token(&self) -> AppResult<()> {
+        let result = self.request_app_access_token().await;
+        if result.is_err() {
+            crate::metrics::record_token_refresh_failure("app");
+        }
+        result
+    }
+
+    async fn request_app_access_token(&self) -> AppResult<()> {
         let response = self
             .send_with_backoff(|| {
                 self.client
@@ -449,6 +465,15 @@ impl TwitchService {
         }
     }
 
+    /// Expiry of the cached app access token, `None` before the first successful refresh
+    pub async fn app_access_token_expires_at(&self) -> Option<chrono::DateTime<Utc>> {
+        self.app_access_token
+            .read()
+            .await
+            .as_ref()
+            .map(|t| t.expires_at)
+    }
+
     /// Validate a token
     pub async fn validate_token(&self, access_token: &str) -> AppResult<bool> {
         let response = self
@@ -462,16 +487,13 @@ impl TwitchService {
         Ok(response.status().is_success())
     }
 
-    /// Revoke a token
-    pub async fn revoke_token(&self, access_token: &str) -> AppResult<()> {
+    /// Revoke an access or refresh token
+    pub async fn revoke_token(&self, token: &str) -> AppResult<()> {
         let response = self
             .send_with_backoff(|| {
                 self.client
                     .post(format!("{}/revoke", TWITCH_AUTH_URL))
-                    .form(&[
-                        ("client_id", self.client_id.as_str()),
-                        ("token", access_token),
-                    ])
+                    .form(&[("client_id", self.client_id.as_str()), ("token", token)])
             })
             .await?;
 
@@ -718,7 +740,13 @@ impl TwitchService {
         let max_backoff_secs: u64 = 60;
 
         for attempt in 0..MAX_RETRIES {
-            match (make_request)().send().await {
+            let result = (make_request)().send().await;
+            crate::metrics::record_api_request(
+                "twitch",
+                matches!(&result, Ok(resp) if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS),
+            );
+
+            match result {
                 Ok(resp) => {
                     // Retry on 429 (rate limit) or server errors (5xx)
                     if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
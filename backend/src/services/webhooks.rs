@@ -8,7 +8,10 @@ use serde::Deserialize;
 use sha2::Sha256;
 use tokio::sync::RwLock;
 
-use crate::db::{NotificationSettingsRepository, UserRepository};
+use crate::db::{
+    NotificationSettingsRepository, UserRepository, WatchedChannel, WatchedChannelRepository,
+    WatchedChannelSubscriptionRepository,
+};
 use crate::error::{AppError, AppResult};
 use crate::services::notifications::{
     CategoryChangeData, NotificationContent, NotificationService, RewardRedemptionData,
@@ -179,8 +182,31 @@ impl WebhookService {
                     updated.subscription_type
                 );
             }
+        } else if let Ok(Some(watched)) =
+            WatchedChannelSubscriptionRepository::find_by_twitch_subscription_id(
+                &state.db,
+                &payload.subscription.id,
+            )
+            .await
+        {
+            if WatchedChannelSubscriptionRepository::update_status(
+                &state.db,
+                &payload.subscription.id,
+                "enabled",
+            )
+            .await
+            .is_ok()
+            {
+                tracing::info!(
+                    "Updated watched channel subscription status to 'enabled' after verification: subscription_id={}, broadcaster={}",
+                    watched.twitch_subscription_id,
+                    watched.broadcaster_id
+                );
+            }
         }
 
+        crate::metrics::record_webhook(&payload.subscription.subscription_type, "verified");
+
         Ok(challenge)
     }
 
@@ -188,6 +214,23 @@ impl WebhookService {
     pub async fn handle_notification(
         state: &Arc<AppState>,
         payload: &EventSubPayload,
+    ) -> AppResult<()> {
+        let subscription_type = payload.subscription.subscription_type.as_str();
+        let result = Self::process_notification(state, payload).await;
+
+        let outcome = match &result {
+            Err(_) => "failed",
+            Ok(()) if Self::is_handled_type(subscription_type) => "processed",
+            Ok(()) => "ignored",
+        };
+        crate::metrics::record_webhook(subscription_type, outcome);
+
+        result
+    }
+
+    async fn process_notification(
+        state: &Arc<AppState>,
+        payload: &EventSubPayload,
     ) -> AppResult<()> {
         // Update subscription status
         if let Ok(Some(db_sub)) =
@@ -205,6 +248,21 @@ impl WebhookService {
                 )
                 .await;
             }
+        } else if let Ok(Some(watched)) =
+            WatchedChannelSubscriptionRepository::find_by_twitch_subscription_id(
+                &state.db,
+                &payload.subscription.id,
+            )
+            .await
+        {
+            if watched.status != "enabled" {
+                let _ = WatchedChannelSubscriptionRepository::update_status(
+                    &state.db,
+                    &payload.subscription.id,
+                    "enabled",
+                )
+                .await;
+            }
         }
 
         let event = payload
@@ -249,7 +307,7 @@ impl WebhookService {
     async fn check_stream_status(
         state: &Arc<AppState>,
         broadcaster_id: &str,
-        user: &crate::db::User,
+        user: Option<&crate::db::User>,
     ) -> AppResult<bool> {
         const CACHE_TTL_SECONDS: i64 = 60;
 
@@ -268,6 +326,46 @@ impl WebhookService {
         }
 
         // Cache is empty or expired, check via API
+        let stream_result = Self::fetch_stream(state, user, broadcaster_id).await;
+
+        // Update cache based on API result
+        let is_live = matches!(stream_result, Ok(Some(_)));
+        let mut cache = STREAM_STATE_CACHE.write().await;
+
+        if is_live {
+            // Stream is online, update cache
+            cache.insert(
+                broadcaster_id.to_string(),
+                StreamState {
+                    is_live: true,
+                    cached_at: Utc::now(),
+                },
+            );
+        } else {
+            // Stream is offline, remove from cache
+            cache.remove(broadcaster_id);
+        }
+
+        Ok(is_live)
+    }
+
+    /// Fetch the current stream for a broadcaster.
+    ///
+    /// Uses the owner's user token (refreshing it when needed) if the broadcaster is a
+    /// registered user, and the app access token for watched channels without an owner.
+    async fn fetch_stream(
+        state: &Arc<AppState>,
+        user: Option<&crate::db::User>,
+        broadcaster_id: &str,
+    ) -> AppResult<Option<crate::services::twitch::Stream>> {
+        let user = match user {
+            Some(u) => u,
+            None => {
+                let app_token = state.twitch.get_valid_app_access_token().await?;
+                return state.twitch.get_stream(&app_token, broadcaster_id).await;
+            }
+        };
+
         let mut access_token = user.twitch_access_token.clone();
         let mut refresh_token = user.twitch_refresh_token.clone();
 
@@ -298,25 +396,50 @@ impl WebhookService {
             }
         }
 
-        // Update cache based on API result
-        let is_live = stream_result.is_ok() && stream_result.as_ref().unwrap().is_some();
-        let mut cache = STREAM_STATE_CACHE.write().await;
+        stream_result
+    }
 
-        if is_live {
-            // Stream is online, update cache
-            cache.insert(
-                broadcaster_id.to_string(),
-                StreamState {
-                    is_live: true,
-                    cached_at: Utc::now(),
-                },
-            );
-        } else {
-            // Stream is offline, remove from cache
-            cache.remove(broadcaster_id);
+    /// Fan an event out to every user watching the broadcaster (follow mode).
+    /// Failures for one watcher are logged and do not affect the others.
+    async fn notify_watchers<'a>(
+        state: &Arc<AppState>,
+        watchers: &[WatchedChannel],
+        content: NotificationContent<'a>,
+    ) {
+        if watchers.is_empty() {
+            return;
         }
 
-        Ok(is_live)
+        let notification_service = NotificationService::new(state);
+        for watcher in watchers {
+            match notification_service
+                .send_watched_channel_notification(
+                    &watcher.user_id,
+                    &watcher.broadcaster_login,
+                    content,
+                )
+                .await
+            {
+                Ok(results) => {
+                    let successful = results.iter().filter(|r| r.success).count();
+                    tracing::info!(
+                        "Watched channel notification results for user {} (broadcaster={}): total={}, successful={}",
+                        watcher.user_id,
+                        watcher.broadcaster_id,
+                        results.len(),
+                        successful
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to notify watcher {} about broadcaster {}: {:?}",
+                        watcher.user_id,
+                        watcher.broadcaster_id,
+                        e
+                    );
+                }
+            }
+        }
     }
 
     async fn handle_stream_online(
@@ -335,23 +458,25 @@ impl WebhookService {
             );
         }
 
-        // Find user
-        let user =
-            match UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await? {
-                Some(u) => u,
-                None => {
-                    tracing::warn!(
-                        "No user found for broadcaster: {} (twitch_id={})",
-                        event.broadcaster_user_name,
-                        event.broadcaster_user_id
-                    );
-                    return Ok(());
-                }
-            };
+        // Find the owner (if the broadcaster is a registered user) and any watchers
+        let owner =
+            UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await?;
+        let watchers =
+            WatchedChannelRepository::find_by_broadcaster_id(&state.db, &event.broadcaster_user_id)
+                .await?;
+
+        if owner.is_none() && watchers.is_empty() {
+            tracing::warn!(
+                "No user found for broadcaster: {} (twitch_id={})",
+                event.broadcaster_user_name,
+                event.broadcaster_user_id
+            );
+            return Ok(());
+        }
 
         // Get stream info (with token refresh logic)
         let (title, category, thumbnail) =
-            Self::get_stream_info(state, &user, &event.broadcaster_user_id).await;
+            Self::get_stream_info(state, owner.as_ref(), &event.broadcaster_user_id).await;
 
         // Prime channel info cache so handle_channel_update can detect title/category changes
         {
@@ -362,32 +487,42 @@ impl WebhookService {
             );
         }
 
+        let streamer_avatar = match &owner {
+            Some(user) if !user.twitch_profile_image_url.is_empty() => {
+                Some(user.twitch_profile_image_url.clone())
+            }
+            Some(_) => None,
+            None => watchers
+                .iter()
+                .find_map(|w| w.broadcaster_profile_image_url.clone()),
+        };
+
         // Send notifications
-        let notification_service = NotificationService::new(state);
         let data = StreamOnlineData {
             streamer_name: event.broadcaster_user_name.clone(),
-            streamer_avatar: if user.twitch_profile_image_url.is_empty() {
-                None
-            } else {
-                Some(user.twitch_profile_image_url.clone())
-            },
+            streamer_avatar,
             title,
             category,
             thumbnail_url: thumbnail,
         };
 
-        let results = notification_service
-            .send_notification(&user.id, NotificationContent::StreamOnline(&data))
-            .await?;
+        if let Some(user) = &owner {
+            let notification_service = NotificationService::new(state);
+            let results = notification_service
+                .send_notification(&user.id, NotificationContent::StreamOnline(&data))
+                .await?;
 
-        let successful = results.iter().filter(|r| r.success).count();
-        tracing::info!(
-            "Stream online notification results for user {}: total={}, successful={}, failed={}",
-            user.id,
-            results.len(),
-            successful,
-            results.len() - successful
-        );
+            let successful = results.iter().filter(|r| r.success).count();
+            tracing::info!(
+                "Stream online notification results for user {}: total={}, successful={}, failed={}",
+                user.id,
+                results.len(),
+                successful,
+                results.len() - successful
+            );
+        }
+
+        Self::notify_watchers(state, &watchers, NotificationContent::StreamOnline(&data)).await;
 
         Ok(())
     }
@@ -406,26 +541,32 @@ impl WebhookService {
             channel_cache.remove(&event.broadcaster_user_id);
         }
 
-        let user =
-            match UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await? {
-                Some(u) => u,
-                None => {
-                    tracing::debug!(
-                        "No user found for broadcaster: {}",
-                        event.broadcaster_user_id
-                    );
-                    return Ok(());
-                }
-            };
+        let owner =
+            UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await?;
+        let watchers =
+            WatchedChannelRepository::find_by_broadcaster_id(&state.db, &event.broadcaster_user_id)
+                .await?;
+
+        if owner.is_none() && watchers.is_empty() {
+            tracing::debug!(
+                "No user found for broadcaster: {}",
+                event.broadcaster_user_id
+            );
+            return Ok(());
+        }
 
-        let notification_service = NotificationService::new(state);
         let data = StreamOfflineData {
             streamer_name: event.broadcaster_user_name,
         };
 
-        notification_service
-            .send_notification(&user.id, NotificationContent::StreamOffline(&data))
-            .await?;
+        if let Some(user) = &owner {
+            let notification_service = NotificationService::new(state);
+            notification_service
+                .send_notification(&user.id, NotificationContent::StreamOffline(&data))
+                .await?;
+        }
+
+        Self::notify_watchers(state, &watchers, NotificationContent::StreamOffline(&data)).await;
 
         Ok(())
     }
@@ -472,33 +613,40 @@ impl WebhookService {
             return Ok(());
         }
 
-        // Find user first to check stream status
-        let user =
-            match UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await? {
-                Some(u) => u,
-                None => {
-                    tracing::debug!(
-                        "No user found for broadcaster: {}",
-                        event.broadcaster_user_id
-                    );
-                    return Ok(());
-                }
-            };
+        // Find owner and watchers first to check stream status
+        let owner =
+            UserRepository::find_by_twitch_id(&state.db, &event.broadcaster_user_id).await?;
+        let watchers =
+            WatchedChannelRepository::find_by_broadcaster_id(&state.db, &event.broadcaster_user_id)
+                .await?;
+
+        if owner.is_none() && watchers.is_empty() {
+            tracing::debug!(
+                "No user found for broadcaster: {}",
+                event.broadcaster_user_id
+            );
+            return Ok(());
+        }
 
         // Check if stream is online using check_stream_status
-        let is_live =
-            match Self::check_stream_status(state, &event.broadcaster_user_id, &user).await {
-                Ok(live) => live,
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to check stream status for {}: {}",
-                        event.broadcaster_user_id,
-                        e
-                    );
-                    // If check fails, assume offline to be safe
-                    false
-                }
-            };
+        let is_live = match Self::check_stream_status(
+            state,
+            &event.broadcaster_user_id,
+            owner.as_ref(),
+        )
+        .await
+        {
+            Ok(live) => live,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to check stream status for {}: {}",
+                    event.broadcaster_user_id,
+                    e
+                );
+                // If check fails, assume offline to be safe
+                false
+            }
+        };
 
         if !is_live {
             tracing::info!(
@@ -519,17 +667,24 @@ impl WebhookService {
                 new_title: event.title.clone(),
                 category_name: event.category_name.clone(),
             };
-            notification_service
-                .send_notification(&user.id, NotificationContent::TitleChange(&data))
-                .await?;
+            if let Some(user) = &owner {
+                notification_service
+                    .send_notification(&user.id, NotificationContent::TitleChange(&data))
+                    .await?;
+            }
+            Self::notify_watchers(state, &watchers, NotificationContent::TitleChange(&data)).await;
         } else if category_changed {
             let data = CategoryChangeData {
                 streamer_name: event.broadcaster_user_name,
                 new_category: event.category_name,
             };
-            notification_service
-                .send_notification(&user.id, NotificationContent::CategoryChange(&data))
-                .await?;
+            if let Some(user) = &owner {
+                notification_service
+                    .send_notification(&user.id, NotificationContent::CategoryChange(&data))
+                    .await?;
+            }
+            Self::notify_watchers(state, &watchers, NotificationContent::CategoryChange(&data))
+                .await;
         }
 
         Ok(())
@@ -561,7 +716,7 @@ impl WebhookService {
 
         // Check if stream is online using check_stream_status
         let is_live =
-            match Self::check_stream_status(state, &event.broadcaster_user_id, &user).await {
+            match Self::check_stream_status(state, &event.broadcaster_user_id, Some(&user)).await {
                 Ok(live) => live,
                 Err(e) => {
                     tracing::warn!(
@@ -619,40 +774,10 @@ impl WebhookService {
 
     async fn get_stream_info(
         state: &Arc<AppState>,
-        user: &crate::db::User,
+        user: Option<&crate::db::User>,
         broadcaster_id: &str,
     ) -> (String, String, Option<String>) {
-        let mut access_token = user.twitch_access_token.clone();
-        let mut refresh_token = user.twitch_refresh_token.clone();
-
-        // Check if token needs refresh
-        let token_expires_at =
-            chrono::DateTime::<Utc>::from_naive_utc_and_offset(user.twitch_token_expires_at, Utc);
-        if token_expires_at - Duration::seconds(60) <= Utc::now() {
-            if let Ok((new_access, new_refresh)) =
-                Self::refresh_token_helper(state, &user.id, &refresh_token).await
-            {
-                access_token = new_access;
-                refresh_token = new_refresh;
-            }
-        }
-
-        // Try to get stream info
-        let mut stream_result = state.twitch.get_stream(&access_token, broadcaster_id).await;
-
-        // Retry with refreshed token if unauthorized
-        if let Err(AppError::TwitchApi(ref msg)) = stream_result {
-            if msg.contains("401") || msg.contains("Unauthorized") {
-                if let Ok((new_access, _)) =
-                    Self::refresh_token_helper(state, &user.id, &refresh_token).await
-                {
-                    access_token = new_access;
-                    stream_result = state.twitch.get_stream(&access_token, broadcaster_id).await;
-                }
-            }
-        }
-
-        match stream_result {
+        match Self::fetch_stream(state, user, broadcaster_id).await {
             Ok(Some(s)) => (s.title, s.game_name, Some(s.thumbnail_url)),
             _ => ("Stream started!".to_string(), "Unknown".to_string(), None),
         }
@@ -663,6 +788,11 @@ impl WebhookService {
         user: &crate::db::User,
         message: &str,
     ) -> AppResult<()> {
+        if user.suspended_at.is_some() {
+            tracing::info!("User {} is suspended; skipping chat message", user.id);
+            return Ok(());
+        }
+
         let mut access_token = user.twitch_access_token.clone();
         let mut refresh_token = user.twitch_refresh_token.clone();
 
@@ -723,6 +853,16 @@ impl WebhookService {
         Ok((token_response.access_token, token_response.refresh_token))
     }
 
+    fn is_handled_type(subscription_type: &str) -> bool {
+        matches!(
+            subscription_type,
+            SUB_TYPE_STREAM_ONLINE
+                | SUB_TYPE_STREAM_OFFLINE
+                | SUB_TYPE_CHANNEL_UPDATE
+                | SUB_TYPE_CHANNEL_POINTS_REDEMPTION
+        )
+    }
+
     fn get_header(headers: &HeaderMap, name: &str) -> AppResult<String> {
         headers
             .get(name)